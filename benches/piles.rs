@@ -0,0 +1,100 @@
+//! Times the backings of a [`CardPile`] against each other, for stack, queue, and shoe use, and
+//! for the hands of whole games of War
+//!
+//! Run with `cargo bench --bench piles`. Each case is repeated over piles of one and eight decks
+
+use std::{
+    collections::VecDeque,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use naipe::common::{card::Card, pile::CardPile, shoe::ContinuousShuffler};
+use naipe::games::{war::War, Game, SeededGame};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// The number of times each case is run
+const ROUNDS: u32 = 200;
+
+/// Deals every card off the top, and stacks them back up
+fn stack<P: CardPile>(pile: &mut P) {
+    let cards: Vec<Card> = std::iter::from_fn(|| pile.pop_top()).collect();
+    for card in cards {
+        pile.push_top(black_box(card));
+    }
+}
+
+/// Plays every card off the top to the bottom, as a War hand winning its own cards would
+fn queue<P: CardPile>(pile: &mut P) {
+    for _ in 0..pile.len() {
+        if let Some(card) = pile.pop_top() {
+            pile.push_bottom(black_box(card));
+        }
+    }
+}
+
+/// Deals a tenth of the pile, and loads it back in with a continuous shuffling machine
+fn shoe<P: CardPile>(pile: &mut P, rng: &mut StdRng) {
+    let dealt: Vec<Card> = (0..pile.len() / 10)
+        .filter_map(|_| pile.pop_top())
+        .collect();
+    ContinuousShuffler::new(20).load(pile, dealt, rng);
+}
+
+/// The number of games of War played with each backing
+const WAR_GAMES: u64 = 200;
+
+/// Plays seeded games of War to the end, keeping each hand in a pile of type `P`
+fn war<P: CardPile + Clone + Default>() -> Duration {
+    let start = Instant::now();
+    for seed in 0..WAR_GAMES {
+        let mut game = War::<P>::from_seed(seed);
+        for _ in 0..100_000 {
+            if game.tick().unwrap() {
+                break;
+            }
+        }
+        black_box(&game);
+    }
+    start.elapsed() / u32::try_from(WAR_GAMES).unwrap()
+}
+
+/// Times a case over a fresh pile, giving the average time per round
+fn time<P: CardPile + Clone>(pile: &P, mut case: impl FnMut(&mut P)) -> Duration {
+    let mut pile = pile.clone();
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        case(&mut pile);
+    }
+    black_box(&pile);
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    for decks in [1, 8] {
+        let cards: Vec<Card> = (0..decks).flat_map(|_| Card::all_cards()).collect();
+        let deque = VecDeque::from(cards.clone());
+        let mut rng = StdRng::seed_from_u64(1293);
+        println!("{} cards", cards.len());
+        println!(
+            "  stack  Vec {:>10?}  VecDeque {:>10?}",
+            time(&cards, stack),
+            time(&deque, stack)
+        );
+        println!(
+            "  queue  Vec {:>10?}  VecDeque {:>10?}",
+            time(&cards, queue),
+            time(&deque, queue)
+        );
+        println!(
+            "  shoe   Vec {:>10?}  VecDeque {:>10?}",
+            time(&cards, |pile| shoe(pile, &mut rng)),
+            time(&deque, |pile| shoe(pile, &mut rng))
+        );
+    }
+    println!(
+        "War games  Vec {:>10?}  VecDeque {:>10?}",
+        war::<Vec<Card>>(),
+        war::<VecDeque<Card>>()
+    );
+}
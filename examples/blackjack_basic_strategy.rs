@@ -0,0 +1,42 @@
+//! Plays rounds of Blackjack with basic strategy and reports the return per unit wagered
+//!
+//! Run with `cargo run --example blackjack_basic_strategy -- [rounds]`
+
+use std::env;
+use std::num::{NonZeroU16, NonZeroUsize};
+
+use naipe::games::agent::AgentGame;
+use naipe::games::blackjack::{basic_strategy, BlackjackGame, BlackjackOutcome};
+
+const DEFAULT_ROUNDS: usize = 10_000;
+
+fn main() {
+    let rounds = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_ROUNDS);
+
+    let mut game = BlackjackGame::new(NonZeroU16::new(6).unwrap(), NonZeroUsize::new(1).unwrap());
+    // Wager two units so blackjack's 3:2 payout is never rounded down
+    game.set_wager(0, 2).unwrap();
+    let mut player = basic_strategy;
+    let mut wagered = 0;
+    let mut net = 0;
+    let mut wins = 0;
+    for _ in 0..rounds {
+        while !game.tick_with_agents(&mut [&mut player]).unwrap() {}
+        let result = game.results()[0];
+        wagered += i64::from(result.wager);
+        net += result.net();
+        if matches!(
+            result.outcome,
+            BlackjackOutcome::Win | BlackjackOutcome::Blackjack
+        ) {
+            wins += 1;
+        }
+        game.new_round();
+    }
+
+    println!("Played {rounds} rounds, winning {wins}");
+    println!("Net {net:+} units on {wagered} wagered");
+}
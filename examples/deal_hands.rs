@@ -0,0 +1,27 @@
+//! Shuffles a deck, deals it out to four hands and prints each hand
+//!
+//! Run with `cargo run --example deal_hands`
+
+use std::num::NonZeroU16;
+
+use naipe::common::card::Suitless;
+use naipe::common::deck::Deck;
+use naipe::common::hand::Hand;
+
+fn main() {
+    let mut deck = Deck::new(NonZeroU16::new(1).unwrap());
+    deck.shuffle_with_default_rng();
+
+    let mut hands = vec![Hand::new(); 4];
+    deck.deal_all_cards_to_hands(&mut hands).unwrap();
+    assert!(deck.is_empty());
+
+    for (seat, hand) in hands.iter().enumerate() {
+        let highest = hand.iter().copied().map(Suitless).max().unwrap();
+        println!(
+            "Hand {}: {hand} ({} cards, highest {highest})",
+            seat + 1,
+            hand.len()
+        );
+    }
+}
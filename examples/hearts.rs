@@ -0,0 +1,74 @@
+//! Plays a game of Hearts, with the first seat ducking tricks against three players who always
+//! play their lowest card, and reports the score after every deal
+//!
+//! Run with `cargo run --example hearts -- [seed]`
+
+use std::env;
+
+use naipe::common::card::Card;
+use naipe::games::agent::AgentGame;
+use naipe::games::event::EventSource;
+use naipe::games::hearts::{card_points, play_lowest, HeartsEvent, HeartsGame, HeartsView};
+use naipe::games::{GameOutcome, SeededGame};
+
+const DEFAULT_SEED: u64 = 1;
+
+/// Plays the highest card that loses the trick, and otherwise throws away the card worth the
+/// most points, falling back to the lowest card when leading
+fn duck(view: &HeartsView) -> Card {
+    let Some((_, led)) = view.trick.first() else {
+        return play_lowest(view);
+    };
+    let winning = view
+        .trick
+        .iter()
+        .filter(|(_, card)| card.get_suit() == led.get_suit())
+        .map(|(_, card)| card.get_rank())
+        .max();
+    let following = view
+        .legal_plays
+        .iter()
+        .any(|card| card.get_suit() == led.get_suit());
+    let choice = if following {
+        view.legal_plays
+            .iter()
+            .filter(|card| Some(card.get_rank()) < winning)
+            .max_by_key(|card| card.get_rank())
+    } else {
+        view.legal_plays
+            .iter()
+            .max_by_key(|card| (card_points(**card), card.get_rank()))
+    };
+    choice.copied().unwrap_or_else(|| play_lowest(view))
+}
+
+fn main() {
+    let seed = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_SEED);
+
+    let mut game = HeartsGame::from_seed(seed);
+    let (mut ducker, mut east, mut south, mut west) = (duck, play_lowest, play_lowest, play_lowest);
+    let mut deals = 0;
+    loop {
+        let finished = game
+            .tick_with_agents(&mut [&mut ducker, &mut east, &mut south, &mut west])
+            .unwrap();
+        for event in game.events() {
+            if let HeartsEvent::DealScored { scores } = event {
+                deals += 1;
+                println!("After deal {deals}: {scores:?}");
+            }
+        }
+        if finished {
+            break;
+        }
+    }
+
+    match game.winner() {
+        Some(0) => println!("The ducking player won"),
+        Some(winner) => println!("Player {} won", winner + 1),
+        None => println!("The game was tied"),
+    }
+}
@@ -0,0 +1,36 @@
+//! Plays a batch of War games to completion and reports how often each player won
+//!
+//! Run with `cargo run --example simulate_war -- [games]`
+
+use std::env;
+
+use naipe::games::simulation::Simulator;
+use naipe::games::war::WarGame;
+
+const DEFAULT_GAMES: u64 = 100;
+const MAX_TICKS: usize = 100_000;
+
+fn main() {
+    let games = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_GAMES);
+
+    let report = Simulator::new(games).max_ticks(MAX_TICKS).run::<WarGame>();
+    let wins = |seat| report.wins().get(seat).copied().unwrap_or_default();
+
+    println!("Played {games} games of War");
+    println!("Player 1 won {}, Player 2 won {}", wins(0), wins(1));
+    if !report.cut_off().is_empty() {
+        println!(
+            "{} games were stopped after {MAX_TICKS} ticks",
+            report.cut_off().len()
+        );
+    }
+    if let Some(average) = report.length_stats().mean() {
+        println!("Average game length: {average:.0} ticks");
+    }
+    if let Some(median) = report.length_quantile(0.5) {
+        println!("Median game length: {median} ticks");
+    }
+}
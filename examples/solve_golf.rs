@@ -0,0 +1,48 @@
+//! Deals a seeded game of Golf solitaire, solves it, and prints the winning line of play
+//!
+//! Run with `cargo run --example solve_golf -- [seed]`
+
+use std::env;
+
+use naipe::games::solitaire::golf::{GolfGame, GolfMove};
+use naipe::games::SeededGame;
+
+const DEFAULT_SEED: u64 = 3;
+const MAX_POSITIONS: u64 = 1_000_000;
+
+fn main() {
+    let seed = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_SEED);
+
+    let mut game = GolfGame::from_seed(seed);
+    println!("Golf deal {seed}");
+    for (column, cards) in game.columns().iter().enumerate() {
+        let cards: Vec<String> = cards.iter().map(ToString::to_string).collect();
+        println!("  column {}: {}", column + 1, cards.join(" "));
+    }
+    if let Some(card) = game.foundation_top() {
+        println!("  foundation: {card}");
+    }
+
+    let Some(solution) = game.solve(MAX_POSITIONS) else {
+        println!("No win found in {MAX_POSITIONS} positions");
+        return;
+    };
+    println!(
+        "Won in {} moves, after looking at {} positions",
+        solution.moves.len(),
+        solution.positions
+    );
+    for next in solution.moves {
+        game.play(next).expect("The solver only plays legal moves");
+        let card = game
+            .foundation_top()
+            .expect("Every move puts a card on the foundation");
+        match next {
+            GolfMove::Play(column) => println!("  play {card} from column {}", column + 1),
+            _ => println!("  draw {card}"),
+        }
+    }
+}
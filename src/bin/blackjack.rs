@@ -0,0 +1,185 @@
+//! Plays Blackjack against the dealer
+//!
+//! Run with `cargo run --features cli --bin blackjack -- [--players <count>] [--script <file>]
+//! [--save <file>] [--resume <file>]`. With more than one player, the players take turns at the
+//! same terminal. With a script, each line of the file is read as though it were typed.
+//!
+//! If input ends or Ctrl-C is pressed, each player's running total is saved to the save file,
+//! and play can continue from it with `--resume`.
+
+mod console;
+
+use std::cell::RefCell;
+use std::env;
+use std::num::{NonZeroU16, NonZeroUsize};
+use std::process;
+
+use naipe::games::agent::{Agent, AgentGame};
+use naipe::games::blackjack::{
+    BlackjackAction, BlackjackError, BlackjackGame, BlackjackView, HandValue,
+};
+
+use console::{flag_value, input_from_args, resume_from_args, Checkpoint, HotSeat, InputSource};
+
+const DECKS: u16 = 6;
+const WAGER: u32 = 10;
+
+fn format_cards(cards: &[naipe::common::card::Card]) -> String {
+    cards
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Describes each player's running total
+fn summary(totals: &[i64]) -> String {
+    match totals {
+        [total] => format!("Finished with a running total of {total:+}"),
+        _ => totals
+            .iter()
+            .enumerate()
+            .map(|(seat, total)| format!("Player {} finished with {total:+}", seat + 1))
+            .collect::<Vec<String>>()
+            .join("\n"),
+    }
+}
+
+/// Saves each player's running total, one per line
+fn save(totals: &[i64]) -> String {
+    totals
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Loads each player's running total from a save
+fn load(save: &str) -> Option<Vec<i64>> {
+    let totals = save
+        .lines()
+        .map(|line| line.trim().parse().ok())
+        .collect::<Option<Vec<i64>>>()?;
+    (!totals.is_empty()).then_some(totals)
+}
+
+/// Creates an agent asking the player in the seat for each decision at the terminal
+fn console_player<'a>(
+    seat: usize,
+    input: &'a RefCell<Box<dyn InputSource>>,
+    hot_seat: &'a RefCell<HotSeat>,
+    checkpoint: &'a Checkpoint,
+) -> impl FnMut(&BlackjackView) -> BlackjackAction + 'a {
+    move |view: &BlackjackView| {
+        hot_seat
+            .borrow_mut()
+            .take_turn(seat, input.borrow_mut().as_mut());
+        if hot_seat.borrow().is_shared() {
+            println!("Player {}", seat + 1);
+        }
+        loop {
+            if let Some(upcard) = view.dealer_upcard {
+                println!("Dealer shows {upcard}");
+            }
+            println!(
+                "Your hand: {} ({}{})",
+                format_cards(&view.hand),
+                if view.value.soft { "soft " } else { "" },
+                view.value.total
+            );
+            if view.can_double {
+                println!("[h]it, [s]tand, or [d]ouble?");
+            } else {
+                println!("[h]it or [s]tand?");
+            }
+            match input.borrow_mut().read_line().as_deref() {
+                Some("h" | "hit") => return BlackjackAction::Hit,
+                Some("d" | "double") if view.can_double => return BlackjackAction::Double,
+                Some("s" | "stand") => return BlackjackAction::Stand,
+                Some(_) => println!("Unrecognized action"),
+                None => checkpoint.exit(),
+            }
+        }
+    }
+}
+
+fn main() -> Result<(), BlackjackError> {
+    env_logger::init();
+
+    let args: Vec<String> = env::args().collect();
+    let mut totals = match resume_from_args(&args) {
+        Ok(Some(saved)) => load(&saved).unwrap_or_else(|| {
+            eprintln!("Could not understand the saved game");
+            process::exit(1);
+        }),
+        Ok(None) => {
+            let players = flag_value(&args, "--players")
+                .and_then(|players| players.parse().ok())
+                .unwrap_or(1)
+                .max(1);
+            vec![0; players]
+        }
+        Err(err) => {
+            eprintln!("Could not read the saved game: {err}");
+            process::exit(1);
+        }
+    };
+    let players = NonZeroUsize::new(totals.len()).unwrap_or(NonZeroUsize::MIN);
+
+    let checkpoint = Checkpoint::from_args(&args);
+    checkpoint.update(save(&totals), summary(&totals));
+    checkpoint.exit_on_interrupt();
+
+    let input = match input_from_args(&args) {
+        Ok(input) => RefCell::new(input),
+        Err(err) => {
+            eprintln!("Could not read the script: {err}");
+            process::exit(1);
+        }
+    };
+    let hot_seat = RefCell::new(HotSeat::new(players.get()));
+    let mut seats: Vec<_> = (0..players.get())
+        .map(|seat| console_player(seat, &input, &hot_seat, &checkpoint))
+        .collect();
+    let mut agents: Vec<&mut dyn Agent<BlackjackView, BlackjackAction>> = seats
+        .iter_mut()
+        .map(|seat| seat as &mut dyn Agent<BlackjackView, BlackjackAction>)
+        .collect();
+
+    let mut game = BlackjackGame::new(NonZeroU16::new(DECKS).unwrap(), players);
+    for seat in 0..players.get() {
+        game.set_wager(seat, WAGER)?;
+    }
+    loop {
+        while !game.tick_with_agents(&mut agents)? {}
+
+        let dealer = game.dealer_hand();
+        println!(
+            "Dealer has {} ({})",
+            format_cards(dealer),
+            HandValue::of(dealer).total
+        );
+        for (seat, result) in game.results().iter().enumerate() {
+            totals[seat] += result.net();
+            if players.get() > 1 {
+                print!("Player {}: ", seat + 1);
+            }
+            println!(
+                "{:?}! Net {:+}, running total {:+}",
+                result.outcome,
+                result.net(),
+                totals[seat]
+            );
+        }
+        checkpoint.update(save(&totals), summary(&totals));
+
+        println!("Play again? [y/n]");
+        match input.borrow_mut().read_line().as_deref() {
+            Some("y") => {}
+            Some(_) => break,
+            None => checkpoint.exit(),
+        }
+        game.new_round();
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+//! Terminal helpers shared by the interactive binaries
+
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// Somewhere the binaries read player input from, a line at a time
+pub trait InputSource {
+    /// Reads a line of input, trimmed and lowercased, or `None` once input has ended
+    fn read_line(&mut self) -> Option<String>;
+}
+
+/// Input typed at the terminal, or piped to standard input
+pub struct Terminal {
+    stdin: io::Stdin,
+}
+
+impl Terminal {
+    /// Creates a source reading from standard input
+    pub fn new() -> Terminal {
+        Terminal { stdin: io::stdin() }
+    }
+}
+
+impl InputSource for Terminal {
+    fn read_line(&mut self) -> Option<String> {
+        let mut input = String::new();
+        match self.stdin.read_line(&mut input) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(input.trim().to_lowercase()),
+        }
+    }
+}
+
+/// Input given up front, such as from a script file, echoed as it is read
+pub struct Scripted {
+    lines: VecDeque<String>,
+}
+
+impl Scripted {
+    /// Creates a source giving each line of the script in turn
+    pub fn new(script: &str) -> Scripted {
+        Scripted {
+            lines: script
+                .lines()
+                .map(|line| line.trim().to_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Creates a source giving each line of the file in turn
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Scripted> {
+        Ok(Scripted::new(&fs::read_to_string(path)?))
+    }
+}
+
+impl InputSource for Scripted {
+    fn read_line(&mut self) -> Option<String> {
+        let line = self.lines.pop_front()?;
+        println!("> {line}");
+        Some(line)
+    }
+}
+
+/// Gets the input source chosen on the command line, a script given with `--script <file>`,
+/// or the terminal by default
+pub fn input_from_args(args: &[String]) -> io::Result<Box<dyn InputSource>> {
+    Ok(match flag_value(args, "--script") {
+        Some(path) => Box::new(Scripted::from_file(path)?),
+        None => Box::new(Terminal::new()),
+    })
+}
+
+/// Gets the value following a flag in the command line arguments
+pub fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+        .cloned()
+}
+
+/// Reads the saved game given with `--resume <file>`, if any
+pub fn resume_from_args(args: &[String]) -> io::Result<Option<String>> {
+    flag_value(args, "--resume")
+        .map(fs::read_to_string)
+        .transpose()
+}
+
+/// The latest state of a game in progress, kept so that it can be saved and summarized if
+/// play stops early
+#[derive(Clone)]
+pub struct Checkpoint {
+    state: Arc<Mutex<CheckpointState>>,
+}
+
+struct CheckpointState {
+    path: Option<PathBuf>,
+    save: String,
+    summary: String,
+}
+
+impl Checkpoint {
+    /// Creates a checkpoint saving to the file given with `--save <file>`, or else to the file
+    /// resumed from with `--resume <file>`
+    pub fn from_args(args: &[String]) -> Checkpoint {
+        let path = flag_value(args, "--save")
+            .or_else(|| flag_value(args, "--resume"))
+            .map(PathBuf::from);
+        Checkpoint {
+            state: Arc::new(Mutex::new(CheckpointState {
+                path,
+                save: String::new(),
+                summary: String::new(),
+            })),
+        }
+    }
+
+    /// Records the latest state of the game, and a summary of it for the players
+    pub fn update(&self, save: String, summary: String) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.save = save;
+        state.summary = summary;
+    }
+
+    /// Saves the game, if there is a file to save to, and prints the summary
+    pub fn finish(&self) {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        println!();
+        println!("{}", state.summary);
+        match &state.path {
+            Some(path) => match fs::write(path, &state.save) {
+                Ok(()) => println!("Saved to {}, continue with --resume", path.display()),
+                Err(err) => eprintln!("Could not save to {}: {err}", path.display()),
+            },
+            None => println!("Run with --save <file> to keep unfinished games"),
+        }
+    }
+
+    /// Finishes, then ends the program
+    pub fn exit(&self) -> ! {
+        self.finish();
+        process::exit(0);
+    }
+
+    /// Finishes and ends the program when the player presses Ctrl-C
+    pub fn exit_on_interrupt(&self) {
+        let checkpoint = self.clone();
+        let handler = ctrlc::set_handler(move || {
+            checkpoint.finish();
+            process::exit(130);
+        });
+        if let Err(err) = handler {
+            eprintln!("Could not catch Ctrl-C: {err}");
+        }
+    }
+}
+
+/// Clears the terminal, leaving the cursor at the top
+pub fn clear_screen() {
+    print!("\x1B[2J\x1B[H");
+    // Nothing useful can be done if stdout is gone
+    let _ = io::stdout().flush();
+}
+
+/// Hands a single terminal between several players in turn
+///
+/// Whenever the turn passes to a different player, the screen is cleared and play waits for
+/// the next player to take over, so no player sees another's hidden cards
+pub struct HotSeat {
+    players: usize,
+    current: Option<usize>,
+}
+
+impl HotSeat {
+    /// Creates a hot seat shared by the given number of players
+    pub fn new(players: usize) -> HotSeat {
+        HotSeat {
+            players,
+            current: None,
+        }
+    }
+
+    /// Finds if more than one player shares the terminal
+    pub fn is_shared(&self) -> bool {
+        self.players > 1
+    }
+
+    /// Gets the terminal ready for the player in the seat (counting from zero) to take a turn
+    pub fn take_turn(&mut self, seat: usize, input: &mut dyn InputSource) {
+        if !self.is_shared() || self.current == Some(seat) {
+            return;
+        }
+        clear_screen();
+        println!("Pass to Player {}, then press enter", seat + 1);
+        input.read_line();
+        clear_screen();
+        self.current = Some(seat);
+    }
+}
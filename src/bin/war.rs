@@ -1,17 +1,138 @@
-use std::io;
+//! Plays War against the computer
+//!
+//! Run with `cargo run --features cli --bin war -- [--hot-seat] [--script <file>] [--save <file>]
+//! [--resume <file>]`. With `--hot-seat`, two players take turns at the same terminal instead.
+//! With a script, each line of the file is read as though it were typed.
+//!
+//! If input ends or Ctrl-C is pressed, both players' cards are saved to the save file, and the
+//! game can continue from it with `--resume`.
 
-use naipe::games::war::WarGame;
-use naipe::games::Game;
+mod console;
+
+use std::cell::RefCell;
+use std::env;
+use std::process;
+
+use naipe::common::hand::Hand;
+use naipe::games::agent::{Agent, AgentGame};
+use naipe::games::event::EventSource;
+use naipe::games::war::{WarEvent, WarGame, WarView};
+
+use console::{input_from_args, resume_from_args, Checkpoint, HotSeat};
+
+fn describe(event: &WarEvent) -> Option<String> {
+    match event {
+        WarEvent::CardPlayed {
+            player,
+            card,
+            face_up: true,
+        } => Some(format!("Player {} plays {card}", player + 1)),
+        WarEvent::WarStarted { rank } => Some(format!("War over {rank}s!")),
+        WarEvent::PileCaptured { player, cards } => {
+            Some(format!("Player {} takes {} cards", player + 1, cards.len()))
+        }
+        WarEvent::Reshuffle { player, cards } => Some(format!(
+            "Player {} shuffles {cards} captured cards into their hand",
+            player + 1
+        )),
+        _ => None,
+    }
+}
+
+/// Describes how many cards each player holds
+fn summary(game: &WarGame) -> String {
+    let count = |player| game.cards(player).map_or(0, |cards| cards.len());
+    format!(
+        "Player 1 holds {} cards, Player 2 holds {}",
+        count(0),
+        count(1)
+    )
+}
+
+/// Saves each player's cards, one player per line
+fn save(game: &WarGame) -> String {
+    (0..2)
+        .filter_map(|player| game.cards(player))
+        .map(|cards| cards.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Loads a game from each player's saved cards
+fn load(save: &str) -> Option<WarGame> {
+    let mut hands = save.lines().map(|line| Hand::from_notation(line).ok());
+    let (player_1, player_2) = (hands.next()??, hands.next()??);
+    WarGame::builder()
+        .hands(player_1, player_2)
+        .build_with_rng(&mut rand::thread_rng())
+        .ok()
+}
 
 fn main() -> Result<(), ()> {
     env_logger::init();
 
-    let mut input = String::new();
-    let stdin = io::stdin();
+    let args: Vec<String> = env::args().collect();
+    let hot_seat_mode = args.iter().any(|arg| arg == "--hot-seat");
+    let mut game_state = match resume_from_args(&args) {
+        Ok(Some(saved)) => load(&saved).unwrap_or_else(|| {
+            eprintln!("Could not understand the saved game");
+            process::exit(1);
+        }),
+        Ok(None) => WarGame::default(),
+        Err(err) => {
+            eprintln!("Could not read the saved game: {err}");
+            process::exit(1);
+        }
+    };
+
+    let checkpoint = Checkpoint::from_args(&args);
+    checkpoint.update(save(&game_state), summary(&game_state));
+    checkpoint.exit_on_interrupt();
+
+    let input = match input_from_args(&args) {
+        Ok(input) => RefCell::new(input),
+        Err(err) => {
+            eprintln!("Could not read the script: {err}");
+            process::exit(1);
+        }
+    };
+    let hot_seat = RefCell::new(HotSeat::new(if hot_seat_mode { 2 } else { 1 }));
+    let console_player = |seat: usize| {
+        let input = &input;
+        let hot_seat = &hot_seat;
+        let checkpoint = &checkpoint;
+        move |view: &WarView| {
+            hot_seat
+                .borrow_mut()
+                .take_turn(seat, input.borrow_mut().as_mut());
+            if hot_seat.borrow().is_shared() {
+                println!("Player {}", seat + 1);
+            }
+            println!(
+                "You have {} cards ({} captured), your opponent has {}. Press enter to flip.",
+                view.hand + view.captured,
+                view.captured,
+                view.opponent
+            );
+            if input.borrow_mut().read_line().is_none() {
+                checkpoint.exit();
+            }
+        }
+    };
+    let mut player = console_player(0);
+    let mut opponent = console_player(1);
+    let mut computer = |_: &WarView| ();
+    let second: &mut dyn Agent<WarView, ()> = if hot_seat_mode {
+        &mut opponent
+    } else {
+        &mut computer
+    };
 
-    let mut game_state = WarGame::default();
-    while !game_state.tick()? {
-        stdin.read_line(&mut input).unwrap();
+    while !game_state.tick_with_agents(&mut [&mut player, second])? {
+        for line in game_state.events().iter().filter_map(describe) {
+            println!("{line}");
+        }
+        checkpoint.update(save(&game_state), summary(&game_state));
     }
     if game_state.player_1_won() {
         println!("Player 1 Won!");
@@ -0,0 +1,122 @@
+//! Which optional parts of the crate were compiled in
+//!
+//! Applications built against different feature sets can check [`features`] at runtime instead
+//! of mirroring the crate's features in their own
+
+use std::fmt::{self, Display, Formatter};
+
+/// An optional subsystem of the crate, enabled by a cargo feature
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Feature {
+    Blackjack,
+    Hearts,
+    Memory,
+    Solitaire,
+    War,
+    /// Shuffling with random number generators, needed by every game
+    Rand,
+    /// The evaluation cache, and the games, built on std's hashed collections
+    Std,
+    /// Serialization of game state with serde
+    Serde,
+    /// Parallel simulations with rayon
+    Rayon,
+    /// Exact fractions in place of floating point rates
+    ExactMath,
+}
+
+impl Feature {
+    /// Gets the name of the cargo feature enabling this subsystem
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feature::Blackjack => "blackjack",
+            Feature::Hearts => "hearts",
+            Feature::Memory => "memory",
+            Feature::Solitaire => "solitaire",
+            Feature::War => "war",
+            Feature::Rand => "rand",
+            Feature::Std => "std",
+            Feature::Serde => "serde",
+            Feature::Rayon => "rayon",
+            Feature::ExactMath => "exact-math",
+        }
+    }
+
+    /// Finds if this feature adds a game
+    pub fn is_game(&self) -> bool {
+        matches!(
+            self,
+            Feature::Blackjack
+                | Feature::Hearts
+                | Feature::Memory
+                | Feature::Solitaire
+                | Feature::War
+        )
+    }
+}
+
+impl Display for Feature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The optional subsystems compiled into this build of the crate
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Features {
+    enabled: Vec<Feature>,
+}
+
+impl Features {
+    /// Finds if a subsystem was compiled in
+    pub fn contains(&self, feature: Feature) -> bool {
+        self.enabled.contains(&feature)
+    }
+
+    /// Gets every subsystem compiled in, games first
+    pub fn enabled(&self) -> &[Feature] {
+        &self.enabled
+    }
+
+    /// Gets the feature names of every game compiled in
+    /// # Examples
+    /// ```
+    /// let games = naipe::features().games();
+    /// assert_eq!(games.contains(&"war"), cfg!(feature = "war"));
+    /// ```
+    pub fn games(&self) -> Vec<&'static str> {
+        self.enabled
+            .iter()
+            .filter(|feature| feature.is_game())
+            .map(Feature::name)
+            .collect()
+    }
+}
+
+/// Finds which optional subsystems were compiled into the crate
+/// # Examples
+/// ```
+/// use naipe::build::Feature;
+/// let features = naipe::features();
+/// assert_eq!(features.contains(Feature::Blackjack), cfg!(feature = "blackjack"));
+/// assert_eq!(features.contains(Feature::ExactMath), cfg!(feature = "exact-math"));
+/// ```
+pub fn features() -> Features {
+    let enabled = [
+        (Feature::Blackjack, cfg!(feature = "blackjack")),
+        (Feature::Hearts, cfg!(feature = "hearts")),
+        (Feature::Memory, cfg!(feature = "memory")),
+        (Feature::Solitaire, cfg!(feature = "solitaire")),
+        (Feature::War, cfg!(feature = "war")),
+        (Feature::Rand, cfg!(feature = "rand")),
+        (Feature::Std, cfg!(feature = "std")),
+        (Feature::Serde, cfg!(feature = "serde")),
+        (Feature::Rayon, cfg!(feature = "rayon")),
+        (Feature::ExactMath, cfg!(feature = "exact-math")),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect();
+    Features { enabled }
+}
@@ -0,0 +1,147 @@
+//! Access to the current time, behind a [`Clock`] that can be swapped out
+//!
+//! Everything in the crate that depends on the time of day or on time passing reads it from a
+//! [`Clock`]. [`SystemClock`] reads the system's wall clock, and [`MockClock`] is set by hand, so
+//! timed behaviour can be tested exactly. Targets without a system clock, such as WebAssembly in
+//! a browser, can supply their own
+
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A source of the current time
+pub trait Clock {
+    /// Gets the time elapsed since 1970-01-01 00:00 UTC
+    fn now(&self) -> Duration;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+/// The system's wall clock
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    /// Gets the system time, or the epoch itself if the system clock is set before it
+    fn now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+/// A clock that only moves when told to, for tests
+///
+/// Clones share the same time, so a test can keep one to move the time seen by another
+/// # Examples
+/// ```
+/// # use naipe::clock::{Clock, MockClock};
+/// use std::time::Duration;
+/// let clock = MockClock::new(Duration::from_secs(60));
+/// let shared = clock.clone();
+/// clock.advance(Duration::from_secs(5));
+/// assert_eq!(shared.now(), Duration::from_secs(65));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MockClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl MockClock {
+    /// Creates a clock showing the given time since the epoch
+    pub fn new(now: Duration) -> MockClock {
+        MockClock {
+            now: Rc::new(Cell::new(now)),
+        }
+    }
+
+    /// Sets the time since the epoch
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+
+    /// Moves the time forward
+    pub fn advance(&self, elapsed: Duration) {
+        self.now.set(self.now.get().saturating_add(elapsed));
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// Limits the time a player has to make a decision
+/// # Examples
+/// ```
+/// # use naipe::clock::{MockClock, TurnTimer};
+/// use std::time::Duration;
+/// let clock = MockClock::default();
+/// let mut timer = TurnTimer::new(clock.clone(), Duration::from_secs(30));
+/// timer.start();
+/// clock.advance(Duration::from_secs(25));
+/// assert_eq!(timer.remaining(), Duration::from_secs(5));
+/// assert!(!timer.is_expired());
+/// clock.advance(Duration::from_secs(10));
+/// assert!(timer.is_expired());
+///
+/// // Each turn starts the timer over
+/// timer.start();
+/// assert_eq!(timer.remaining(), Duration::from_secs(30));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TurnTimer<C> {
+    clock: C,
+    limit: Duration,
+    started: Option<Duration>,
+}
+
+impl<C: Clock> TurnTimer<C> {
+    /// Creates a stopped timer allowing the given time for each turn
+    pub fn new(clock: C, limit: Duration) -> TurnTimer<C> {
+        TurnTimer {
+            clock,
+            limit,
+            started: None,
+        }
+    }
+
+    /// Gets the time allowed for each turn
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+
+    /// Starts timing a new turn
+    pub fn start(&mut self) {
+        self.started = Some(self.clock.now());
+    }
+
+    /// Stops the timer between turns
+    pub fn stop(&mut self) {
+        self.started = None;
+    }
+
+    /// Gets the time taken on the current turn so far, or nothing if the timer is stopped
+    pub fn elapsed(&self) -> Duration {
+        self.started.map_or(Duration::ZERO, |started| {
+            self.clock.now().saturating_sub(started)
+        })
+    }
+
+    /// Gets the time left on the current turn
+    pub fn remaining(&self) -> Duration {
+        self.limit.saturating_sub(self.elapsed())
+    }
+
+    /// Finds if the current turn has run out of time
+    pub fn is_expired(&self) -> bool {
+        self.started.is_some() && self.elapsed() >= self.limit
+    }
+}
@@ -3,10 +3,30 @@
 //! Includes Ranks, Suits, and Cards themselves
 //! Also includes wrappers that change collation properties
 
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use thiserror::Error;
 
 /// An enum representing the rank of a card
-#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Debug)]
+///
+/// Jokers rank above every other card, but are not part of a standard deck
+///
+/// Ranks compare in declaration order, from twos up through kings to aces and then jokers. This
+/// order is part of the crate's stable API, and is the same as [`RankOrder::ace_high`]
+///
+/// [`RankOrder::ace_high`]: super::order::RankOrder::ace_high
+/// # Examples
+/// ```
+/// # use naipe::common::card::Rank;
+/// assert!(Rank::Two < Rank::Three);
+/// assert!(Rank::King < Rank::Ace);
+/// assert!(Rank::Ace < Rank::Joker);
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rank {
     Two,
     Three,
@@ -21,10 +41,11 @@ pub enum Rank {
     Queen,
     King,
     Ace,
+    Joker,
 }
 
 impl Rank {
-    /// Get all the ranks in the enum in a vector
+    /// Get all the ranks of a standard deck in a vector, leaving out the joker
     /// # Examples
     /// ```
     /// # use naipe::common::card::Rank;
@@ -68,7 +89,7 @@ impl Rank {
     /// ```
     pub fn is_directly_after(&self, other_rank: Rank) -> bool {
         match self {
-            Rank::Ace => false,
+            Rank::Ace | Rank::Joker => false,
             Rank::Two => other_rank == Rank::Ace,
             Rank::Three => other_rank == Rank::Two,
             Rank::Four => other_rank == Rank::Three,
@@ -101,12 +122,64 @@ impl Display for Rank {
             Rank::Jack => write!(f, "J"),
             Rank::Queen => write!(f, "Q"),
             Rank::King => write!(f, "K"),
+            Rank::Joker => write!(f, "🃏"),
+        }
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    /// Parses a rank from its number or letter, ignoring case
+    ///
+    /// Tens may be written as `10` or `T`, and jokers as `JK`, `Joker`, or `🃏`
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Rank;
+    /// assert_eq!("A".parse(), Ok(Rank::Ace));
+    /// assert_eq!("10".parse(), Ok(Rank::Ten));
+    /// assert_eq!("t".parse(), Ok(Rank::Ten));
+    /// assert_eq!("jk".parse(), Ok(Rank::Joker));
+    /// assert!("11".parse::<Rank>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Rank, ParseCardError> {
+        match s.to_ascii_uppercase().as_str() {
+            "A" => Ok(Rank::Ace),
+            "2" => Ok(Rank::Two),
+            "3" => Ok(Rank::Three),
+            "4" => Ok(Rank::Four),
+            "5" => Ok(Rank::Five),
+            "6" => Ok(Rank::Six),
+            "7" => Ok(Rank::Seven),
+            "8" => Ok(Rank::Eight),
+            "9" => Ok(Rank::Nine),
+            "10" | "T" => Ok(Rank::Ten),
+            "J" => Ok(Rank::Jack),
+            "Q" => Ok(Rank::Queen),
+            "K" => Ok(Rank::King),
+            "JK" | "JOKER" | "🃏" => Ok(Rank::Joker),
+            _ => Err(ParseCardError::InvalidRank(s.to_string())),
         }
     }
 }
 
 /// An enum representing the suit of a card
-#[derive(Clone, Copy, Eq, PartialEq, Debug, PartialOrd, Ord)]
+///
+/// Suits compare in declaration order, spades lowest, then clubs, hearts, and diamonds. This
+/// order is part of the crate's stable API, and is the same as [`SuitOrder::standard`]. It is
+/// not the bridge order, which is [`SuitOrder::bridge`]
+///
+/// [`SuitOrder::standard`]: super::order::SuitOrder::standard
+/// [`SuitOrder::bridge`]: super::order::SuitOrder::bridge
+/// # Examples
+/// ```
+/// # use naipe::common::card::Suit;
+/// let mut suits = vec![Suit::Diamond, Suit::Heart, Suit::Club, Suit::Spade];
+/// suits.sort();
+/// assert_eq!(suits, Suit::all_suits());
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Debug, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Spade,
     Club,
@@ -125,6 +198,17 @@ impl Suit {
     pub fn all_suits() -> Vec<Suit> {
         vec![Suit::Spade, Suit::Club, Suit::Heart, Suit::Diamond]
     }
+
+    /// Finds if the suit is red, rather than black
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Suit;
+    /// assert!(Suit::Diamond.is_red());
+    /// assert!(!Suit::Club.is_red());
+    /// ```
+    pub fn is_red(&self) -> bool {
+        matches!(self, Suit::Heart | Suit::Diamond)
+    }
 }
 
 impl Display for Suit {
@@ -138,8 +222,55 @@ impl Display for Suit {
     }
 }
 
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    /// Parses a suit from its letter, ignoring case, or its symbol
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Suit;
+    /// assert_eq!("s".parse(), Ok(Suit::Spade));
+    /// assert_eq!("♦".parse(), Ok(Suit::Diamond));
+    /// assert_eq!("♡".parse(), Ok(Suit::Heart));
+    /// assert!("x".parse::<Suit>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Suit, ParseCardError> {
+        match s {
+            "S" | "s" | "♠" | "♤" => Ok(Suit::Spade),
+            "C" | "c" | "♣" | "♧" => Ok(Suit::Club),
+            "H" | "h" | "♥" | "♡" => Ok(Suit::Heart),
+            "D" | "d" | "♦" | "♢" => Ok(Suit::Diamond),
+            _ => Err(ParseCardError::InvalidSuit(s.to_string())),
+        }
+    }
+}
+
 /// A struct representing a card
-#[derive(Clone, Copy, Eq, PartialEq, Debug, PartialOrd, Ord)]
+///
+/// Cards compare by suit first, then by rank, so sorting groups a hand by suit with each suit
+/// running from twos up to aces, and jokers last in their suit. This order is part of the
+/// crate's stable API, and is the same as [`CardOrder::standard`]. Games that rank cards
+/// differently, such as by rank first or with aces low, compare them with a [`CardOrder`]
+/// instead
+///
+/// [`CardOrder`]: super::order::CardOrder
+/// [`CardOrder::standard`]: super::order::CardOrder::standard
+/// # Examples
+/// ```
+/// # use naipe::common::card::Card;
+/// let mut hand: Vec<Card> = ["2D", "AS", "KH", "3S", "2C"]
+///     .iter()
+///     .map(|card| card.parse().unwrap())
+///     .collect();
+/// hand.sort();
+/// let sorted: Vec<Card> = ["3S", "AS", "2C", "KH", "2D"]
+///     .iter()
+///     .map(|card| card.parse().unwrap())
+///     .collect();
+/// assert_eq!(hand, sorted);
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Debug, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card {
     suit: Suit,
     rank: Rank,
@@ -151,7 +282,29 @@ impl Card {
         Card { suit, rank }
     }
 
-    /// Compiles a list of all possible cards into a vector
+    /// Creates a black joker, shown as the joker of spades
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// let joker = Card::black_joker();
+    /// assert!(joker.is_joker());
+    /// assert_eq!(joker, Card::new(Suit::Spade, Rank::Joker));
+    /// ```
+    pub fn black_joker() -> Card {
+        Card::new(Suit::Spade, Rank::Joker)
+    }
+
+    /// Creates a red joker, shown as the joker of hearts
+    pub fn red_joker() -> Card {
+        Card::new(Suit::Heart, Rank::Joker)
+    }
+
+    /// Finds if this card is a joker
+    pub fn is_joker(&self) -> bool {
+        self.rank == Rank::Joker
+    }
+
+    /// Compiles a list of all the cards of a standard deck into a vector, without jokers
     /// # Examples
     /// ```
     /// # use naipe::common::card::Card;
@@ -198,6 +351,57 @@ impl Display for Card {
     }
 }
 
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    /// Parses a card written as its rank followed by its suit
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// assert_eq!("AS".parse(), Ok(Card::new(Suit::Spade, Rank::Ace)));
+    /// assert_eq!("10h".parse(), Ok(Card::new(Suit::Heart, Rank::Ten)));
+    /// assert_eq!("Q♦".parse(), Ok(Card::new(Suit::Diamond, Rank::Queen)));
+    ///
+    /// let card = Card::new(Suit::Club, Rank::Seven);
+    /// assert_eq!(card.to_string().parse(), Ok(card));
+    /// ```
+    fn from_str(s: &str) -> Result<Card, ParseCardError> {
+        let s = s.trim();
+        let (suit_start, _) = s.char_indices().last().ok_or(ParseCardError::EmptyToken)?;
+        let (rank, suit) = s.split_at(suit_start);
+        if rank.is_empty() {
+            return Err(ParseCardError::InvalidRank(rank.to_string()));
+        }
+        Ok(Card::new(suit.parse()?, rank.parse()?))
+    }
+}
+
+/// Parses a list of cards separated by whitespace or commas, optionally wrapped in brackets
+pub(crate) fn parse_cards(notation: &str) -> Result<Vec<Card>, ParseCardError> {
+    let notation = notation.trim();
+    let notation = notation
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .unwrap_or(notation);
+    notation
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Errors related to parsing cards from text
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseCardError {
+    #[error("Invalid rank: {0:?}")]
+    InvalidRank(String),
+    #[error("Invalid suit: {0:?}")]
+    InvalidSuit(String),
+    #[error("Expected a card, but found nothing")]
+    EmptyToken,
+}
+
 /// A wrapper around a card that does not compare suits
 /// # Usage
 /// ```
@@ -272,3 +476,61 @@ impl Display for Suitless {
         write!(f, "{}", self.0)
     }
 }
+
+/// A wrapper around a card that does not compare suits, and ranks aces below twos
+///
+/// Jokers still rank above every other card
+/// # Usage
+/// ```
+/// # use naipe::common::card::{AceLow, Card, Rank, Suit};
+/// let ace = AceLow(Card::new(Suit::Spade, Rank::Ace));
+/// let two = AceLow(Card::new(Suit::Heart, Rank::Two));
+/// assert!(ace < two);
+/// assert!(two.0.get_rank().is_directly_after(ace.0.get_rank()));
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct AceLow(pub Card);
+
+impl AceLow {
+    /// Unwraps this card and gets the underlying card
+    ///
+    /// Consumes this wrapper
+    pub fn unwrap(self) -> Card {
+        self.0
+    }
+
+    /// Gets how far up the ace low ordering this card's rank is
+    fn position(self) -> u8 {
+        match self.0.rank {
+            Rank::Ace => 0,
+            rank => rank as u8 + 1,
+        }
+    }
+}
+
+impl PartialOrd for AceLow {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AceLow {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.position().cmp(&other.position())
+    }
+}
+
+impl PartialEq for AceLow {
+    fn eq(&self, other: &AceLow) -> bool {
+        self.0.rank == other.0.rank
+    }
+}
+
+impl Eq for AceLow {}
+
+impl Display for AceLow {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,273 @@
+//! A compact set representation for cards
+//!
+//! Each distinct card maps to a single bit, making sets cheap to copy, hash, and compare
+
+use std::fmt::{self, Display, Formatter};
+
+use super::{
+    card::{Card, Rank, Suit},
+    hand::Hand,
+};
+
+/// Ranks in the order of their bit positions in a [`CardSet`]
+const RANKS: [Rank; 13] = [
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+    Rank::Ace,
+];
+
+/// Suits in the order of their bit positions in a [`CardSet`]
+const SUITS: [Suit; 4] = [Suit::Spade, Suit::Club, Suit::Heart, Suit::Diamond];
+
+/// The number of cards in a standard deck, and the bit position of the first joker
+const STANDARD_CARDS: usize = RANKS.len() * SUITS.len();
+
+/// The number of bit positions, for standard cards and a joker of each suit
+pub(crate) const CARD_SLOTS: usize = STANDARD_CARDS + SUITS.len();
+
+/// The bits used for standard cards and jokers
+const CARD_BITS: u64 = (1 << CARD_SLOTS) - 1;
+
+/// A set of distinct cards, stored as a bitmask
+///
+/// Duplicate cards (e.g. from multi-set decks) collapse into a single entry
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::common::card_set::CardSet;
+/// let mut set = CardSet::new();
+/// set.insert(Card::new(Suit::Spade, Rank::Ace));
+/// assert!(set.contains(Card::new(Suit::Spade, Rank::Ace)));
+/// assert!(!set.contains(Card::new(Suit::Heart, Rank::Ace)));
+/// assert_eq!(set.len(), 1);
+///
+/// // Jokers have their own bits, apart from the standard cards
+/// set.insert(Card::black_joker());
+/// assert!(!set.contains(Card::new(Suit::Club, Rank::Two)));
+/// assert_eq!(set.iter().last(), Some(Card::black_joker()));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct CardSet(u64);
+
+impl CardSet {
+    /// Creates a new, empty set
+    pub fn new() -> CardSet {
+        CardSet(0)
+    }
+
+    /// Creates a set from its raw bit representation
+    ///
+    /// Bits beyond those used for cards are discarded
+    pub fn from_bits(bits: u64) -> CardSet {
+        CardSet(bits & CARD_BITS)
+    }
+
+    /// Gets the raw bit representation of this set
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    /// Creates a set containing every card of a standard deck, without jokers
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card_set::CardSet;
+    /// assert_eq!(CardSet::full().len(), 52);
+    /// ```
+    pub fn full() -> CardSet {
+        CardSet((1 << STANDARD_CARDS) - 1)
+    }
+
+    /// Creates a set containing every card of the given suit
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::card_set::CardSet;
+    /// let hearts = CardSet::suit(Suit::Heart);
+    /// assert_eq!(hearts.len(), 13);
+    /// assert!(hearts.iter().all(|card| card.get_suit() == Suit::Heart));
+    /// ```
+    pub fn suit(suit: Suit) -> CardSet {
+        CardSet(0x1fff << (suit as u32 * 13))
+    }
+
+    /// Gets the position of the card's bit, unique for each distinct card
+    ///
+    /// Jokers sit after the 52 standard cards, one for each suit
+    pub(crate) fn index(card: Card) -> usize {
+        if card.is_joker() {
+            STANDARD_CARDS + card.get_suit() as usize
+        } else {
+            card.get_suit() as usize * 13 + card.get_rank() as usize
+        }
+    }
+
+    /// Gets the card with the given bit position
+    pub(crate) fn card_at(index: usize) -> Card {
+        if index >= STANDARD_CARDS {
+            Card::new(SUITS[index - STANDARD_CARDS], Rank::Joker)
+        } else {
+            Card::new(SUITS[index / 13], RANKS[index % 13])
+        }
+    }
+
+    fn bit(card: Card) -> u64 {
+        1 << Self::index(card)
+    }
+
+    /// Adds a card to this set, returning whether it was newly added
+    pub fn insert(&mut self, card: Card) -> bool {
+        let bit = Self::bit(card);
+        let added = self.0 & bit == 0;
+        self.0 |= bit;
+        added
+    }
+
+    /// Removes a card from this set, returning whether it was present
+    pub fn remove(&mut self, card: Card) -> bool {
+        let bit = Self::bit(card);
+        let present = self.0 & bit != 0;
+        self.0 &= !bit;
+        present
+    }
+
+    /// Finds if the given card is in this set
+    pub fn contains(&self, card: Card) -> bool {
+        self.0 & Self::bit(card) != 0
+    }
+
+    /// Finds how many cards are in this set
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Finds if this set has no cards
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Gets the cards in either set
+    #[must_use]
+    pub fn union(&self, other: CardSet) -> CardSet {
+        CardSet(self.0 | other.0)
+    }
+
+    /// Gets the cards in both sets
+    #[must_use]
+    pub fn intersection(&self, other: CardSet) -> CardSet {
+        CardSet(self.0 & other.0)
+    }
+
+    /// Gets the cards in this set but not the other
+    #[must_use]
+    pub fn difference(&self, other: CardSet) -> CardSet {
+        CardSet(self.0 & !other.0)
+    }
+
+    /// Iterates over the cards in this set, ordered by suit then rank
+    pub fn iter(&self) -> CardSetIter {
+        CardSetIter(self.0)
+    }
+}
+
+/// An iterator over the cards in a [`CardSet`]
+#[derive(Clone, Debug)]
+pub struct CardSetIter(u64);
+
+impl Iterator for CardSetIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(CardSet::card_at(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CardSetIter {}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> CardSetIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> CardSetIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> CardSet {
+        let mut set = CardSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<T: IntoIterator<Item = Card>>(&mut self, iter: T) {
+        for card in iter {
+            self.insert(card);
+        }
+    }
+}
+
+impl From<&Hand> for CardSet {
+    /// Collects the distinct cards in a hand
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::card_set::CardSet;
+    /// # use naipe::common::hand::Hand;
+    /// let hand: Hand = [
+    ///     Card::new(Suit::Heart, Rank::Two),
+    ///     Card::new(Suit::Club, Rank::King),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    /// let set = CardSet::from(&hand);
+    /// assert_eq!(set.len(), 2);
+    /// assert!(set.contains(Card::new(Suit::Club, Rank::King)));
+    /// ```
+    fn from(hand: &Hand) -> CardSet {
+        hand.iter().copied().collect()
+    }
+}
+
+impl Display for CardSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let string = self
+            .iter()
+            .map(|card| card.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        write!(f, "{{{string}}}")
+    }
+}
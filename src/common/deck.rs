@@ -1,15 +1,35 @@
 //! A module for handling decks
 //!
-//! Decks can have any integer number of full sets of cards
+//! Decks can have any integer number of full sets of cards,
+//! or a custom composition built with [`DeckBuilder`]
 
-use std::{iter::zip, num::NonZeroU16};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    iter::zip,
+    num::NonZeroU16,
+};
 
+#[cfg(feature = "rand")]
 use rand::prelude::SliceRandom;
 use thiserror::Error;
 
-use super::{card::Card, hand::Hand};
+use super::{
+    card::{parse_cards, Card, ParseCardError, Rank, Suit},
+    card_set::CardSet,
+    hand::Hand,
+    pile::CardPile,
+};
+use crate::sealed::Sealed;
+
+/// FNV-1a offset basis, used to start a fingerprint
+const FINGERPRINT_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// FNV-1a prime, mixed in for each card of a fingerprint
+const FINGERPRINT_PRIME: u64 = 0x0100_0000_01b3;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Deck {
     cards: Vec<Card>,
 }
@@ -33,22 +53,135 @@ impl Deck {
         Deck { cards: vec![] }
     }
 
+    /// Creates a deck from cards written in text, such as `"AS KH 9c"`, for a fixed deal
+    ///
+    /// Cards are separated by whitespace or commas, and the first card listed is the top card,
+    /// so it is drawn or dealt first
+    /// # Errors
+    /// [`ParseCardError`] if any card could not be parsed
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Card;
+    /// # use naipe::common::deck::Deck;
+    /// let mut deck = Deck::from_notation("AS KH 9c 2d").unwrap();
+    /// assert_eq!(deck.draw(), Some("AS".parse().unwrap()));
+    /// let hands = deck.deal_cards(3, 1).unwrap();
+    /// assert_eq!(hands[2], vec!["2d".parse::<Card>().unwrap()]);
+    /// ```
+    pub fn from_notation(notation: &str) -> Result<Deck, ParseCardError> {
+        let mut cards = parse_cards(notation)?;
+        cards.reverse();
+        Ok(Deck { cards })
+    }
+
     /// Shuffles the deck with the provided Rng
     /// Useful for seeded Rng
+    #[cfg(feature = "rand")]
     pub fn shuffle<Rng: rand::Rng + ?Sized>(&mut self, rng: &mut Rng) {
         self.cards.shuffle(rng);
     }
 
+    /// Shuffles the deck with the provided Rng, giving the position in the old order of each card
+    /// in the new order
+    ///
+    /// Draws the same numbers from the Rng as [`Deck::shuffle`], so leaves the deck in the same
+    /// order
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::Deck;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// let mut deck = Deck::from_notation("AS KH 9C 2D").unwrap();
+    /// let mut shuffled = deck.clone();
+    /// deck.shuffle(&mut StdRng::seed_from_u64(5));
+    /// let permutation = shuffled.shuffle_permutation(&mut StdRng::seed_from_u64(5));
+    /// assert_eq!(shuffled, deck);
+    ///
+    /// let mut sorted = permutation.clone();
+    /// sorted.sort();
+    /// assert_eq!(sorted, vec![0, 1, 2, 3]);
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn shuffle_permutation<Rng: rand::Rng + ?Sized>(&mut self, rng: &mut Rng) -> Vec<usize> {
+        let mut permutation: Vec<usize> = (0..self.cards.len()).collect();
+        permutation.shuffle(rng);
+        self.cards = permutation.iter().map(|index| self.cards[*index]).collect();
+        permutation
+    }
+
     /// Shuffles the deck with the default [`rand::thread_rng`]
+    #[cfg(feature = "rand")]
     pub fn shuffle_with_default_rng(&mut self) {
         self.cards.shuffle(&mut rand::thread_rng());
     }
 
+    /// Reorders the deck by a permutation the caller supplies, giving the position in the old
+    /// order of each card in the new order, as [`Deck::shuffle_permutation`] returns
+    ///
+    /// Shuffles without the `rand` feature, such as from a hardware random source
+    /// # Errors
+    /// [`DeckPermutationError::WrongLength`] unless there is a position for every card, or
+    /// [`DeckPermutationError::InvalidPosition`] if a position is not in the deck or repeated,
+    /// leaving the deck unchanged either way
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::{Deck, DeckPermutationError};
+    /// let mut deck = Deck::from_notation("AS KH 9C").unwrap();
+    /// deck.permute(&[1, 2, 0]).unwrap();
+    /// assert_eq!(deck, Deck::from_notation("9C AS KH").unwrap());
+    /// assert_eq!(deck.permute(&[0, 0, 1]), Err(DeckPermutationError::InvalidPosition(0)));
+    /// assert!(deck.permute(&[0, 1]).is_err());
+    /// ```
+    pub fn permute(&mut self, permutation: &[usize]) -> Result<(), DeckPermutationError> {
+        if permutation.len() != self.cards.len() {
+            return Err(DeckPermutationError::WrongLength {
+                expected: self.cards.len(),
+                actual: permutation.len(),
+            });
+        }
+        let mut placed = vec![false; self.cards.len()];
+        for index in permutation {
+            match placed.get_mut(*index) {
+                Some(placed @ false) => *placed = true,
+                _ => return Err(DeckPermutationError::InvalidPosition(*index)),
+            }
+        }
+        self.cards = permutation.iter().map(|index| self.cards[*index]).collect();
+        Ok(())
+    }
+
     /// Adds a card to this deck
     pub fn add(&mut self, card: Card) {
         self.cards.push(card);
     }
 
+    /// Inserts a card with the given number of cards above it, or at the bottom if the deck is
+    /// not that deep
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::Deck;
+    /// let mut deck = Deck::from_notation("2C 3C").unwrap();
+    /// deck.insert(1, "AS".parse().unwrap());
+    /// assert_eq!(deck.draw(), "2C".parse().ok());
+    /// assert_eq!(deck.draw(), "AS".parse().ok());
+    /// ```
+    pub fn insert(&mut self, depth: usize, card: Card) {
+        let index = self.cards.len().saturating_sub(depth);
+        self.cards.insert(index, card);
+    }
+
+    /// Draws the top card from the deck, if there is one
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::Deck;
+    /// let mut deck = Deck::default();
+    /// assert!(deck.draw().is_some());
+    /// assert_eq!(deck.len(), 51);
+    /// assert_eq!(Deck::new_empty().draw(), None);
+    /// ```
+    pub fn draw(&mut self) -> Option<Card> {
+        self.cards.pop()
+    }
+
     /// Gets the number of cards currently in the deck
     pub fn len(&self) -> usize {
         self.cards.len()
@@ -64,6 +197,35 @@ impl Deck {
         self.cards.is_empty()
     }
 
+    /// Gets a short fingerprint of the order of the cards in this deck
+    ///
+    /// Decks in the same order always have the same fingerprint, on every platform and version,
+    /// but different orders may rarely collide, so use [`Deck::same_order`] to confirm a match
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::Deck;
+    /// let mut deck_1 = Deck::default();
+    /// let mut deck_2 = Deck::default();
+    /// assert_eq!(deck_1.fingerprint(), deck_2.fingerprint());
+    /// let reversed: Vec<usize> = (0..deck_1.len()).rev().collect();
+    /// deck_1.permute(&reversed).unwrap();
+    /// assert_ne!(deck_1.fingerprint(), deck_2.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> DeckFingerprint {
+        let hash = self.cards.iter().fold(FINGERPRINT_OFFSET, |hash, card| {
+            // Offset by one so the first card has a non-zero code
+            (hash ^ (CardSet::index(*card) as u64 + 1)).wrapping_mul(FINGERPRINT_PRIME)
+        });
+        DeckFingerprint(hash)
+    }
+
+    /// Finds if this deck has exactly the same cards in the same order as another
+    ///
+    /// Compares fingerprints first, so mismatches are usually found without comparing each card
+    pub fn same_order(&self, other: &Deck) -> bool {
+        self.fingerprint() == other.fingerprint() && self.cards == other.cards
+    }
+
     /// Deals a set amount of cards from the top of the deck for the specified number of hands
     /// # Errors
     /// [`DeckDealError::NotEnoughCards`] if the deck does not have enough cards to fulfill the deal request
@@ -148,9 +310,300 @@ impl Extend<Card> for Deck {
     }
 }
 
+impl IntoIterator for Deck {
+    type Item = Card;
+    type IntoIter = std::vec::IntoIter<Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.into_iter()
+    }
+}
+
+/// A deck is backed by a [`Vec`], with the same costs
+impl Sealed for Deck {}
+
+impl CardPile for Deck {
+    fn len(&self) -> usize {
+        Deck::len(self)
+    }
+
+    fn peek_top(&self) -> Option<Card> {
+        self.cards.last().copied()
+    }
+
+    fn push_top(&mut self, card: Card) {
+        self.add(card);
+    }
+
+    fn pop_top(&mut self) -> Option<Card> {
+        self.draw()
+    }
+
+    fn push_bottom(&mut self, card: Card) {
+        self.cards.push_bottom(card);
+    }
+
+    fn pop_bottom(&mut self) -> Option<Card> {
+        self.cards.pop_bottom()
+    }
+
+    fn insert(&mut self, depth: usize, card: Card) {
+        Deck::insert(self, depth, card);
+    }
+}
+
+/// Builds decks with a custom composition, such as stripped decks or decks with jokers
+///
+/// Starts from one standard set of 52 cards
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Rank, Suit};
+/// # use naipe::common::deck::DeckBuilder;
+/// use std::num::NonZeroU16;
+/// let deck = DeckBuilder::new()
+///     .sets(NonZeroU16::new(2).unwrap())
+///     .without_ranks(&[Rank::Two, Rank::Three])
+///     .without_suits(&[Suit::Diamond])
+///     .jokers(1)
+///     .build();
+/// assert_eq!(deck.len(), 2 * (11 * 3 + 1));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeckBuilder {
+    sets: NonZeroU16,
+    ranks: Vec<Rank>,
+    suits: Vec<Suit>,
+    jokers: u8,
+}
+
+impl DeckBuilder {
+    /// Creates a builder for one standard set of 52 cards
+    pub fn new() -> DeckBuilder {
+        DeckBuilder {
+            sets: NonZeroU16::MIN,
+            ranks: Rank::all_ranks(),
+            suits: Suit::all_suits(),
+            jokers: 0,
+        }
+    }
+
+    /// Creates a builder for a 32 card Piquet deck, with sevens through aces
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::DeckBuilder;
+    /// assert_eq!(DeckBuilder::piquet().build().len(), 32);
+    /// ```
+    pub fn piquet() -> DeckBuilder {
+        DeckBuilder::new().without_ranks(&[
+            Rank::Two,
+            Rank::Three,
+            Rank::Four,
+            Rank::Five,
+            Rank::Six,
+        ])
+    }
+
+    /// Creates a builder for a 24 card Euchre deck, with nines through aces
+    pub fn euchre() -> DeckBuilder {
+        DeckBuilder::piquet().without_ranks(&[Rank::Seven, Rank::Eight])
+    }
+
+    /// Creates a builder for a 48 card Pinochle deck, with two sets of nines through aces
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::DeckBuilder;
+    /// assert_eq!(DeckBuilder::pinochle().build().len(), 48);
+    /// ```
+    pub fn pinochle() -> DeckBuilder {
+        DeckBuilder::euchre().sets(NonZeroU16::MIN.saturating_add(1))
+    }
+
+    /// Sets the number of copies of the whole composition, jokers included
+    #[must_use]
+    pub fn sets(mut self, sets: NonZeroU16) -> DeckBuilder {
+        self.sets = sets;
+        self
+    }
+
+    /// Keeps only the given ranks
+    ///
+    /// Jokers are added with [`DeckBuilder::jokers`] instead, so are ignored here
+    #[must_use]
+    pub fn ranks(mut self, ranks: &[Rank]) -> DeckBuilder {
+        self.ranks = Rank::all_ranks()
+            .into_iter()
+            .filter(|rank| ranks.contains(rank))
+            .collect();
+        self
+    }
+
+    /// Removes the given ranks
+    #[must_use]
+    pub fn without_ranks(mut self, ranks: &[Rank]) -> DeckBuilder {
+        self.ranks.retain(|rank| !ranks.contains(rank));
+        self
+    }
+
+    /// Keeps only the given suits
+    #[must_use]
+    pub fn suits(mut self, suits: &[Suit]) -> DeckBuilder {
+        self.suits = Suit::all_suits()
+            .into_iter()
+            .filter(|suit| suits.contains(suit))
+            .collect();
+        self
+    }
+
+    /// Removes the given suits
+    #[must_use]
+    pub fn without_suits(mut self, suits: &[Suit]) -> DeckBuilder {
+        self.suits.retain(|suit| !suits.contains(suit));
+        self
+    }
+
+    /// Sets the number of jokers in each set, alternating black and red
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Card;
+    /// # use naipe::common::deck::DeckBuilder;
+    /// let deck = DeckBuilder::new().jokers(2).build();
+    /// assert_eq!(deck.len(), 54);
+    /// let jokers: Vec<Card> = deck.into_iter().filter(Card::is_joker).collect();
+    /// assert_eq!(jokers, vec![Card::black_joker(), Card::red_joker()]);
+    /// ```
+    #[must_use]
+    pub fn jokers(mut self, jokers: u8) -> DeckBuilder {
+        self.jokers = jokers;
+        self
+    }
+
+    /// Gets the number of cards in each set
+    pub fn cards_per_set(&self) -> usize {
+        self.ranks.len() * self.suits.len() + usize::from(self.jokers)
+    }
+
+    /// Builds the deck, with the cards of each set in order and the jokers last
+    pub fn build(&self) -> Deck {
+        let set: Vec<Card> = self
+            .suits
+            .iter()
+            .flat_map(|suit| self.ranks.iter().map(|rank| Card::new(*suit, *rank)))
+            .chain((0..self.jokers).map(|joker| {
+                if joker % 2 == 0 {
+                    Card::black_joker()
+                } else {
+                    Card::red_joker()
+                }
+            }))
+            .collect();
+        let cards = (0..self.sets.get()).flat_map(|_| set.clone()).collect();
+        Deck { cards }
+    }
+}
+
+impl Default for DeckBuilder {
+    fn default() -> DeckBuilder {
+        DeckBuilder::new()
+    }
+}
+
+/// A 64-bit fingerprint of a deck's order, from [`Deck::fingerprint`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct DeckFingerprint(pub u64);
+
+impl Display for DeckFingerprint {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Records deck orders to detect duplicate shuffles
+///
+/// Fingerprint collisions between different orders are told apart by comparing the full decks
+/// # Examples
+/// ```
+/// # use naipe::common::deck::{Deck, ShuffleLog};
+/// let mut log = ShuffleLog::new();
+/// let mut deck = Deck::default();
+/// assert!(log.record(&deck));
+/// assert!(!log.record(&deck.clone()));
+/// assert_eq!(log.duplicates(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ShuffleLog {
+    seen: BTreeMap<DeckFingerprint, Vec<Deck>>,
+    recorded: usize,
+    duplicates: usize,
+}
+
+impl ShuffleLog {
+    /// Creates a new, empty log
+    pub fn new() -> ShuffleLog {
+        ShuffleLog::default()
+    }
+
+    /// Records a deck order, returning `false` if the same order was recorded before
+    pub fn record(&mut self, deck: &Deck) -> bool {
+        self.recorded += 1;
+        let orders = self.seen.entry(deck.fingerprint()).or_default();
+        if orders.iter().any(|seen| seen.cards == deck.cards) {
+            self.duplicates += 1;
+            false
+        } else {
+            orders.push(deck.clone());
+            true
+        }
+    }
+
+    /// Finds if the same deck order has been recorded
+    pub fn contains(&self, deck: &Deck) -> bool {
+        self.seen
+            .get(&deck.fingerprint())
+            .is_some_and(|orders| orders.iter().any(|seen| seen.cards == deck.cards))
+    }
+
+    /// Gets the number of decks recorded, including duplicates
+    pub fn len(&self) -> usize {
+        self.recorded
+    }
+
+    /// Finds if no decks have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.recorded == 0
+    }
+
+    /// Gets the number of recorded decks that repeated an earlier order
+    pub fn duplicates(&self) -> usize {
+        self.duplicates
+    }
+}
+
 /// Errors related to dealing from a deck
+///
+/// New kinds of errors may be added in the future, so matches must include a wildcard arm
+/// ```compile_fail
+/// # use naipe::common::deck::DeckDealError;
+/// fn describe(error: DeckDealError) -> &'static str {
+///     match error {
+///         DeckDealError::NotEnoughCards => "not enough cards",
+///     }
+/// }
+/// ```
 #[derive(Copy, Clone, Debug, Error)]
+#[non_exhaustive]
 pub enum DeckDealError {
     #[error("Not enough cards in deck to deal")]
     NotEnoughCards,
 }
+
+/// Errors related to reordering a deck by a permutation
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DeckPermutationError {
+    #[error("Expected a permutation of {expected} positions, but got {actual}")]
+    WrongLength { expected: usize, actual: usize },
+    #[error("Position {0} is past the end of the deck or given more than once")]
+    InvalidPosition(usize),
+}
@@ -0,0 +1,418 @@
+//! Fixed width text diagrams of tables, for logs, emails, and forum posts
+//!
+//! A [`BridgeDiagram`] lays out four hands around a compass, and a [`PokerTable`] lists each
+//! seat's stack and bet under the board. Both render with [`Display`], writing tens as `T` so
+//! every rank takes a single column. Bridge deals can also be read from and written to the
+//! one line dot notation used by PBN files and forum posts.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::{
+    card::{Card, Rank, Suit},
+    card_set::CardSet,
+};
+
+/// The suits in the order bridge diagrams list them
+const BRIDGE_SUITS: [Suit; 4] = [Suit::Spade, Suit::Heart, Suit::Diamond, Suit::Club];
+/// The bridge seats in clockwise order, as written in dot notation
+const BRIDGE_SEATS: [char; 4] = ['N', 'E', 'S', 'W'];
+/// The number of community cards dealt in hold'em style games
+const BOARD_SIZE: usize = 5;
+
+/// How suits are written in diagrams
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum SuitStyle {
+    /// Suit symbols, such as `♠`
+    #[default]
+    Symbols,
+    /// Suit letters, such as `S`, for places that cannot show symbols
+    Letters,
+}
+
+impl SuitStyle {
+    /// Writes a suit in this style
+    pub fn suit(&self, suit: Suit) -> String {
+        match self {
+            SuitStyle::Symbols => suit.to_string(),
+            SuitStyle::Letters => match suit {
+                Suit::Spade => "S",
+                Suit::Heart => "H",
+                Suit::Diamond => "D",
+                Suit::Club => "C",
+            }
+            .to_string(),
+        }
+    }
+
+    /// Writes a card in this style, as its rank then its suit
+    pub fn card(&self, card: Card) -> String {
+        format!(
+            "{}{}",
+            rank_letter(card.get_rank()),
+            self.suit(card.get_suit())
+        )
+    }
+}
+
+/// A bridge deal, with North at the top and the other hands in clockwise order
+/// # Examples
+/// ```
+/// # use naipe::common::card::Card;
+/// # use naipe::common::card_set::CardSet;
+/// # use naipe::common::diagram::{BridgeDiagram, SuitStyle};
+/// let hand = |notation: &str| -> CardSet {
+///     notation
+///         .split_whitespace()
+///         .map(|card| card.parse::<Card>().unwrap())
+///         .collect()
+/// };
+/// let diagram = BridgeDiagram::new([
+///     hand("AS KS 10H 2C"),
+///     hand("QS 9D"),
+///     hand("AH KH QH AD KD"),
+///     hand("JS"),
+/// ])
+/// .style(SuitStyle::Letters);
+/// let expected = "
+///         S AK
+///         H T
+///         D -
+///         C 2
+/// S J             S Q
+/// H -        N    H -
+/// D -      W   E  D 9
+/// C -        S    C -
+///         S -
+///         H AKQ
+///         D AK
+///         C -
+/// ";
+/// assert_eq!(diagram.to_string(), expected.trim_start_matches('\n'));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BridgeDiagram {
+    hands: [CardSet; 4],
+    style: SuitStyle,
+}
+
+impl BridgeDiagram {
+    /// Creates a diagram of the North, East, South, and West hands
+    pub fn new(hands: [CardSet; 4]) -> BridgeDiagram {
+        BridgeDiagram {
+            hands,
+            style: SuitStyle::default(),
+        }
+    }
+
+    /// Sets how suits are written
+    #[must_use]
+    pub fn style(mut self, style: SuitStyle) -> BridgeDiagram {
+        self.style = style;
+        self
+    }
+
+    /// Reads a deal in dot notation, such as `N:AKQJ.T98.765.432 - - -`
+    ///
+    /// The seat before the colon holds the first hand, with the rest following clockwise. Each
+    /// hand lists its spades, hearts, diamonds, then clubs, separated by dots, and a hand written
+    /// as `-` is unknown and left empty
+    /// # Errors
+    /// A [`DealNotationError`] describing the first problem found
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::diagram::BridgeDiagram;
+    /// let deal = BridgeDiagram::from_notation("E:AKQJ.T98.765.432 - - -").unwrap();
+    /// let east = deal.hands()[1];
+    /// assert_eq!(east.len(), 13);
+    /// assert!(east.contains(Card::new(Suit::Heart, Rank::Ten)));
+    /// assert!(deal.hands()[0].is_empty());
+    /// assert_eq!(deal.notation(), "N:- AKQJ.T98.765.432 - -");
+    ///
+    /// assert!(BridgeDiagram::from_notation("N:AA... - - -").is_err());
+    /// ```
+    pub fn from_notation(notation: &str) -> Result<BridgeDiagram, DealNotationError> {
+        let (first, hands) = notation
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| DealNotationError::MissingSeat(notation.to_string()))?;
+        let first = BRIDGE_SEATS
+            .iter()
+            .position(|seat| first.trim().eq_ignore_ascii_case(&seat.to_string()))
+            .ok_or_else(|| DealNotationError::InvalidSeat(first.to_string()))?;
+        let hands: Vec<&str> = hands.split_whitespace().collect();
+        if hands.len() != BRIDGE_SEATS.len() {
+            return Err(DealNotationError::WrongHandCount(hands.len()));
+        }
+
+        let mut dealt = CardSet::new();
+        let mut parsed = [CardSet::new(); 4];
+        for (offset, hand) in hands.into_iter().enumerate() {
+            let seat = (first + offset) % BRIDGE_SEATS.len();
+            if hand == "-" {
+                continue;
+            }
+            let suits: Vec<&str> = hand.split('.').collect();
+            if suits.len() != BRIDGE_SUITS.len() {
+                return Err(DealNotationError::WrongSuitCount(hand.to_string()));
+            }
+            for (suit, ranks) in BRIDGE_SUITS.iter().zip(suits) {
+                for rank in ranks.chars() {
+                    let rank = rank
+                        .to_string()
+                        .parse()
+                        .ok()
+                        .filter(|rank| *rank != Rank::Joker)
+                        .ok_or(DealNotationError::InvalidRank(rank))?;
+                    let card = Card::new(*suit, rank);
+                    if !dealt.insert(card) {
+                        return Err(DealNotationError::DuplicateCard(card));
+                    }
+                    parsed[seat].insert(card);
+                }
+            }
+        }
+        Ok(BridgeDiagram::new(parsed))
+    }
+
+    /// Gets the North, East, South, and West hands
+    pub fn hands(&self) -> &[CardSet; 4] {
+        &self.hands
+    }
+
+    /// Writes the deal in dot notation, starting from North, with empty hands as `-`
+    pub fn notation(&self) -> String {
+        let hands: Vec<String> = self
+            .hands
+            .iter()
+            .map(|hand| {
+                if hand.is_empty() {
+                    return "-".to_string();
+                }
+                let suits: Vec<String> = BRIDGE_SUITS
+                    .iter()
+                    .map(|suit| holding(*hand, *suit))
+                    .collect();
+                suits.join(".")
+            })
+            .collect();
+        format!("N:{}", hands.join(" "))
+    }
+
+    /// Writes a hand as one line per suit, highest ranks first, with a dash for a void
+    fn hand_lines(&self, hand: CardSet) -> Vec<String> {
+        BRIDGE_SUITS
+            .iter()
+            .map(|suit| {
+                let holding = holding(hand, *suit);
+                let holding = if holding.is_empty() {
+                    "-".to_string()
+                } else {
+                    holding
+                };
+                format!("{} {holding}", self.style.suit(*suit))
+            })
+            .collect()
+    }
+}
+
+impl FromStr for BridgeDiagram {
+    type Err = DealNotationError;
+
+    /// Reads a deal in dot notation, as for [`BridgeDiagram::from_notation`]
+    fn from_str(s: &str) -> Result<BridgeDiagram, DealNotationError> {
+        BridgeDiagram::from_notation(s)
+    }
+}
+
+impl Display for BridgeDiagram {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let [north, east, south, west] = self.hands.map(|hand| self.hand_lines(hand));
+        let compass = ["", "  N", "W   E", "  S"].map(|line| format!("{line:5}"));
+        let width = [&north, &east, &south, &west]
+            .iter()
+            .flat_map(|lines| lines.iter())
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or_default()
+            .max(compass[2].len())
+            + 3;
+        for line in &north {
+            writeln!(f, "{:width$}{line}", "")?;
+        }
+        for ((west, centre), east) in west.iter().zip(&compass).zip(&east) {
+            writeln!(f, "{west:<width$}{centre:^width$}{east}")?;
+        }
+        for line in &south {
+            writeln!(f, "{:width$}{line}", "")?;
+        }
+        Ok(())
+    }
+}
+
+/// A seat at a poker table
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PokerSeat {
+    pub name: String,
+    pub stack: u64,
+    /// The amount bet in the current round
+    pub bet: u64,
+    /// The hole cards to show, or none to keep them hidden
+    pub hole: Vec<Card>,
+    pub folded: bool,
+}
+
+/// A poker table, with the board, the pot, and each seat
+/// # Examples
+/// ```
+/// # use naipe::common::card::Card;
+/// # use naipe::common::diagram::{PokerSeat, PokerTable, SuitStyle};
+/// let cards = |notation: &str| -> Vec<Card> {
+///     notation
+///         .split_whitespace()
+///         .map(|card| card.parse().unwrap())
+///         .collect()
+/// };
+/// let table = PokerTable {
+///     board: cards("AS KH 7D"),
+///     pot: 1200,
+///     button: Some(0),
+///     seats: vec![
+///         PokerSeat {
+///             name: "Alice".to_string(),
+///             stack: 1500,
+///             bet: 100,
+///             hole: cards("AH AC"),
+///             folded: false,
+///         },
+///         PokerSeat {
+///             name: "Bob".to_string(),
+///             stack: 800,
+///             folded: true,
+///             ..PokerSeat::default()
+///         },
+///     ],
+///     style: SuitStyle::Letters,
+/// };
+/// let expected = "
+/// Board: AS KH 7D -- --  Pot: 1200
+/// D Seat 1  Alice  1500  bet 100  AH AC
+///   Seat 2  Bob     800  folded
+/// ";
+/// assert_eq!(table.to_string(), expected.trim_start_matches('\n'));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PokerTable {
+    /// The community cards dealt so far
+    pub board: Vec<Card>,
+    pub pot: u64,
+    /// The seat holding the dealer button, counting from zero
+    pub button: Option<usize>,
+    pub seats: Vec<PokerSeat>,
+    pub style: SuitStyle,
+}
+
+impl Display for PokerTable {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut board: Vec<String> = self
+            .board
+            .iter()
+            .map(|card| self.style.card(*card))
+            .collect();
+        board.resize(board.len().max(BOARD_SIZE), "--".to_string());
+        writeln!(f, "Board: {}  Pot: {}", board.join(" "), self.pot)?;
+
+        let name_width = self
+            .seats
+            .iter()
+            .map(|seat| seat.name.chars().count())
+            .max()
+            .unwrap_or_default();
+        let stack_width = self
+            .seats
+            .iter()
+            .map(|seat| seat.stack.to_string().len())
+            .max()
+            .unwrap_or_default();
+        // Wide enough for "folded" or the largest bet
+        let status_width = self
+            .seats
+            .iter()
+            .map(|seat| format!("bet {}", seat.bet).len())
+            .chain(["folded".len()])
+            .max()
+            .unwrap_or_default();
+        for (index, seat) in self.seats.iter().enumerate() {
+            let button = if self.button == Some(index) { "D" } else { " " };
+            let status = if seat.folded {
+                "folded".to_string()
+            } else if seat.bet > 0 {
+                format!("bet {}", seat.bet)
+            } else {
+                String::new()
+            };
+            let hole: Vec<String> = if seat.folded {
+                vec![]
+            } else {
+                seat.hole
+                    .iter()
+                    .map(|card| self.style.card(*card))
+                    .collect()
+            };
+            let line = format!(
+                "{button} Seat {}  {:<name_width$}  {:>stack_width$}  {status:<status_width$}  {}",
+                index + 1,
+                seat.name,
+                seat.stack,
+                hole.join(" ")
+            );
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the ranks held in a suit, highest first
+fn holding(hand: CardSet, suit: Suit) -> String {
+    let mut ranks: Vec<Rank> = hand
+        .iter()
+        .filter(|card| card.get_suit() == suit)
+        .map(|card| card.get_rank())
+        .collect();
+    ranks.sort_by(|a, b| b.cmp(a));
+    ranks.into_iter().map(rank_letter).collect()
+}
+
+/// Writes a rank as a single character, with tens as `T`
+fn rank_letter(rank: Rank) -> String {
+    match rank {
+        Rank::Ten => "T".to_string(),
+        rank => rank.to_string(),
+    }
+}
+
+/// Errors related to reading bridge deals in dot notation
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DealNotationError {
+    #[error("{0:?} does not start with the first hand's seat and a colon")]
+    MissingSeat(String),
+    #[error("{0:?} is not a seat, expected N, E, S, or W")]
+    InvalidSeat(String),
+    #[error("A deal has four hands, not {0}")]
+    WrongHandCount(usize),
+    #[error("{0:?} does not list four suits separated by dots")]
+    WrongSuitCount(String),
+    #[error("{0:?} is not a rank")]
+    InvalidRank(char),
+    #[error("Card {0} is dealt more than once")]
+    DuplicateCard(Card),
+}
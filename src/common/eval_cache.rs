@@ -0,0 +1,175 @@
+//! A bounded memoization layer for expensive hand evaluations
+//!
+//! Evaluations are keyed by the [`CardSet`] of the cards evaluated, so they must not
+//! depend on card order or duplicate cards
+
+use std::{
+    collections::{HashMap, VecDeque},
+    num::NonZeroUsize,
+};
+
+use super::card_set::CardSet;
+
+/// A least-recently-used cache of evaluation results keyed by [`CardSet`]
+///
+/// Each use of an entry stamps it with a new generation and queues its key with that stamp, so
+/// the least recently used entry is the first in the queue whose stamp is still current. Lookups,
+/// insertions, and evictions take constant time, amortized over the stale keys skipped
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::common::card_set::CardSet;
+/// # use naipe::common::eval_cache::EvalCache;
+/// use std::num::NonZeroUsize;
+/// let mut cache = EvalCache::new(NonZeroUsize::new(2).unwrap());
+/// let set: CardSet = [Card::new(Suit::Spade, Rank::Ace)].into_iter().collect();
+/// assert_eq!(cache.get_or_insert_with(set, |set| set.len()), 1);
+/// assert_eq!(cache.get_or_insert_with(set, |_| unreachable!()), 1);
+/// assert_eq!(cache.hits(), 1);
+/// assert_eq!(cache.misses(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EvalCache<V> {
+    capacity: NonZeroUsize,
+    entries: HashMap<CardSet, (V, u64)>,
+    /// Keys in order of use, oldest first, with the generation of each use
+    order: VecDeque<(CardSet, u64)>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl<V: Clone> EvalCache<V> {
+    /// Creates a new cache holding at most `capacity` evaluations
+    pub fn new(capacity: NonZeroUsize) -> EvalCache<V> {
+        EvalCache {
+            capacity,
+            entries: HashMap::with_capacity(capacity.get()),
+            order: VecDeque::with_capacity(capacity.get()),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Gets the cached evaluation for the set, if present, marking it as recently used
+    pub fn get(&mut self, set: CardSet) -> Option<V> {
+        let clock = self.clock + 1;
+        let (value, last_used) = self.entries.get_mut(&set)?;
+        *last_used = clock;
+        let value = value.clone();
+        self.touch(set, clock);
+        Some(value)
+    }
+
+    /// Stores an evaluation for the set, evicting the least recently used entry if full
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::card_set::CardSet;
+    /// # use naipe::common::eval_cache::EvalCache;
+    /// use std::num::NonZeroUsize;
+    /// let set = |rank| -> CardSet { [Card::new(Suit::Heart, rank)].into_iter().collect() };
+    /// let (ace, king, queen) = (set(Rank::Ace), set(Rank::King), set(Rank::Queen));
+    /// let mut cache = EvalCache::new(NonZeroUsize::new(2).unwrap());
+    /// cache.insert(ace, 14);
+    /// cache.insert(king, 13);
+    /// // Using the ace leaves the king least recently used
+    /// assert_eq!(cache.get(ace), Some(14));
+    /// cache.insert(queen, 12);
+    /// assert_eq!(cache.get(king), None);
+    /// assert_eq!((cache.get(ace), cache.get(queen)), (Some(14), Some(12)));
+    /// ```
+    pub fn insert(&mut self, set: CardSet, value: V) {
+        if self.entries.len() >= self.capacity.get() && !self.entries.contains_key(&set) {
+            self.evict_oldest();
+        }
+        let clock = self.clock + 1;
+        self.entries.insert(set, (value, clock));
+        self.touch(set, clock);
+    }
+
+    /// Queues a use of the set at the given generation, dropping stale keys once they outnumber
+    /// the entries
+    fn touch(&mut self, set: CardSet, clock: u64) {
+        self.clock = clock;
+        self.order.push_back((set, clock));
+        if self.order.len() > 2 * self.capacity.get() {
+            let entries = &self.entries;
+            self.order
+                .retain(|(set, used)| entries.get(set).is_some_and(|(_, last)| last == used));
+        }
+    }
+
+    /// Removes the least recently used entry, skipping keys queued by earlier uses
+    fn evict_oldest(&mut self) {
+        while let Some((set, used)) = self.order.pop_front() {
+            if self
+                .entries
+                .get(&set)
+                .is_some_and(|(_, last_used)| *last_used == used)
+            {
+                self.entries.remove(&set);
+                return;
+            }
+        }
+    }
+
+    /// Gets the cached evaluation for the set, computing and storing it on a miss
+    ///
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::card_set::CardSet;
+    /// # use naipe::common::eval_cache::EvalCache;
+    /// use std::num::NonZeroUsize;
+    /// let mut cache = EvalCache::new(NonZeroUsize::new(1).unwrap());
+    /// let ace: CardSet = [Card::new(Suit::Spade, Rank::Ace)].into_iter().collect();
+    /// let king: CardSet = [Card::new(Suit::Spade, Rank::King)].into_iter().collect();
+    /// cache.get_or_insert_with(ace, |_| 14);
+    /// cache.get_or_insert_with(king, |_| 13);
+    /// // The ace evaluation was evicted to make room for the king
+    /// assert_eq!(cache.get(ace), None);
+    /// assert_eq!(cache.get(king), Some(13));
+    /// ```
+    pub fn get_or_insert_with<F: FnOnce(CardSet) -> V>(&mut self, set: CardSet, evaluate: F) -> V {
+        if let Some(value) = self.get(set) {
+            self.hits += 1;
+            return value;
+        }
+        self.misses += 1;
+        let value = evaluate(set);
+        self.insert(set, value.clone());
+        value
+    }
+
+    /// Gets the number of evaluations currently cached
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Finds if the cache has no evaluations stored
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gets the maximum number of evaluations this cache holds
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    /// Gets how many lookups through [`EvalCache::get_or_insert_with`] were served from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Gets how many lookups through [`EvalCache::get_or_insert_with`] had to be evaluated
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Removes every cached evaluation, keeping hit and miss counts
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
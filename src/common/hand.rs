@@ -1,10 +1,20 @@
 //! An implementation of a hand to store cards
 
-use std::fmt::{self, Display, Formatter};
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    ops::Index,
+};
 
-use super::card::Card;
+use super::{
+    card::{parse_cards, Card, ParseCardError, Rank, Suit},
+    order::CardOrder,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Hand {
     cards: Vec<Card>,
 }
@@ -15,11 +25,173 @@ impl Hand {
         Hand::default()
     }
 
+    /// Creates a hand from cards written in text, such as `"AS KH 9c"`
+    ///
+    /// Cards are separated by whitespace or commas, and the last card listed is the top card
+    /// # Errors
+    /// [`ParseCardError`] if any card could not be parsed
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::hand::Hand;
+    /// let mut hand = Hand::from_notation("AS KH 9c").unwrap();
+    /// assert_eq!(hand.len(), 3);
+    /// assert_eq!(hand.pop(), Some(Card::new(Suit::Club, Rank::Nine)));
+    ///
+    /// // Displayed hands can be read back in
+    /// assert_eq!(Hand::from_notation(&hand.to_string()), Ok(hand));
+    /// assert!(Hand::from_notation("AS 1H").is_err());
+    /// ```
+    pub fn from_notation(notation: &str) -> Result<Hand, ParseCardError> {
+        Ok(Hand {
+            cards: parse_cards(notation)?,
+        })
+    }
+
     /// Pops off the top card from the hand
     pub fn pop(&mut self) -> Option<Card> {
         self.cards.pop()
     }
 
+    /// Adds a card to the top of the hand
+    pub fn push(&mut self, card: Card) {
+        self.cards.push(card);
+    }
+
+    /// Gets the top card of the hand without removing it
+    pub fn peek(&self) -> Option<Card> {
+        self.cards.last().copied()
+    }
+
+    /// Gets up to the given number of cards from the top of the hand without removing them, in
+    /// the order they would be popped
+    /// # Examples
+    /// ```
+    /// # use naipe::common::hand::Hand;
+    /// let mut hand = Hand::from_notation("AS KH 9c").unwrap();
+    /// let next = hand.peek_n(2);
+    /// assert_eq!(next, vec!["9c".parse().unwrap(), "KH".parse().unwrap()]);
+    /// assert_eq!(hand.pop(), Some(next[0]));
+    /// assert_eq!(hand.peek_n(5).len(), 2);
+    /// ```
+    pub fn peek_n(&self, n: usize) -> Vec<Card> {
+        self.cards.iter().rev().take(n).copied().collect()
+    }
+
+    /// Gets the card at the given position, counting from the bottom of the hand
+    ///
+    /// Hands can also be indexed directly, panicking if there is no such card
+    /// # Examples
+    /// ```
+    /// # use naipe::common::hand::Hand;
+    /// let hand = Hand::from_notation("AS KH 9c").unwrap();
+    /// assert_eq!(hand.get(1), Some("KH".parse().unwrap()));
+    /// assert_eq!(hand[0], "AS".parse().unwrap());
+    /// assert_eq!(hand.get(3), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<Card> {
+        self.cards.get(index).copied()
+    }
+
+    /// Finds if the hand holds the given card
+    pub fn contains(&self, card: Card) -> bool {
+        self.cards.contains(&card)
+    }
+
+    /// Finds the position of the given card, counting from the bottom of the hand
+    pub fn position(&self, card: Card) -> Option<usize> {
+        self.cards.iter().position(|held| *held == card)
+    }
+
+    /// Removes the given card from the hand, keeping the other cards in order
+    ///
+    /// If the hand holds more than one copy, the one closest to the bottom is removed
+    /// # Examples
+    /// ```
+    /// # use naipe::common::hand::Hand;
+    /// let mut hand = Hand::from_notation("AS KH 9c").unwrap();
+    /// assert_eq!(hand.remove("KH".parse().unwrap()), Some("KH".parse().unwrap()));
+    /// assert_eq!(hand.remove("KH".parse().unwrap()), None);
+    /// assert_eq!(hand, Hand::from_notation("AS 9c").unwrap());
+    /// ```
+    pub fn remove(&mut self, card: Card) -> Option<Card> {
+        let index = self.position(card)?;
+        Some(self.cards.remove(index))
+    }
+
+    /// Removes the card at the given position, keeping the other cards in order
+    pub fn remove_at(&mut self, index: usize) -> Option<Card> {
+        (index < self.cards.len()).then(|| self.cards.remove(index))
+    }
+
+    /// Removes every card matching the predicate, returning them in their order in the hand
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Suit;
+    /// # use naipe::common::hand::Hand;
+    /// let mut hand = Hand::from_notation("AS KH 9S 2D").unwrap();
+    /// let spades = hand.take_matching(|card| card.get_suit() == Suit::Spade);
+    /// assert_eq!(spades, vec!["AS".parse().unwrap(), "9S".parse().unwrap()]);
+    /// assert_eq!(hand, Hand::from_notation("KH 2D").unwrap());
+    /// ```
+    pub fn take_matching<F: FnMut(&Card) -> bool>(&mut self, mut predicate: F) -> Vec<Card> {
+        let (taken, kept) = self.cards.iter().partition(|card| predicate(card));
+        self.cards = kept;
+        taken
+    }
+
+    /// Sorts the hand by rank, then by suit, from bottom to top
+    pub fn sort_by_rank(&mut self) {
+        self.cards
+            .sort_by_key(|card| (card.get_rank(), card.get_suit()));
+    }
+
+    /// Sorts the hand by suit, then by rank, from bottom to top
+    /// # Examples
+    /// ```
+    /// # use naipe::common::hand::Hand;
+    /// let mut hand = Hand::from_notation("KH AS 9H 2S").unwrap();
+    /// hand.sort_by_suit();
+    /// assert_eq!(hand, Hand::from_notation("2S AS 9H KH").unwrap());
+    /// hand.sort_by_rank();
+    /// assert_eq!(hand, Hand::from_notation("2S 9H KH AS").unwrap());
+    /// ```
+    pub fn sort_by_suit(&mut self) {
+        self.cards.sort();
+    }
+
+    /// Groups the cards in the hand by suit, keeping their order within each suit
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Suit;
+    /// # use naipe::common::hand::Hand;
+    /// let hand = Hand::from_notation("KH AS 9H").unwrap();
+    /// let suits = hand.group_by_suit();
+    /// assert_eq!(suits[&Suit::Heart].len(), 2);
+    /// assert!(!suits.contains_key(&Suit::Club));
+    /// ```
+    pub fn group_by_suit(&self) -> BTreeMap<Suit, Vec<Card>> {
+        let mut groups: BTreeMap<Suit, Vec<Card>> = BTreeMap::new();
+        for card in &self.cards {
+            groups.entry(card.get_suit()).or_default().push(*card);
+        }
+        groups
+    }
+
+    /// Groups the cards in the hand by rank, keeping their order within each rank
+    pub fn group_by_rank(&self) -> BTreeMap<Rank, Vec<Card>> {
+        let mut groups: BTreeMap<Rank, Vec<Card>> = BTreeMap::new();
+        for card in &self.cards {
+            groups.entry(card.get_rank()).or_default().push(*card);
+        }
+        groups
+    }
+
+    /// Gets the cards in this hand, from bottom to top
+    pub fn as_slice(&self) -> &[Card] {
+        &self.cards
+    }
+
     /// Finds if the hand is empty
     pub fn is_empty(&self) -> bool {
         self.cards.is_empty()
@@ -29,6 +201,19 @@ impl Hand {
     pub fn len(&self) -> usize {
         self.cards.len()
     }
+
+    /// Iterates over the cards in this hand, from bottom to top
+    pub fn iter(&self) -> std::slice::Iter<'_, Card> {
+        self.cards.iter()
+    }
+}
+
+impl Index<usize> for Hand {
+    type Output = Card;
+
+    fn index(&self, index: usize) -> &Card {
+        &self.cards[index]
+    }
 }
 
 impl FromIterator<Card> for Hand {
@@ -52,9 +237,7 @@ impl<'a> IntoIterator for &'a Hand {
     type IntoIter = std::slice::Iter<'a, Card>;
 
     fn into_iter(self) -> Self::IntoIter {
-        // Need to split out into line to get the slice properly
-        let cards = &self.cards;
-        cards.iter()
+        self.iter()
     }
 }
 
@@ -74,3 +257,131 @@ impl Display for Hand {
         write!(f, "[{string}]")
     }
 }
+
+/// A hand that keeps itself sorted as cards are added, for games that always show hands in order
+///
+/// A plain [`Hand`] keeps whatever arrangement the player chose, while a sorted hand places each
+/// card by its [`CardOrder`], lowest first, without re-sorting the whole hand
+/// # Examples
+/// ```
+/// # use naipe::common::hand::{Hand, SortedHand};
+/// # use naipe::common::order::{CardOrder, RankOrder, SuitOrder};
+/// let order = CardOrder::new(RankOrder::ace_high(), SuitOrder::bridge()).suits_first();
+/// let mut hand = SortedHand::new(order);
+/// hand.extend(Hand::from_notation("KH 2S AS 9H").unwrap());
+/// assert_eq!(hand.insert("3C".parse().unwrap()), 0);
+/// assert_eq!(Hand::from(hand), Hand::from_notation("3C 9H KH 2S AS").unwrap());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SavedSortedHand"))]
+pub struct SortedHand {
+    cards: Vec<Card>,
+    order: CardOrder,
+}
+
+impl SortedHand {
+    /// Creates an empty hand, sorted by the given ordering
+    pub fn new(order: CardOrder) -> SortedHand {
+        SortedHand {
+            cards: vec![],
+            order,
+        }
+    }
+
+    /// Adds a card in its place, after any equal cards, returning its position from the bottom
+    pub fn insert(&mut self, card: Card) -> usize {
+        let index = self
+            .cards
+            .partition_point(|held| self.order.compare(*held, card) != Ordering::Greater);
+        self.cards.insert(index, card);
+        index
+    }
+
+    /// Removes one copy of the card from the hand, if held
+    pub fn remove(&mut self, card: Card) -> Option<Card> {
+        let index = self.cards.iter().position(|held| *held == card)?;
+        Some(self.cards.remove(index))
+    }
+
+    /// Finds if the hand holds the given card
+    pub fn contains(&self, card: Card) -> bool {
+        self.cards.contains(&card)
+    }
+
+    /// Gets the ordering the hand is sorted by
+    pub fn order(&self) -> &CardOrder {
+        &self.order
+    }
+
+    /// Gets the cards in this hand, lowest first
+    pub fn as_slice(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Finds if the hand has no cards
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Gets the number of cards in the hand
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Iterates over the cards in this hand, lowest first
+    pub fn iter(&self) -> std::slice::Iter<'_, Card> {
+        self.cards.iter()
+    }
+}
+
+/// A sorted hand as saved, whose cards may not be in order
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SavedSortedHand {
+    cards: Vec<Card>,
+    order: CardOrder,
+}
+
+#[cfg(feature = "serde")]
+impl From<SavedSortedHand> for SortedHand {
+    /// Sorts the saved cards, keeping equal cards in the order they were saved
+    fn from(saved: SavedSortedHand) -> SortedHand {
+        let mut cards = saved.cards;
+        cards.sort_by(|card, other| saved.order.compare(*card, *other));
+        SortedHand {
+            cards,
+            order: saved.order,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a SortedHand {
+    type Item = &'a Card;
+    type IntoIter = std::slice::Iter<'a, Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Extend<Card> for SortedHand {
+    fn extend<T: IntoIterator<Item = Card>>(&mut self, iter: T) {
+        for card in iter {
+            self.insert(card);
+        }
+    }
+}
+
+impl From<SortedHand> for Hand {
+    /// Keeps the cards in order, with the highest card on top
+    fn from(hand: SortedHand) -> Hand {
+        Hand { cards: hand.cards }
+    }
+}
+
+impl Display for SortedHand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Hand::from(self.clone()))
+    }
+}
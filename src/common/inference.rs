@@ -0,0 +1,546 @@
+//! Opponent hand inference for trick-taking games
+//!
+//! Tracks where unseen cards may be from the play history, and estimates the probability
+//! of each opponent holding each card, for use by computer strategies. [`SeenCards`] keeps the
+//! plainer memory of which cards a seat has seen, and where
+
+use thiserror::Error;
+
+use super::{
+    card::{Card, Suit},
+    card_set::{CardSet, CARD_SLOTS},
+};
+
+/// Number of fitting passes used when estimating probabilities
+const FITTING_ROUNDS: usize = 64;
+
+/// Tracks the possible locations of cards hidden in opponents' hands
+///
+/// Seats are indexed from zero, and the observer's own seat should be given a hand size of zero,
+/// as its cards are not part of the unseen pool
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::common::card_set::CardSet;
+/// # use naipe::common::inference::HandInference;
+/// let ace = Card::new(Suit::Spade, Rank::Ace);
+/// let two = Card::new(Suit::Heart, Rank::Two);
+/// let unseen: CardSet = [ace, two].into_iter().collect();
+/// let mut inference = HandInference::new(vec![0, 1, 1], unseen);
+/// // Seat 1 did not follow a spade lead, so seat 2 must hold the ace
+/// inference.mark_void(1, Suit::Spade).unwrap();
+/// let tables = inference.tables();
+/// assert_eq!(tables[2].get(ace), 1.0);
+/// assert_eq!(tables[1].get(two), 1.0);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandInference {
+    unseen: CardSet,
+    held: Vec<CardSet>,
+    excluded: Vec<CardSet>,
+    hand_sizes: Vec<usize>,
+}
+
+impl HandInference {
+    /// Creates a new inference given each seat's hand size and the cards whose location is unknown
+    pub fn new(hand_sizes: Vec<usize>, unseen: CardSet) -> HandInference {
+        let seats = hand_sizes.len();
+        HandInference {
+            unseen,
+            held: vec![CardSet::new(); seats],
+            excluded: vec![CardSet::new(); seats],
+            hand_sizes,
+        }
+    }
+
+    /// Gets the number of seats being tracked
+    pub fn seats(&self) -> usize {
+        self.hand_sizes.len()
+    }
+
+    /// Gets the cards whose location is not yet known
+    pub fn unseen(&self) -> CardSet {
+        self.unseen
+    }
+
+    /// Gets the number of cards still held by the given seat
+    /// # Errors
+    /// [`InferenceError::SeatOutOfRange`] if the seat is not tracked
+    pub fn hand_size(&self, seat: usize) -> Result<usize, InferenceError> {
+        self.check_seat(seat)?;
+        Ok(self.hand_sizes[seat])
+    }
+
+    /// Records a card played by a seat, marking the seat void if it did not follow the led suit
+    /// # Errors
+    /// [`InferenceError::SeatOutOfRange`] if the seat is not tracked,
+    /// [`InferenceError::EmptyHand`] if the seat has no cards left, or
+    /// [`InferenceError::CardNotInPlay`] if the card could not be in the seat's hand
+    pub fn record_play(
+        &mut self,
+        seat: usize,
+        card: Card,
+        led_suit: Option<Suit>,
+    ) -> Result<(), InferenceError> {
+        self.check_seat(seat)?;
+        if self.hand_sizes[seat] == 0 {
+            return Err(InferenceError::EmptyHand);
+        }
+        if !self.held[seat].remove(card) {
+            if !self.unseen.contains(card) || self.excluded[seat].contains(card) {
+                return Err(InferenceError::CardNotInPlay);
+            }
+            self.unseen.remove(card);
+        }
+        self.hand_sizes[seat] -= 1;
+        if let Some(suit) = led_suit.filter(|suit| *suit != card.get_suit()) {
+            self.mark_void(seat, suit)?;
+        }
+        Ok(())
+    }
+
+    /// Records that a card was revealed outside of any hand (e.g. discarded or exposed)
+    pub fn record_seen(&mut self, card: Card) {
+        self.unseen.remove(card);
+    }
+
+    /// Marks a seat as having no cards of the given suit
+    /// # Errors
+    /// [`InferenceError::SeatOutOfRange`] if the seat is not tracked
+    pub fn mark_void(&mut self, seat: usize, suit: Suit) -> Result<(), InferenceError> {
+        self.check_seat(seat)?;
+        self.excluded[seat] = self.excluded[seat].union(CardSet::suit(suit));
+        Ok(())
+    }
+
+    /// Marks a card as certainly held by a seat, such as a card passed to it
+    /// # Errors
+    /// [`InferenceError::SeatOutOfRange`] if the seat is not tracked, or
+    /// [`InferenceError::CardNotInPlay`] if the card's location is already known
+    pub fn mark_held(&mut self, seat: usize, card: Card) -> Result<(), InferenceError> {
+        self.check_seat(seat)?;
+        if !self.unseen.remove(card) {
+            return Err(InferenceError::CardNotInPlay);
+        }
+        self.held[seat].insert(card);
+        Ok(())
+    }
+
+    /// Finds which seats are known to have no cards of the given suit
+    pub fn void_seats(&self, suit: Suit) -> Vec<usize> {
+        let suit_cards = CardSet::suit(suit);
+        (0..self.seats())
+            .filter(|seat| self.excluded[*seat].intersection(suit_cards) == suit_cards)
+            .collect()
+    }
+
+    /// Estimates, for every seat, the probability of holding each card
+    ///
+    /// Cards whose location is forced by known holdings, voids, and hand sizes are certain,
+    /// and the rest are spread as evenly as the constraints allow (fitted by iterative
+    /// proportional scaling)
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::card_set::CardSet;
+    /// # use naipe::common::inference::HandInference;
+    /// // Three opponents share the 39 cards the observer does not hold
+    /// let unseen = CardSet::full().difference(CardSet::suit(Suit::Club));
+    /// let mut inference = HandInference::new(vec![0, 13, 13, 13], unseen);
+    /// let queen = Card::new(Suit::Spade, Rank::Queen);
+    /// let tables = inference.tables();
+    /// assert!((tables[1].get(queen) - 1.0 / 3.0).abs() < 1e-9);
+    ///
+    /// // Once seat 3 shows out of spades, the queen is split between seats 1 and 2
+    /// inference.mark_void(3, Suit::Spade).unwrap();
+    /// let tables = inference.tables();
+    /// assert_eq!(tables[3].get(queen), 0.0);
+    /// assert!((tables[1].get(queen) - 0.5).abs() < 1e-9);
+    /// assert!((tables[3].expected_suit_length(Suit::Heart) - 6.5).abs() < 1e-6);
+    /// ```
+    pub fn tables(&self) -> Vec<ProbabilityTable> {
+        let resolved = self.resolved();
+        let seats = self.seats();
+        let mut weights = vec![[0.0_f64; CARD_SLOTS]; seats];
+        for (seat, seat_weights) in weights.iter_mut().enumerate() {
+            for card in resolved.held[seat] {
+                seat_weights[CardSet::index(card)] = 1.0;
+            }
+            if resolved.free_slots(seat) > 0 {
+                for card in resolved.unseen.difference(resolved.excluded[seat]) {
+                    seat_weights[CardSet::index(card)] = 1.0;
+                }
+            }
+        }
+
+        for _ in 0..FITTING_ROUNDS {
+            for (seat, seat_weights) in weights.iter_mut().enumerate() {
+                let total: f64 = seat_weights.iter().sum();
+                if total > 0.0 {
+                    #[allow(clippy::cast_precision_loss)]
+                    let scale = self.hand_sizes[seat] as f64 / total;
+                    for weight in seat_weights.iter_mut() {
+                        *weight *= scale;
+                    }
+                }
+            }
+            normalize_cards(&mut weights);
+        }
+
+        weights
+            .into_iter()
+            .map(|probabilities| ProbabilityTable { probabilities })
+            .collect()
+    }
+
+    /// Copies this inference, placing every card whose location is forced by the constraints
+    fn resolved(&self) -> HandInference {
+        let mut resolved = self.clone();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for card in resolved.unseen {
+                let mut candidates = (0..resolved.seats()).filter(|seat| {
+                    resolved.free_slots(*seat) > 0 && !resolved.excluded[*seat].contains(card)
+                });
+                if let (Some(seat), None) = (candidates.next(), candidates.next()) {
+                    resolved.unseen.remove(card);
+                    resolved.held[seat].insert(card);
+                    changed = true;
+                }
+            }
+            for seat in 0..resolved.seats() {
+                let possible = resolved.unseen.difference(resolved.excluded[seat]);
+                let free_slots = resolved.free_slots(seat);
+                if free_slots > 0 && possible.len() == free_slots {
+                    resolved.unseen = resolved.unseen.difference(possible);
+                    resolved.held[seat] = resolved.held[seat].union(possible);
+                    changed = true;
+                }
+            }
+        }
+        resolved
+    }
+
+    /// Gets how many of a seat's cards are not yet known
+    fn free_slots(&self, seat: usize) -> usize {
+        self.hand_sizes[seat].saturating_sub(self.held[seat].len())
+    }
+
+    fn check_seat(&self, seat: usize) -> Result<(), InferenceError> {
+        if seat < self.seats() {
+            Ok(())
+        } else {
+            Err(InferenceError::SeatOutOfRange)
+        }
+    }
+}
+
+/// Scales each card's weights across seats to sum to one
+fn normalize_cards(weights: &mut [[f64; CARD_SLOTS]]) {
+    for index in 0..CARD_SLOTS {
+        let total: f64 = weights.iter().map(|seat_weights| seat_weights[index]).sum();
+        if total > 0.0 {
+            for seat_weights in weights.iter_mut() {
+                seat_weights[index] /= total;
+            }
+        }
+    }
+}
+
+/// The estimated probability of a single seat holding each card
+///
+/// Jokers have their own entries, so decks with jokers can be inferred like any other
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::common::card_set::CardSet;
+/// # use naipe::common::inference::HandInference;
+/// let mut unseen = CardSet::full();
+/// unseen.insert(Card::black_joker());
+/// unseen.insert(Card::red_joker());
+/// let inference = HandInference::new(vec![27, 27], unseen);
+/// let tables = inference.tables();
+/// assert!((tables[0].get(Card::black_joker()) - 0.5).abs() < 1e-9);
+/// assert!((tables[1].get(Card::red_joker()) - 0.5).abs() < 1e-9);
+/// assert_eq!(tables[0].get(Card::new(Suit::Club, Rank::Joker)), 0.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProbabilityTable {
+    probabilities: [f64; CARD_SLOTS],
+}
+
+impl ProbabilityTable {
+    /// Gets the probability that the seat holds the given card
+    pub fn get(&self, card: Card) -> f64 {
+        self.probabilities[CardSet::index(card)]
+    }
+
+    /// Gets every card the seat may hold
+    pub fn possible(&self) -> CardSet {
+        self.cards_where(|probability| probability > 0.0)
+    }
+
+    /// Gets every card the seat certainly holds
+    pub fn certain(&self) -> CardSet {
+        self.cards_where(|probability| probability >= 1.0 - f64::EPSILON)
+    }
+
+    /// Gets the expected number of cards of the given suit held by the seat
+    pub fn expected_suit_length(&self, suit: Suit) -> f64 {
+        CardSet::suit(suit).iter().map(|card| self.get(card)).sum()
+    }
+
+    fn cards_where<F: Fn(f64) -> bool>(&self, predicate: F) -> CardSet {
+        (0..CARD_SLOTS)
+            .filter(|index| predicate(self.probabilities[*index]))
+            .map(CardSet::card_at)
+            .collect()
+    }
+}
+
+/// Tracks which seats have shown out of which suits during trick play
+///
+/// Also records each seat's discards (cards played off-suit), which carry signals in many games
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::common::inference::VoidTracker;
+/// let mut voids = VoidTracker::new(4);
+/// voids.record_play(2, Suit::Heart, Card::new(Suit::Club, Rank::Two));
+/// assert!(voids.is_void(2, Suit::Heart));
+/// assert!(!voids.is_void(1, Suit::Heart));
+/// assert_eq!(voids.void_seats(Suit::Heart), vec![2]);
+/// assert_eq!(voids.discards(2), &[Card::new(Suit::Club, Rank::Two)]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VoidTracker {
+    voids: Vec<Vec<Suit>>,
+    discards: Vec<Vec<Card>>,
+}
+
+impl VoidTracker {
+    /// Creates a tracker for the given number of seats, with no known voids
+    pub fn new(seats: usize) -> VoidTracker {
+        VoidTracker {
+            voids: vec![vec![]; seats],
+            discards: vec![vec![]; seats],
+        }
+    }
+
+    /// Records a card played to a trick, marking the seat void if it did not follow the led suit
+    /// # Panics
+    /// If the seat is not tracked
+    pub fn record_play(&mut self, seat: usize, led_suit: Suit, card: Card) {
+        if card.get_suit() == led_suit {
+            return;
+        }
+        self.discards[seat].push(card);
+        self.mark_void(seat, led_suit);
+    }
+
+    /// Marks a seat as void in a suit, as when it is revealed by other means
+    /// # Panics
+    /// If the seat is not tracked
+    pub fn mark_void(&mut self, seat: usize, suit: Suit) {
+        if !self.voids[seat].contains(&suit) {
+            self.voids[seat].push(suit);
+        }
+    }
+
+    /// Finds if a seat is known to have no cards of a suit
+    pub fn is_void(&self, seat: usize, suit: Suit) -> bool {
+        self.voids
+            .get(seat)
+            .is_some_and(|voids| voids.contains(&suit))
+    }
+
+    /// Gets the suits a seat is known to be void in, in the order they were revealed
+    pub fn voids(&self, seat: usize) -> &[Suit] {
+        self.voids.get(seat).map_or(&[], Vec::as_slice)
+    }
+
+    /// Gets the seats known to be void in a suit
+    pub fn void_seats(&self, suit: Suit) -> Vec<usize> {
+        (0..self.voids.len())
+            .filter(|seat| self.is_void(*seat, suit))
+            .collect()
+    }
+
+    /// Gets the cards a seat played off-suit, in play order
+    pub fn discards(&self, seat: usize) -> &[Card] {
+        self.discards.get(seat).map_or(&[], Vec::as_slice)
+    }
+
+    /// Copies every known void into a [`HandInference`] over the same seats
+    /// # Errors
+    /// [`InferenceError::SeatOutOfRange`] if the inference tracks fewer seats
+    pub fn apply_to(&self, inference: &mut HandInference) -> Result<(), InferenceError> {
+        for (seat, voids) in self.voids.iter().enumerate() {
+            for suit in voids {
+                inference.mark_void(seat, *suit)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A card becoming known to a seat
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Reveal {
+    /// The card is in the seat's hand, or otherwise in its keeping
+    Held { seat: usize, card: Card },
+    /// The card was played or discarded, and is no longer in any hand
+    Gone(Card),
+}
+
+/// Game events that may show cards to the seats watching them
+pub trait Revealing {
+    /// Gets the cards this event shows to the observing seat
+    fn reveals(&self, observer: usize) -> Vec<Reveal>;
+}
+
+/// What a seat knows about where a card is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum CardKnowledge {
+    /// The card was last seen with the given seat
+    Held(usize),
+    /// The card was last seen leaving play
+    Gone,
+    /// The card has not been seen
+    Unknown,
+}
+
+/// A seat's memory of every card it has seen, and where each card went
+///
+/// A bot's controller feeds it the events of the game, or the seat's own [`Reveal`]s, and then
+/// asks which cards are certainly held by each seat, certainly gone, or still unknown. Every
+/// card is tracked once, so games dealing from more than one deck can only be partly tracked
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::common::card_set::CardSet;
+/// # use naipe::common::inference::{CardKnowledge, Reveal, SeenCards};
+/// let ace = Card::new(Suit::Spade, Rank::Ace);
+/// let king = Card::new(Suit::Spade, Rank::King);
+/// let mut seen = SeenCards::new(0, 2, CardSet::full());
+/// seen.record(Reveal::Held { seat: 0, card: ace });
+/// seen.record(Reveal::Held { seat: 1, card: king });
+/// seen.record(Reveal::Gone(king));
+/// assert_eq!(seen.knowledge(ace), CardKnowledge::Held(0));
+/// assert_eq!(seen.knowledge(king), CardKnowledge::Gone);
+/// assert!(seen.held(1).is_empty());
+/// assert_eq!(seen.unknown().len(), 50);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeenCards {
+    observer: usize,
+    pool: CardSet,
+    held: Vec<CardSet>,
+    gone: CardSet,
+}
+
+impl SeenCards {
+    /// Creates an empty memory for the observing seat, at a table of the given number of seats
+    /// playing with the given cards
+    pub fn new(observer: usize, seats: usize, pool: CardSet) -> SeenCards {
+        SeenCards {
+            observer,
+            pool,
+            held: vec![CardSet::new(); seats],
+            gone: CardSet::new(),
+        }
+    }
+
+    /// Gets the seat whose memory this is
+    pub fn observer(&self) -> usize {
+        self.observer
+    }
+
+    /// Remembers a card being seen, forgetting wherever it was seen before
+    ///
+    /// Cards outside the pool, and seats outside the table, are ignored
+    pub fn record(&mut self, reveal: Reveal) {
+        let card = match reveal {
+            Reveal::Held { card, .. } | Reveal::Gone(card) => card,
+        };
+        if !self.pool.contains(card) {
+            return;
+        }
+        self.forget(card);
+        match reveal {
+            Reveal::Held { seat, card } => {
+                if let Some(held) = self.held.get_mut(seat) {
+                    held.insert(card);
+                }
+            }
+            Reveal::Gone(card) => {
+                self.gone.insert(card);
+            }
+        }
+    }
+
+    /// Remembers every card the event shows to the observing seat
+    pub fn observe<E: Revealing>(&mut self, event: &E) {
+        for reveal in event.reveals(self.observer) {
+            self.record(reveal);
+        }
+    }
+
+    /// Forgets where a card was seen, as when it is shuffled back into a deck
+    pub fn forget(&mut self, card: Card) {
+        for held in &mut self.held {
+            held.remove(card);
+        }
+        self.gone.remove(card);
+    }
+
+    /// Gets the cards certainly held by a seat
+    pub fn held(&self, seat: usize) -> CardSet {
+        self.held.get(seat).copied().unwrap_or_default()
+    }
+
+    /// Gets the cards certainly out of play
+    pub fn gone(&self) -> CardSet {
+        self.gone
+    }
+
+    /// Gets the cards in the pool that have not been seen
+    pub fn unknown(&self) -> CardSet {
+        self.held
+            .iter()
+            .fold(self.pool.difference(self.gone), |unknown, held| {
+                unknown.difference(*held)
+            })
+    }
+
+    /// Finds what the observing seat knows about a card
+    pub fn knowledge(&self, card: Card) -> CardKnowledge {
+        if self.gone.contains(card) {
+            return CardKnowledge::Gone;
+        }
+        self.held
+            .iter()
+            .position(|held| held.contains(card))
+            .map_or(CardKnowledge::Unknown, CardKnowledge::Held)
+    }
+}
+
+/// Errors related to updating a [`HandInference`]
+#[derive(Copy, Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum InferenceError {
+    #[error("Seat is not tracked by this inference")]
+    SeatOutOfRange,
+    #[error("Seat has no cards left to play")]
+    EmptyHand,
+    #[error("Card cannot be in this seat's hand")]
+    CardNotInPlay,
+}
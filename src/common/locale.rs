@@ -0,0 +1,105 @@
+//! Languages card names and descriptions can be written in
+
+use super::card::Rank;
+
+/// A language for describing cards and hands to players
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Locale {
+    /// Gets the name of a single card of the rank
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Rank;
+    /// # use naipe::common::locale::Locale;
+    /// assert_eq!(Locale::English.rank_name(Rank::Queen), "Queen");
+    /// assert_eq!(Locale::Spanish.rank_name(Rank::Queen), "reina");
+    /// ```
+    pub fn rank_name(&self, rank: Rank) -> &'static str {
+        match self {
+            Locale::English => match rank {
+                Rank::Ace => "Ace",
+                Rank::Two => "Two",
+                Rank::Three => "Three",
+                Rank::Four => "Four",
+                Rank::Five => "Five",
+                Rank::Six => "Six",
+                Rank::Seven => "Seven",
+                Rank::Eight => "Eight",
+                Rank::Nine => "Nine",
+                Rank::Ten => "Ten",
+                Rank::Jack => "Jack",
+                Rank::Queen => "Queen",
+                Rank::King => "King",
+                Rank::Joker => "Joker",
+            },
+            Locale::Spanish => match rank {
+                Rank::Ace => "as",
+                Rank::Two => "dos",
+                Rank::Three => "tres",
+                Rank::Four => "cuatro",
+                Rank::Five => "cinco",
+                Rank::Six => "seis",
+                Rank::Seven => "siete",
+                Rank::Eight => "ocho",
+                Rank::Nine => "nueve",
+                Rank::Ten => "diez",
+                Rank::Jack => "jota",
+                Rank::Queen => "reina",
+                Rank::King => "rey",
+                Rank::Joker => "comodín",
+            },
+        }
+    }
+
+    /// Gets the name of several cards of the rank
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Rank;
+    /// # use naipe::common::locale::Locale;
+    /// assert_eq!(Locale::English.rank_plural(Rank::Six), "Sixes");
+    /// assert_eq!(Locale::Spanish.rank_plural(Rank::King), "reyes");
+    /// ```
+    pub fn rank_plural(&self, rank: Rank) -> &'static str {
+        match self {
+            Locale::English => match rank {
+                Rank::Ace => "Aces",
+                Rank::Two => "Twos",
+                Rank::Three => "Threes",
+                Rank::Four => "Fours",
+                Rank::Five => "Fives",
+                Rank::Six => "Sixes",
+                Rank::Seven => "Sevens",
+                Rank::Eight => "Eights",
+                Rank::Nine => "Nines",
+                Rank::Ten => "Tens",
+                Rank::Jack => "Jacks",
+                Rank::Queen => "Queens",
+                Rank::King => "Kings",
+                Rank::Joker => "Jokers",
+            },
+            Locale::Spanish => match rank {
+                Rank::Ace => "ases",
+                Rank::Two => "doses",
+                Rank::Three => "treses",
+                Rank::Four => "cuatros",
+                Rank::Five => "cincos",
+                Rank::Six => "seises",
+                Rank::Seven => "sietes",
+                Rank::Eight => "ochos",
+                Rank::Nine => "nueves",
+                Rank::Ten => "dieces",
+                Rank::Jack => "jotas",
+                Rank::Queen => "reinas",
+                Rank::King => "reyes",
+                Rank::Joker => "comodines",
+            },
+        }
+    }
+}
@@ -3,5 +3,22 @@
 //! Includes hands, decks, and cards, and utilities around each
 
 pub mod card;
+pub mod card_set;
 pub mod deck;
+pub mod diagram;
+#[cfg(feature = "std")]
+pub mod eval_cache;
 pub mod hand;
+pub mod inference;
+pub mod locale;
+pub mod order;
+pub mod pile;
+pub mod poker;
+// Difficulty ratings always band deals exactly, so fractions are only documented with the feature
+#[cfg_attr(not(feature = "exact-math"), doc(hidden))]
+pub mod ratio;
+#[cfg(feature = "rand")]
+pub mod shoe;
+pub mod shuffle;
+pub mod strength;
+pub mod theme;
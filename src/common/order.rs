@@ -0,0 +1,329 @@
+//! Configurable orderings for ranks, suits, and cards
+//!
+//! The derived orderings on [`Rank`], [`Suit`], and [`Card`] suit many games, but others play
+//! aces low, rank suits differently, or strip ranks out entirely. The derived orderings are also
+//! available here by name, as [`RankOrder::ace_high`], [`SuitOrder::standard`], and
+//! [`CardOrder::standard`], for code that would rather say which order it depends on
+
+use std::cmp::Ordering;
+
+use thiserror::Error;
+
+use super::card::{Card, Rank, Suit};
+
+/// An ordering of ranks, from lowest to highest
+///
+/// Ranks left out of the ordering rank below every rank in it
+/// # Examples
+/// ```
+/// # use naipe::common::card::Rank;
+/// # use naipe::common::order::RankOrder;
+/// use std::cmp::Ordering;
+/// let ace_low = RankOrder::ace_low();
+/// assert_eq!(ace_low.compare(Rank::Ace, Rank::Two), Ordering::Less);
+/// assert!(ace_low.is_directly_after(Rank::Two, Rank::Ace));
+/// assert!(!ace_low.is_directly_after(Rank::Ace, Rank::King));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RankOrder {
+    ranks: Vec<Rank>,
+}
+
+impl RankOrder {
+    /// Creates an ordering from ranks listed lowest first
+    /// # Errors
+    /// [`OrderError::DuplicateRank`] if a rank is listed more than once
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Rank;
+    /// # use naipe::common::order::RankOrder;
+    /// // Belote trumps, where the jack and nine outrank the ace
+    /// let trumps = RankOrder::new(vec![
+    ///     Rank::Seven,
+    ///     Rank::Eight,
+    ///     Rank::Queen,
+    ///     Rank::King,
+    ///     Rank::Ten,
+    ///     Rank::Ace,
+    ///     Rank::Nine,
+    ///     Rank::Jack,
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(trumps.max(Rank::Ace, Rank::Nine), Rank::Nine);
+    /// assert!(RankOrder::new(vec![Rank::Ace, Rank::Ace]).is_err());
+    /// ```
+    pub fn new(ranks: Vec<Rank>) -> Result<RankOrder, OrderError> {
+        for (index, rank) in ranks.iter().enumerate() {
+            if ranks[..index].contains(rank) {
+                return Err(OrderError::DuplicateRank(*rank));
+            }
+        }
+        Ok(RankOrder { ranks })
+    }
+
+    /// Creates the usual ordering, with aces high and jokers above them
+    pub fn ace_high() -> RankOrder {
+        RankOrder {
+            ranks: vec![
+                Rank::Two,
+                Rank::Three,
+                Rank::Four,
+                Rank::Five,
+                Rank::Six,
+                Rank::Seven,
+                Rank::Eight,
+                Rank::Nine,
+                Rank::Ten,
+                Rank::Jack,
+                Rank::Queen,
+                Rank::King,
+                Rank::Ace,
+                Rank::Joker,
+            ],
+        }
+    }
+
+    /// Creates an ordering with aces below twos, and jokers above kings
+    pub fn ace_low() -> RankOrder {
+        let mut ranks = Rank::all_ranks();
+        ranks.push(Rank::Joker);
+        RankOrder { ranks }
+    }
+
+    /// Gets the ranks in this ordering, lowest first
+    pub fn ranks(&self) -> &[Rank] {
+        &self.ranks
+    }
+
+    /// Gets how far up the ordering the rank is, if it is in the ordering
+    pub fn position(&self, rank: Rank) -> Option<usize> {
+        self.ranks.iter().position(|ordered| *ordered == rank)
+    }
+
+    /// Compares two ranks under this ordering
+    pub fn compare(&self, rank: Rank, other: Rank) -> Ordering {
+        self.position(rank).cmp(&self.position(other))
+    }
+
+    /// Gets the higher of two ranks under this ordering, preferring the first if they are equal
+    pub fn max(&self, rank: Rank, other: Rank) -> Rank {
+        if self.compare(other, rank) == Ordering::Greater {
+            other
+        } else {
+            rank
+        }
+    }
+
+    /// Determines if the rank comes directly after the other, with no wrapping around
+    pub fn is_directly_after(&self, rank: Rank, other: Rank) -> bool {
+        match (self.position(rank), self.position(other)) {
+            (Some(position), Some(other_position)) => position == other_position + 1,
+            _ => false,
+        }
+    }
+}
+
+impl Default for RankOrder {
+    fn default() -> RankOrder {
+        RankOrder::ace_high()
+    }
+}
+
+/// A priority of suits, from lowest to highest
+///
+/// Suits left out of the priority rank below every suit in it
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuitOrder {
+    suits: Vec<Suit>,
+}
+
+impl SuitOrder {
+    /// Creates a priority from suits listed lowest first
+    /// # Errors
+    /// [`OrderError::DuplicateSuit`] if a suit is listed more than once
+    pub fn new(suits: Vec<Suit>) -> Result<SuitOrder, OrderError> {
+        for (index, suit) in suits.iter().enumerate() {
+            if suits[..index].contains(suit) {
+                return Err(OrderError::DuplicateSuit(*suit));
+            }
+        }
+        Ok(SuitOrder { suits })
+    }
+
+    /// Creates the bridge priority, of clubs, diamonds, hearts, then spades
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Suit;
+    /// # use naipe::common::order::SuitOrder;
+    /// use std::cmp::Ordering;
+    /// let bridge = SuitOrder::bridge();
+    /// assert_eq!(bridge.compare(Suit::Spade, Suit::Heart), Ordering::Greater);
+    /// assert_eq!(bridge.compare(Suit::Club, Suit::Diamond), Ordering::Less);
+    /// ```
+    pub fn bridge() -> SuitOrder {
+        SuitOrder {
+            suits: vec![Suit::Club, Suit::Diamond, Suit::Heart, Suit::Spade],
+        }
+    }
+
+    /// Creates the priority of the derived ordering on [`Suit`], of spades, clubs, hearts, then
+    /// diamonds
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Suit;
+    /// # use naipe::common::order::SuitOrder;
+    /// let standard = SuitOrder::standard();
+    /// for suit in Suit::all_suits() {
+    ///     for other in Suit::all_suits() {
+    ///         assert_eq!(standard.compare(suit, other), suit.cmp(&other));
+    ///     }
+    /// }
+    /// ```
+    pub fn standard() -> SuitOrder {
+        SuitOrder {
+            suits: Suit::all_suits(),
+        }
+    }
+
+    /// Creates a priority where every suit ranks equally
+    pub fn equal() -> SuitOrder {
+        SuitOrder { suits: vec![] }
+    }
+
+    /// Creates a priority with a single trump suit above the rest, which rank equally
+    pub fn trump(suit: Suit) -> SuitOrder {
+        SuitOrder { suits: vec![suit] }
+    }
+
+    /// Gets the suits in this priority, lowest first
+    pub fn suits(&self) -> &[Suit] {
+        &self.suits
+    }
+
+    /// Compares two suits under this priority
+    pub fn compare(&self, suit: Suit, other: Suit) -> Ordering {
+        let position = |suit| self.suits.iter().position(|ordered| *ordered == suit);
+        position(suit).cmp(&position(other))
+    }
+}
+
+impl Default for SuitOrder {
+    fn default() -> SuitOrder {
+        SuitOrder::equal()
+    }
+}
+
+/// A way of comparing whole cards, by rank and then by suit, or by suit and then by rank
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::common::order::{CardOrder, RankOrder, SuitOrder};
+/// let order = CardOrder::new(RankOrder::ace_low(), SuitOrder::bridge());
+/// let mut cards = vec![
+///     Card::new(Suit::Spade, Rank::Two),
+///     Card::new(Suit::Heart, Rank::Ace),
+///     Card::new(Suit::Club, Rank::Two),
+/// ];
+/// order.sort(&mut cards);
+/// assert_eq!(
+///     cards,
+///     vec![
+///         Card::new(Suit::Heart, Rank::Ace),
+///         Card::new(Suit::Club, Rank::Two),
+///         Card::new(Suit::Spade, Rank::Two),
+///     ]
+/// );
+///
+/// let by_suit = order.suits_first();
+/// assert_eq!(by_suit.max(&cards), Some(Card::new(Suit::Spade, Rank::Two)));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CardOrder {
+    ranks: RankOrder,
+    suits: SuitOrder,
+    suits_first: bool,
+}
+
+impl CardOrder {
+    /// Creates an ordering comparing ranks first, breaking ties by suit
+    pub fn new(ranks: RankOrder, suits: SuitOrder) -> CardOrder {
+        CardOrder {
+            ranks,
+            suits,
+            suits_first: false,
+        }
+    }
+
+    /// Creates the derived ordering on [`Card`], comparing suits first in the standard priority,
+    /// then ranks with aces high
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Card;
+    /// # use naipe::common::order::CardOrder;
+    /// let mut cards = Card::all_cards();
+    /// CardOrder::standard().sort(&mut cards);
+    /// let mut derived = Card::all_cards();
+    /// derived.sort();
+    /// assert_eq!(cards, derived);
+    /// ```
+    pub fn standard() -> CardOrder {
+        CardOrder::new(RankOrder::ace_high(), SuitOrder::standard()).suits_first()
+    }
+
+    /// Changes this ordering to compare suits first, breaking ties by rank
+    #[must_use]
+    pub fn suits_first(mut self) -> CardOrder {
+        self.suits_first = true;
+        self
+    }
+
+    /// Gets the ordering of ranks
+    pub fn rank_order(&self) -> &RankOrder {
+        &self.ranks
+    }
+
+    /// Gets the priority of suits
+    pub fn suit_order(&self) -> &SuitOrder {
+        &self.suits
+    }
+
+    /// Compares two cards under this ordering
+    pub fn compare(&self, card: Card, other: Card) -> Ordering {
+        let ranks = self.ranks.compare(card.get_rank(), other.get_rank());
+        let suits = self.suits.compare(card.get_suit(), other.get_suit());
+        if self.suits_first {
+            suits.then(ranks)
+        } else {
+            ranks.then(suits)
+        }
+    }
+
+    /// Sorts the cards from lowest to highest, keeping equal cards in their original order
+    pub fn sort(&self, cards: &mut [Card]) {
+        cards.sort_by(|card, other| self.compare(*card, *other));
+    }
+
+    /// Gets the highest card, preferring the earliest of equal cards
+    pub fn max(&self, cards: &[Card]) -> Option<Card> {
+        cards.iter().copied().reduce(|best, card| {
+            if self.compare(card, best) == Ordering::Greater {
+                card
+            } else {
+                best
+            }
+        })
+    }
+}
+
+/// Errors related to building orderings
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OrderError {
+    #[error("Rank {0} is listed more than once")]
+    DuplicateRank(Rank),
+    #[error("Suit {0} is listed more than once")]
+    DuplicateSuit(Suit),
+}
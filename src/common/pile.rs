@@ -0,0 +1,160 @@
+//! Piles of cards, over a choice of backing container
+//!
+//! Most piles are only dealt from and added to at the top, which a [`Vec`] does best. Piles that
+//! also take cards at the bottom, such as a hand that wins cards to its bottom, move every card
+//! when backed by a [`Vec`], while a [`VecDeque`] takes them in constant time. Across several
+//! decks, turning a whole pile over from top to bottom is several times faster with a
+//! [`VecDeque`].
+//!
+//! Cards inserted in the middle, such as by a
+//! [`ContinuousShuffler`](super::shoe::ContinuousShuffler), move fewer cards in a [`VecDeque`],
+//! but moving a [`Vec`]'s cards in one block is quick enough that a [`Vec`] still comes out
+//! ahead at shoe sizes, as it does for top-only use.
+//!
+//! Code generic over [`CardPile`] lets callers pick. Run `cargo bench --bench piles` to measure
+//! the difference for a given machine and pile size
+
+use std::collections::VecDeque;
+
+use super::card::Card;
+use crate::sealed::Sealed;
+
+/// A pile of cards with a top and a bottom
+///
+/// Every pile keeps its top card last, as a [`Deck`](super::deck::Deck) does, so piles of any
+/// backing list their cards in the same order. The trait is sealed, and implemented only for
+/// [`Vec`], [`VecDeque`], and [`Deck`](super::deck::Deck)
+/// # Examples
+/// ```
+/// # use naipe::common::card::Card;
+/// # use naipe::common::pile::CardPile;
+/// use std::collections::VecDeque;
+/// fn rotate<P: CardPile>(pile: &mut P) {
+///     if let Some(card) = pile.pop_top() {
+///         pile.push_bottom(card);
+///     }
+/// }
+///
+/// let cards: Vec<Card> = ["2C", "3C", "4C"].iter().map(|card| card.parse().unwrap()).collect();
+/// let mut stack = cards.clone();
+/// let mut queue = VecDeque::from(cards);
+/// rotate(&mut stack);
+/// rotate(&mut queue);
+/// assert_eq!(stack, Vec::from(queue));
+/// assert_eq!(stack.peek_top(), "3C".parse().ok());
+/// ```
+///
+/// Piles of other types cannot be added outside the crate
+/// ```compile_fail
+/// # use naipe::common::card::Card;
+/// # use naipe::common::pile::CardPile;
+/// struct Stack(Vec<Card>);
+///
+/// impl CardPile for Stack {
+///     fn len(&self) -> usize { self.0.len() }
+///     fn peek_top(&self) -> Option<Card> { self.0.last().copied() }
+///     fn push_top(&mut self, card: Card) { self.0.push(card) }
+///     fn pop_top(&mut self) -> Option<Card> { self.0.pop() }
+///     fn push_bottom(&mut self, card: Card) { self.0.insert(0, card) }
+///     fn pop_bottom(&mut self) -> Option<Card> { None }
+///     fn insert(&mut self, _depth: usize, _card: Card) {}
+/// }
+/// ```
+pub trait CardPile: Sealed {
+    /// Gets the number of cards in the pile
+    fn len(&self) -> usize;
+
+    /// Finds if the pile has no cards
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the top card, without removing it
+    fn peek_top(&self) -> Option<Card>;
+
+    /// Adds a card to the top
+    fn push_top(&mut self, card: Card);
+
+    /// Removes the top card
+    fn pop_top(&mut self) -> Option<Card>;
+
+    /// Adds a card to the bottom
+    fn push_bottom(&mut self, card: Card);
+
+    /// Removes the bottom card
+    fn pop_bottom(&mut self) -> Option<Card>;
+
+    /// Inserts a card with the given number of cards above it, or at the bottom if the pile is
+    /// not that deep
+    fn insert(&mut self, depth: usize, card: Card);
+}
+
+impl Sealed for Vec<Card> {}
+
+impl CardPile for Vec<Card> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn peek_top(&self) -> Option<Card> {
+        self.last().copied()
+    }
+
+    fn push_top(&mut self, card: Card) {
+        self.push(card);
+    }
+
+    fn pop_top(&mut self) -> Option<Card> {
+        self.pop()
+    }
+
+    /// Adds a card to the bottom, moving every card in the pile
+    fn push_bottom(&mut self, card: Card) {
+        Vec::insert(self, 0, card);
+    }
+
+    /// Removes the bottom card, moving every card in the pile
+    fn pop_bottom(&mut self) -> Option<Card> {
+        (!Vec::is_empty(self)).then(|| self.remove(0))
+    }
+
+    /// Inserts a card, moving every card above it
+    fn insert(&mut self, depth: usize, card: Card) {
+        let index = Vec::len(self).saturating_sub(depth);
+        Vec::insert(self, index, card);
+    }
+}
+
+impl Sealed for VecDeque<Card> {}
+
+impl CardPile for VecDeque<Card> {
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn peek_top(&self) -> Option<Card> {
+        self.back().copied()
+    }
+
+    fn push_top(&mut self, card: Card) {
+        self.push_back(card);
+    }
+
+    fn pop_top(&mut self) -> Option<Card> {
+        self.pop_back()
+    }
+
+    fn push_bottom(&mut self, card: Card) {
+        self.push_front(card);
+    }
+
+    fn pop_bottom(&mut self) -> Option<Card> {
+        self.pop_front()
+    }
+
+    /// Inserts a card, moving the cards above or below it, whichever are fewer
+    fn insert(&mut self, depth: usize, card: Card) {
+        let index = VecDeque::len(self).saturating_sub(depth);
+        VecDeque::insert(self, index, card);
+    }
+}
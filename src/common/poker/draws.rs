@@ -0,0 +1,293 @@
+//! Draws, outs, and the odds of hitting them, for coaching and bot heuristics
+//!
+//! Given a player's hole cards and the board on the flop or turn, [`DrawAnalysis`] finds the
+//! draws the player holds, the unseen cards that would improve their hand, and how likely one of
+//! them is to come by the river. Analysing many hands against one board can share an
+//! [`EvalCache`] through [`DrawAnalysis::new_cached`], so each hand that could come is evaluated
+//! once
+
+use super::{check_cards, HandCategory, PokerError, PokerHandRank, POKER_HAND_SIZE};
+#[cfg(feature = "std")]
+use crate::common::eval_cache::EvalCache;
+use crate::common::{
+    card::{Card, Rank, Suit},
+    card_set::CardSet,
+};
+
+/// Every rank in order for straights, with the ace both low and high
+const STRAIGHT_RANKS: [Rank; 14] = [
+    Rank::Ace,
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+    Rank::Ace,
+];
+
+/// An unfinished hand, one card away from a straight or flush
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Draw {
+    /// Four cards of the suit, at least one of them a hole card
+    Flush(Suit),
+    /// Two ranks would complete a straight, as with four ranks in a row open at both ends
+    OpenEndedStraight,
+    /// Only one rank would complete a straight, filling a gap or an end closed by the ace
+    Gutshot,
+}
+
+/// The draws and outs of a player's hand on the flop or turn
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::common::poker::draws::{Draw, DrawAnalysis};
+/// let hole = [
+///     Card::new(Suit::Heart, Rank::Nine),
+///     Card::new(Suit::Heart, Rank::Eight),
+/// ];
+/// let flop = [
+///     Card::new(Suit::Heart, Rank::Seven),
+///     Card::new(Suit::Heart, Rank::Two),
+///     Card::new(Suit::Club, Rank::Six),
+/// ];
+/// let analysis = DrawAnalysis::new(&hole, &flop).unwrap();
+/// assert_eq!(analysis.draws(), &[Draw::Flush(Suit::Heart), Draw::OpenEndedStraight]);
+/// // Nine hearts and six more fives or tens
+/// assert_eq!(analysis.outs().len(), 15);
+/// assert!((analysis.hit_probability() - 0.5412).abs() < 0.0001);
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DrawAnalysis {
+    rank: PokerHandRank,
+    draws: Vec<Draw>,
+    outs: CardSet,
+    unseen: usize,
+    cards_to_come: usize,
+}
+
+impl DrawAnalysis {
+    /// Analyses a player's hole cards against a board of three or four cards
+    ///
+    /// An out is an unseen card that would improve the category of the player's hand to two pair
+    /// or better, unless it improves the board's own hand as much, such as a card pairing the
+    /// board
+    /// # Errors
+    /// [`PokerError::WrongCardCount`] with the board's size if it is not three or four cards,
+    /// or with the total if there are not five or six cards in all,
+    /// [`PokerError::DuplicateCard`] if any card appears more than once, or
+    /// [`PokerError::Joker`] if any card is a joker
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank};
+    /// # use naipe::common::poker::draws::{Draw, DrawAnalysis};
+    /// let cards = |notation: &str| -> Vec<Card> {
+    ///     notation.split(' ').map(|card| card.parse().unwrap()).collect()
+    /// };
+    /// // Only a ten fills Broadway
+    /// let analysis = DrawAnalysis::new(&cards("AS KD"), &cards("QH JC 3S")).unwrap();
+    /// assert_eq!(analysis.draws(), &[Draw::Gutshot]);
+    /// let outs: Vec<Card> = analysis.outs().iter().collect();
+    /// assert_eq!(outs.len(), 4);
+    /// assert!(outs.iter().all(|card| card.get_rank() == Rank::Ten));
+    /// ```
+    pub fn new(hole: &[Card], board: &[Card]) -> Result<DrawAnalysis, PokerError> {
+        DrawAnalysis::analyse(hole, board, PokerHandRank::best_of_set)
+    }
+
+    /// Analyses a player's hole cards against a board of three or four cards, as
+    /// [`DrawAnalysis::new`] does, keeping every hand evaluated in the cache
+    ///
+    /// Analysing many hands against the same board, such as every hand in an opponent's range,
+    /// then evaluates the board with each card to come only once
+    /// # Errors
+    /// As for [`DrawAnalysis::new`]
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Card;
+    /// # use naipe::common::eval_cache::EvalCache;
+    /// # use naipe::common::poker::draws::DrawAnalysis;
+    /// use std::num::NonZeroUsize;
+    /// let cards = |notation: &str| -> Vec<Card> {
+    ///     notation.split(' ').map(|card| card.parse().unwrap()).collect()
+    /// };
+    /// let board = cards("QH JC 3S 7D");
+    /// let mut cache = EvalCache::new(NonZeroUsize::new(1_000).unwrap());
+    /// let first = DrawAnalysis::new_cached(&cards("AS KD"), &board, &mut cache).unwrap();
+    /// assert_eq!(first, DrawAnalysis::new(&cards("AS KD"), &board).unwrap());
+    /// let misses = cache.misses();
+    ///
+    /// // The second hand reuses the board's evaluations
+    /// let second = DrawAnalysis::new_cached(&cards("9C 8C"), &board, &mut cache).unwrap();
+    /// assert_eq!(second, DrawAnalysis::new(&cards("9C 8C"), &board).unwrap());
+    /// assert!(cache.hits() > 0);
+    /// assert!(cache.misses() < 2 * misses);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn new_cached(
+        hole: &[Card],
+        board: &[Card],
+        cache: &mut EvalCache<PokerHandRank>,
+    ) -> Result<DrawAnalysis, PokerError> {
+        DrawAnalysis::analyse(hole, board, |cards| {
+            PokerHandRank::best_of_set_cached(cache, cards)
+        })
+    }
+
+    /// Analyses the hand, evaluating the best hand of each set of cards with the given function
+    fn analyse<F>(
+        hole: &[Card],
+        board: &[Card],
+        mut evaluate: F,
+    ) -> Result<DrawAnalysis, PokerError>
+    where
+        F: FnMut(CardSet) -> Result<PokerHandRank, PokerError>,
+    {
+        if !(3..POKER_HAND_SIZE).contains(&board.len()) {
+            return Err(PokerError::WrongCardCount(board.len()));
+        }
+        let known: Vec<Card> = hole.iter().chain(board).copied().collect();
+        if !(POKER_HAND_SIZE..=6).contains(&known.len()) {
+            return Err(PokerError::WrongCardCount(known.len()));
+        }
+        check_cards(&known)?;
+
+        let known_set: CardSet = known.iter().copied().collect();
+        let board_set: CardSet = board.iter().copied().collect();
+        let rank = evaluate(known_set)?;
+        let unseen = CardSet::full().difference(known_set);
+        let outs = unseen
+            .iter()
+            .filter(|card| is_out(&rank, known_set, board_set, *card, &mut evaluate))
+            .collect();
+        Ok(DrawAnalysis {
+            draws: find_draws(&rank, hole, &known),
+            rank,
+            outs,
+            unseen: unseen.len(),
+            cards_to_come: POKER_HAND_SIZE - board.len(),
+        })
+    }
+
+    /// Gets the strength of the player's hand as it stands
+    pub fn rank(&self) -> &PokerHandRank {
+        &self.rank
+    }
+
+    /// Gets the flush draw, then any straight draw, the player holds
+    pub fn draws(&self) -> &[Draw] {
+        &self.draws
+    }
+
+    /// Gets every unseen card that would improve the player's hand
+    pub fn outs(&self) -> CardSet {
+        self.outs
+    }
+
+    /// Gets the number of board cards still to come by the river
+    pub fn cards_to_come(&self) -> usize {
+        self.cards_to_come
+    }
+
+    /// Gets the chance that at least one out comes by the river, treating every card not held
+    /// or on the board as equally likely
+    pub fn hit_probability(&self) -> f64 {
+        let (misses, deals) = self.miss_counts();
+        #[allow(clippy::cast_precision_loss)]
+        let miss_probability = misses as f64 / deals as f64;
+        1.0 - miss_probability
+    }
+
+    /// Gets the exact chance that at least one out comes by the river
+    #[cfg(feature = "exact-math")]
+    pub fn hit_ratio(&self) -> crate::common::ratio::Ratio {
+        let (misses, deals) = self.miss_counts();
+        crate::common::ratio::Ratio::new(deals - misses, deals)
+            .unwrap_or(crate::common::ratio::Ratio::ZERO)
+    }
+
+    /// Counts the ways the remaining cards can come without an out, and the ways they can come
+    fn miss_counts(&self) -> (u64, u64) {
+        let blanks = self.unseen - self.outs.len();
+        (
+            choose(blanks, self.cards_to_come),
+            choose(self.unseen, self.cards_to_come),
+        )
+    }
+}
+
+/// Finds if the card improves the player's hand category to two pair or better, and by more than
+/// it improves the board's
+fn is_out<F>(
+    rank: &PokerHandRank,
+    known: CardSet,
+    board: CardSet,
+    card: Card,
+    evaluate: &mut F,
+) -> bool
+where
+    F: FnMut(CardSet) -> Result<PokerHandRank, PokerError>,
+{
+    let with_card = |mut cards: CardSet| {
+        cards.insert(card);
+        cards
+    };
+    let Ok(improved) = evaluate(with_card(known)) else {
+        return false;
+    };
+    if improved.category() <= rank.category() || improved.category() < HandCategory::TwoPair {
+        return false;
+    }
+    evaluate(with_card(board)).map_or(true, |board_rank| {
+        board_rank.category() < improved.category()
+    })
+}
+
+/// Finds the flush and straight draws in the cards, unless the hand is already that strong
+fn find_draws(rank: &PokerHandRank, hole: &[Card], known: &[Card]) -> Vec<Draw> {
+    let mut draws = vec![];
+    if rank.category() < HandCategory::Flush {
+        let flush_suit = Suit::all_suits().into_iter().find(|suit| {
+            known.iter().filter(|card| card.get_suit() == *suit).count() == POKER_HAND_SIZE - 1
+                && hole.iter().any(|card| card.get_suit() == *suit)
+        });
+        draws.extend(flush_suit.map(Draw::Flush));
+    }
+    if rank.category() < HandCategory::Straight {
+        let held = |rank: &Rank| known.iter().any(|card| card.get_rank() == *rank);
+        let mut completing: Vec<Rank> = STRAIGHT_RANKS
+            .windows(POKER_HAND_SIZE)
+            .filter_map(|window| {
+                let mut missing = window.iter().filter(|rank| !held(rank));
+                match (missing.next(), missing.next()) {
+                    (Some(rank), None) => Some(*rank),
+                    _ => None,
+                }
+            })
+            .collect();
+        completing.sort();
+        completing.dedup();
+        match completing.len() {
+            0 => {}
+            1 => draws.push(Draw::Gutshot),
+            _ => draws.push(Draw::OpenEndedStraight),
+        }
+    }
+    draws
+}
+
+/// Counts the ways to choose some of the items
+fn choose(items: usize, chosen: usize) -> u64 {
+    (0..chosen).fold(1, |ways, index| {
+        ways * items.saturating_sub(index) as u64 / (index + 1) as u64
+    })
+}
@@ -0,0 +1,573 @@
+//! Poker hand evaluation
+//!
+//! Classifies five card hands into the standard categories, and finds the best five card hand
+//! out of larger sets of cards for community card games. Evaluated hands can be described to
+//! players, such as "Full House, Kings over Fours", in any [`Locale`]
+
+pub mod draws;
+pub mod strength;
+
+use std::fmt::{self, Display, Formatter};
+
+use thiserror::Error;
+
+#[cfg(feature = "std")]
+use super::eval_cache::EvalCache;
+
+use super::{
+    card::{Card, Rank},
+    card_set::CardSet,
+    hand::Hand,
+    locale::Locale,
+};
+
+/// The number of cards in a poker hand
+pub const POKER_HAND_SIZE: usize = 5;
+
+/// The category of a poker hand, ordered from weakest to strongest
+#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum HandCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl Display for HandCategory {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            HandCategory::HighCard => write!(f, "High Card"),
+            HandCategory::OnePair => write!(f, "One Pair"),
+            HandCategory::TwoPair => write!(f, "Two Pair"),
+            HandCategory::ThreeOfAKind => write!(f, "Three of a Kind"),
+            HandCategory::Straight => write!(f, "Straight"),
+            HandCategory::Flush => write!(f, "Flush"),
+            HandCategory::FullHouse => write!(f, "Full House"),
+            HandCategory::FourOfAKind => write!(f, "Four of a Kind"),
+            HandCategory::StraightFlush => write!(f, "Straight Flush"),
+        }
+    }
+}
+
+/// What a poker hand is made of, for describing it to players
+/// # Examples
+/// ```
+/// # use naipe::common::card::Rank;
+/// # use naipe::common::locale::Locale;
+/// # use naipe::common::poker::HandDescription;
+/// let full_house = HandDescription::FullHouse {
+///     trips: Rank::King,
+///     pair: Rank::Four,
+/// };
+/// assert_eq!(full_house.to_string(), "Full House, Kings over Fours");
+/// assert_eq!(full_house.localized(Locale::Spanish), "Full, reyes sobre cuatros");
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum HandDescription {
+    HighCard(Rank),
+    OnePair(Rank),
+    TwoPair {
+        high: Rank,
+        low: Rank,
+    },
+    ThreeOfAKind(Rank),
+    Straight {
+        high: Rank,
+    },
+    Flush {
+        high: Rank,
+    },
+    FullHouse {
+        trips: Rank,
+        pair: Rank,
+    },
+    FourOfAKind(Rank),
+    /// A straight flush, which is a royal flush when ace high
+    StraightFlush {
+        high: Rank,
+    },
+}
+
+impl HandDescription {
+    /// Gets the category of the hand described
+    pub fn category(&self) -> HandCategory {
+        match self {
+            HandDescription::HighCard(_) => HandCategory::HighCard,
+            HandDescription::OnePair(_) => HandCategory::OnePair,
+            HandDescription::TwoPair { .. } => HandCategory::TwoPair,
+            HandDescription::ThreeOfAKind(_) => HandCategory::ThreeOfAKind,
+            HandDescription::Straight { .. } => HandCategory::Straight,
+            HandDescription::Flush { .. } => HandCategory::Flush,
+            HandDescription::FullHouse { .. } => HandCategory::FullHouse,
+            HandDescription::FourOfAKind(_) => HandCategory::FourOfAKind,
+            HandDescription::StraightFlush { .. } => HandCategory::StraightFlush,
+        }
+    }
+
+    /// Writes the description in the language of the locale
+    pub fn localized(&self, locale: Locale) -> String {
+        let name = |rank| locale.rank_name(rank);
+        let plural = |rank| locale.rank_plural(rank);
+        match locale {
+            Locale::English => match *self {
+                HandDescription::HighCard(rank) => format!("High Card, {}", name(rank)),
+                HandDescription::OnePair(rank) => format!("Pair of {}", plural(rank)),
+                HandDescription::TwoPair { high, low } => {
+                    format!("Two Pair, {} and {}", plural(high), plural(low))
+                }
+                HandDescription::ThreeOfAKind(rank) => {
+                    format!("Three of a Kind, {}", plural(rank))
+                }
+                HandDescription::Straight { high } => format!("Straight, {} High", name(high)),
+                HandDescription::Flush { high } => format!("Flush, {} High", name(high)),
+                HandDescription::FullHouse { trips, pair } => {
+                    format!("Full House, {} over {}", plural(trips), plural(pair))
+                }
+                HandDescription::FourOfAKind(rank) => format!("Four of a Kind, {}", plural(rank)),
+                HandDescription::StraightFlush { high: Rank::Ace } => "Royal Flush".to_string(),
+                HandDescription::StraightFlush { high } => {
+                    format!("Straight Flush, {} High", name(high))
+                }
+            },
+            Locale::Spanish => match *self {
+                HandDescription::HighCard(rank) => format!("Carta alta, {}", name(rank)),
+                HandDescription::OnePair(rank) => format!("Pareja de {}", plural(rank)),
+                HandDescription::TwoPair { high, low } => {
+                    format!("Doble pareja, {} y {}", plural(high), plural(low))
+                }
+                HandDescription::ThreeOfAKind(rank) => format!("Trío de {}", plural(rank)),
+                HandDescription::Straight { high } => format!("Escalera al {}", name(high)),
+                HandDescription::Flush { high } => format!("Color al {}", name(high)),
+                HandDescription::FullHouse { trips, pair } => {
+                    format!("Full, {} sobre {}", plural(trips), plural(pair))
+                }
+                HandDescription::FourOfAKind(rank) => format!("Póker de {}", plural(rank)),
+                HandDescription::StraightFlush { high: Rank::Ace } => "Escalera real".to_string(),
+                HandDescription::StraightFlush { high } => {
+                    format!("Escalera de color al {}", name(high))
+                }
+            },
+        }
+    }
+}
+
+impl Display for HandDescription {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.localized(Locale::English))
+    }
+}
+
+/// The strength of a five card poker hand
+///
+/// Hands compare first by category, then by the ranks that make up the category,
+/// then by kickers. Suits never break ties.
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::common::poker::{HandCategory, PokerHandRank};
+/// let full_house = [
+///     Card::new(Suit::Spade, Rank::King),
+///     Card::new(Suit::Heart, Rank::King),
+///     Card::new(Suit::Club, Rank::King),
+///     Card::new(Suit::Spade, Rank::Four),
+///     Card::new(Suit::Diamond, Rank::Four),
+/// ];
+/// let flush = [
+///     Card::new(Suit::Heart, Rank::Ace),
+///     Card::new(Suit::Heart, Rank::Jack),
+///     Card::new(Suit::Heart, Rank::Nine),
+///     Card::new(Suit::Heart, Rank::Six),
+///     Card::new(Suit::Heart, Rank::Two),
+/// ];
+/// let full_house = PokerHandRank::evaluate(&full_house).unwrap();
+/// let flush = PokerHandRank::evaluate(&flush).unwrap();
+/// assert_eq!(full_house.category(), HandCategory::FullHouse);
+/// assert_eq!(flush.category(), HandCategory::Flush);
+/// assert!(full_house > flush);
+/// ```
+#[derive(Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PokerHandRank {
+    category: HandCategory,
+    ranks: Vec<Rank>,
+}
+
+impl PokerHandRank {
+    /// Evaluates exactly five cards
+    /// # Errors
+    /// [`PokerError::WrongCardCount`] if there are not exactly five cards,
+    /// [`PokerError::DuplicateCard`] if any card appears more than once, or
+    /// [`PokerError::Joker`] if any card is a joker
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank};
+    /// # use naipe::common::poker::{HandCategory, PokerHandRank};
+    /// let cards = |notation: &str| -> Vec<Card> {
+    ///     notation.split(' ').map(|card| card.parse().unwrap()).collect()
+    /// };
+    /// let royal = PokerHandRank::evaluate(&cards("AS KS QS JS 10S")).unwrap();
+    /// assert_eq!(royal.category(), HandCategory::StraightFlush);
+    /// assert_eq!(royal.ranks(), &[Rank::Ace]);
+    ///
+    /// let broadway = PokerHandRank::evaluate(&cards("AH KD QC JS 10H")).unwrap();
+    /// assert_eq!(broadway.category(), HandCategory::Straight);
+    /// assert_eq!(broadway.ranks(), &[Rank::Ace]);
+    /// ```
+    pub fn evaluate(cards: &[Card]) -> Result<PokerHandRank, PokerError> {
+        if cards.len() != POKER_HAND_SIZE {
+            return Err(PokerError::WrongCardCount(cards.len()));
+        }
+        check_cards(cards)?;
+        Ok(evaluate_five(cards))
+    }
+
+    /// Finds the strongest five card hand that can be made from five to seven cards
+    /// # Errors
+    /// [`PokerError::WrongCardCount`] if there are fewer than five or more than seven cards,
+    /// [`PokerError::DuplicateCard`] if any card appears more than once, or
+    /// [`PokerError::Joker`] if any card is a joker
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::poker::{HandCategory, PokerHandRank};
+    /// let cards = [
+    ///     Card::new(Suit::Spade, Rank::Ace),
+    ///     Card::new(Suit::Heart, Rank::Two),
+    ///     Card::new(Suit::Club, Rank::Three),
+    ///     Card::new(Suit::Diamond, Rank::Four),
+    ///     Card::new(Suit::Spade, Rank::Five),
+    ///     Card::new(Suit::Heart, Rank::King),
+    ///     Card::new(Suit::Club, Rank::King),
+    /// ];
+    /// let best = PokerHandRank::best_of(&cards).unwrap();
+    /// assert_eq!(best.category(), HandCategory::Straight);
+    /// assert_eq!(best.ranks(), &[Rank::Five]);
+    /// ```
+    pub fn best_of(cards: &[Card]) -> Result<PokerHandRank, PokerError> {
+        Ok(PokerHandRank::best_hand(cards)?.rank)
+    }
+
+    /// Finds the strongest five card hand that can be made from five to seven cards, along with
+    /// the five cards that make it
+    ///
+    /// If several combinations are equally strong, the cards of any one of them are given
+    /// # Errors
+    /// As for [`PokerHandRank::best_of`]
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::poker::{HandCategory, PokerHandRank};
+    /// let hole = [
+    ///     Card::new(Suit::Heart, Rank::Ace),
+    ///     Card::new(Suit::Club, Rank::Three),
+    /// ];
+    /// let board = [
+    ///     Card::new(Suit::Heart, Rank::King),
+    ///     Card::new(Suit::Heart, Rank::Nine),
+    ///     Card::new(Suit::Spade, Rank::Nine),
+    ///     Card::new(Suit::Heart, Rank::Four),
+    ///     Card::new(Suit::Heart, Rank::Two),
+    /// ];
+    /// let cards: Vec<Card> = hole.iter().chain(&board).copied().collect();
+    /// let best = PokerHandRank::best_hand(&cards).unwrap();
+    /// assert_eq!(best.rank().category(), HandCategory::Flush);
+    /// assert!(!best.cards().contains(&Card::new(Suit::Spade, Rank::Nine)));
+    /// assert_eq!(best.used_from(&hole), vec![Card::new(Suit::Heart, Rank::Ace)]);
+    /// ```
+    pub fn best_hand(cards: &[Card]) -> Result<BestHand, PokerError> {
+        if !(POKER_HAND_SIZE..=7).contains(&cards.len()) {
+            return Err(PokerError::WrongCardCount(cards.len()));
+        }
+        check_cards(cards)?;
+        Ok(best_five(cards))
+    }
+
+    /// Finds the strongest five card hand that can be made from a set of five to seven cards
+    ///
+    /// [`PokerHandRank::best_of_set_cached`] keeps the results for sets evaluated again
+    /// # Errors
+    /// [`PokerError::WrongCardCount`] if there are fewer than five or more than seven cards, or
+    /// [`PokerError::Joker`] if any card is a joker
+    pub fn best_of_set(cards: CardSet) -> Result<PokerHandRank, PokerError> {
+        let cards: Vec<Card> = cards.iter().collect();
+        Self::best_of(&cards)
+    }
+
+    /// Finds the strongest five card hand that can be made from a set of five to seven cards,
+    /// answering from the cache if the set has been evaluated before
+    ///
+    /// Sets that cannot be evaluated are not cached
+    /// # Errors
+    /// As for [`PokerHandRank::best_of_set`]
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::card_set::CardSet;
+    /// # use naipe::common::eval_cache::EvalCache;
+    /// # use naipe::common::poker::{HandCategory, PokerHandRank};
+    /// use std::num::NonZeroUsize;
+    /// let mut cache = EvalCache::new(NonZeroUsize::new(64).unwrap());
+    /// let cards: CardSet = Suit::all_suits()
+    ///     .into_iter()
+    ///     .map(|suit| Card::new(suit, Rank::Seven))
+    ///     .chain([Card::new(Suit::Club, Rank::Two)])
+    ///     .collect();
+    /// let rank = PokerHandRank::best_of_set_cached(&mut cache, cards).unwrap();
+    /// assert_eq!(rank.category(), HandCategory::FourOfAKind);
+    /// assert_eq!(PokerHandRank::best_of_set_cached(&mut cache, cards), Ok(rank));
+    /// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn best_of_set_cached(
+        cache: &mut EvalCache<PokerHandRank>,
+        cards: CardSet,
+    ) -> Result<PokerHandRank, PokerError> {
+        let listed: Vec<Card> = cards.iter().collect();
+        if !(POKER_HAND_SIZE..=7).contains(&listed.len()) {
+            return Err(PokerError::WrongCardCount(listed.len()));
+        }
+        check_cards(&listed)?;
+        Ok(cache.get_or_insert_with(cards, |_| best_five(&listed).rank))
+    }
+
+    /// Gets the category of this hand
+    pub fn category(&self) -> HandCategory {
+        self.category
+    }
+
+    /// Gets the ranks used to break ties within the category, most significant first
+    ///
+    /// Straights only list their highest card, with a five high straight ranking below a six high one
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::poker::PokerHandRank;
+    /// let pair_of_nines = |kicker| {
+    ///     PokerHandRank::evaluate(&[
+    ///         Card::new(Suit::Spade, Rank::Nine),
+    ///         Card::new(Suit::Heart, Rank::Nine),
+    ///         Card::new(Suit::Club, Rank::Ace),
+    ///         Card::new(Suit::Club, Rank::Seven),
+    ///         Card::new(Suit::Diamond, kicker),
+    ///     ])
+    ///     .unwrap()
+    /// };
+    /// let better = pair_of_nines(Rank::Four);
+    /// let worse = pair_of_nines(Rank::Three);
+    /// assert_eq!(better.ranks(), &[Rank::Nine, Rank::Ace, Rank::Seven, Rank::Four]);
+    /// assert!(better > worse);
+    /// ```
+    pub fn ranks(&self) -> &[Rank] {
+        &self.ranks
+    }
+
+    /// Describes what the hand is made of, naming the ranks that make up its category
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::common::locale::Locale;
+    /// # use naipe::common::poker::{HandDescription, PokerHandRank};
+    /// let two_pair = PokerHandRank::evaluate(&[
+    ///     Card::new(Suit::Spade, Rank::Ace),
+    ///     Card::new(Suit::Club, Rank::Ace),
+    ///     Card::new(Suit::Spade, Rank::Eight),
+    ///     Card::new(Suit::Club, Rank::Eight),
+    ///     Card::new(Suit::Diamond, Rank::Nine),
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(
+    ///     two_pair.description(),
+    ///     HandDescription::TwoPair {
+    ///         high: Rank::Ace,
+    ///         low: Rank::Eight
+    ///     }
+    /// );
+    /// assert_eq!(two_pair.to_string(), "Two Pair, Aces and Eights");
+    /// assert_eq!(
+    ///     two_pair.description().localized(Locale::Spanish),
+    ///     "Doble pareja, ases y ochos"
+    /// );
+    /// ```
+    pub fn description(&self) -> HandDescription {
+        let first = self.ranks[0];
+        // Categories made of two groups always have a second rank
+        let second = self.ranks.get(1).copied().unwrap_or(first);
+        match self.category {
+            HandCategory::HighCard => HandDescription::HighCard(first),
+            HandCategory::OnePair => HandDescription::OnePair(first),
+            HandCategory::TwoPair => HandDescription::TwoPair {
+                high: first,
+                low: second,
+            },
+            HandCategory::ThreeOfAKind => HandDescription::ThreeOfAKind(first),
+            HandCategory::Straight => HandDescription::Straight { high: first },
+            HandCategory::Flush => HandDescription::Flush { high: first },
+            HandCategory::FullHouse => HandDescription::FullHouse {
+                trips: first,
+                pair: second,
+            },
+            HandCategory::FourOfAKind => HandDescription::FourOfAKind(first),
+            HandCategory::StraightFlush => HandDescription::StraightFlush { high: first },
+        }
+    }
+}
+
+impl TryFrom<&Hand> for PokerHandRank {
+    type Error = PokerError;
+
+    /// Evaluates the best five card hand held, from five to seven cards
+    fn try_from(hand: &Hand) -> Result<PokerHandRank, PokerError> {
+        let cards: Vec<Card> = hand.iter().copied().collect();
+        PokerHandRank::best_of(&cards)
+    }
+}
+
+impl Display for PokerHandRank {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+/// The strongest hand found among several cards, and the five cards that make it
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BestHand {
+    rank: PokerHandRank,
+    cards: [Card; POKER_HAND_SIZE],
+}
+
+impl BestHand {
+    /// Gets the strength of the hand
+    pub fn rank(&self) -> &PokerHandRank {
+        &self.rank
+    }
+
+    /// Gets the five cards making the hand, in the order they were given
+    pub fn cards(&self) -> &[Card; POKER_HAND_SIZE] {
+        &self.cards
+    }
+
+    /// Gets which of the given cards, such as a player's hole cards, are part of the hand
+    ///
+    /// Games requiring a number of hole cards to be played, such as Omaha, can check the
+    /// length of the result
+    pub fn used_from(&self, cards: &[Card]) -> Vec<Card> {
+        cards
+            .iter()
+            .filter(|card| self.cards.contains(card))
+            .copied()
+            .collect()
+    }
+}
+
+/// Checks that the cards are all distinct, and that none are jokers
+fn check_cards(cards: &[Card]) -> Result<(), PokerError> {
+    let mut seen = CardSet::new();
+    for card in cards {
+        if card.is_joker() {
+            return Err(PokerError::Joker(*card));
+        }
+        if !seen.insert(*card) {
+            return Err(PokerError::DuplicateCard(*card));
+        }
+    }
+    Ok(())
+}
+
+fn best_five(cards: &[Card]) -> BestHand {
+    // Each mask with five bits set picks out one five card combination
+    (0_u32..1 << cards.len())
+        .filter(|mask| mask.count_ones() as usize == POKER_HAND_SIZE)
+        .map(|mask| {
+            let mut five = [cards[0]; POKER_HAND_SIZE];
+            let picked = cards
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| mask & (1 << index) != 0);
+            for (slot, (_, card)) in five.iter_mut().zip(picked) {
+                *slot = *card;
+            }
+            BestHand {
+                rank: evaluate_five(&five),
+                cards: five,
+            }
+        })
+        .max_by(|a, b| a.rank.cmp(&b.rank))
+        .expect("at least one combination is evaluated")
+}
+
+fn evaluate_five(cards: &[Card]) -> PokerHandRank {
+    // Groups of equal ranks, largest group first, then highest rank first
+    let mut groups: Vec<(usize, Rank)> = Vec::with_capacity(POKER_HAND_SIZE);
+    for card in cards {
+        match groups.iter_mut().find(|(_, rank)| *rank == card.get_rank()) {
+            Some((count, _)) => *count += 1,
+            None => groups.push((1, card.get_rank())),
+        }
+    }
+    groups.sort_by(|a, b| b.cmp(a));
+
+    let ranks: Vec<Rank> = groups.iter().map(|(_, rank)| *rank).collect();
+    let is_flush = cards
+        .iter()
+        .all(|card| card.get_suit() == cards[0].get_suit());
+    let straight_high = straight_high_card(&ranks);
+
+    let (category, ranks) = match (groups[0].0, groups.get(1).map(|group| group.0)) {
+        _ if is_flush && straight_high.is_some() => {
+            (HandCategory::StraightFlush, vec![straight_high.unwrap()])
+        }
+        (4, _) => (HandCategory::FourOfAKind, ranks),
+        (3, Some(2)) => (HandCategory::FullHouse, ranks),
+        _ if is_flush => (HandCategory::Flush, ranks),
+        _ if straight_high.is_some() => (HandCategory::Straight, vec![straight_high.unwrap()]),
+        (3, _) => (HandCategory::ThreeOfAKind, ranks),
+        (2, Some(2)) => (HandCategory::TwoPair, ranks),
+        (2, _) => (HandCategory::OnePair, ranks),
+        _ => (HandCategory::HighCard, ranks),
+    };
+    PokerHandRank { category, ranks }
+}
+
+/// Finds the high card of a straight, given five distinct ranks sorted high to low
+fn straight_high_card(ranks: &[Rank]) -> Option<Rank> {
+    if ranks.len() != POKER_HAND_SIZE {
+        return None;
+    }
+    if ranks
+        .windows(2)
+        .all(|pair| pair[0].is_directly_after(pair[1]))
+    {
+        return Some(ranks[0]);
+    }
+    // Broadway, where the ace plays high
+    if ranks == [Rank::Ace, Rank::King, Rank::Queen, Rank::Jack, Rank::Ten] {
+        return Some(Rank::Ace);
+    }
+    // The wheel, where the ace plays low
+    if ranks == [Rank::Ace, Rank::Five, Rank::Four, Rank::Three, Rank::Two] {
+        return Some(Rank::Five);
+    }
+    None
+}
+
+/// Errors related to evaluating poker hands
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PokerError {
+    #[error("Wrong number of cards to evaluate: {0}")]
+    WrongCardCount(usize),
+    #[error("Card {0} appears more than once")]
+    DuplicateCard(Card),
+    #[error("Jokers are not supported: {0}")]
+    Joker(Card),
+}
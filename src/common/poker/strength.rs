@@ -0,0 +1,91 @@
+//! Strength meters for Texas Hold'em hands
+//!
+//! A hand's current strength is the share of the opponent's possible hole cards it beats on the
+//! board as it stands, counting ties as half. Its potential is the chance of hitting an out by
+//! the river, from [`DrawAnalysis`]
+
+use super::{check_cards, draws::DrawAnalysis, PokerError, PokerHandRank, POKER_HAND_SIZE};
+use crate::common::{
+    card::Card,
+    card_set::CardSet,
+    strength::{StrengthBar, StrengthKind},
+};
+
+/// The number of hole cards each player holds
+const HOLE_CARDS: usize = 2;
+
+/// Rates two hole cards against a board of three to five cards, for a strength meter
+///
+/// The overall strength adds the potential to the current strength, in proportion to the hands
+/// the player is behind, as a rough effective strength. Every card not held or on the board is
+/// treated as equally likely to be held by the opponent or to come
+/// # Errors
+/// [`PokerError::WrongCardCount`] with the hole's size if it is not two cards, or with the
+/// board's size if it is not three to five cards, [`PokerError::DuplicateCard`] if any card
+/// appears more than once, or [`PokerError::Joker`] if any card is a joker
+/// # Examples
+/// ```
+/// # use naipe::common::card::Card;
+/// # use naipe::common::poker::strength::strength;
+/// # use naipe::common::strength::StrengthKind;
+/// let cards = |notation: &str| -> Vec<Card> {
+///     notation.split(' ').map(|card| card.parse().unwrap()).collect()
+/// };
+/// // The nut flush on the river beats every hand but a full house or better
+/// let nuts = strength(&cards("AH KH"), &cards("2H 7H 9H JC 3S")).unwrap();
+/// assert!(nuts.value() > 0.99);
+/// assert_eq!(nuts.component(StrengthKind::Potential), Some(0.0));
+///
+/// // A flush draw with nothing made yet has little current strength, but real potential
+/// let draw = strength(&cards("5H 4H"), &cards("AH KH 9C")).unwrap();
+/// assert!(draw.component(StrengthKind::Current).unwrap() < 0.3);
+/// assert!(draw.component(StrengthKind::Potential).unwrap() > 0.3);
+/// assert!(draw.value() > draw.component(StrengthKind::Current).unwrap());
+/// ```
+pub fn strength(hole: &[Card], board: &[Card]) -> Result<StrengthBar, PokerError> {
+    if hole.len() != HOLE_CARDS {
+        return Err(PokerError::WrongCardCount(hole.len()));
+    }
+    if !(3..=POKER_HAND_SIZE).contains(&board.len()) {
+        return Err(PokerError::WrongCardCount(board.len()));
+    }
+    let current = current_strength(hole, board)?;
+    let potential = if board.len() < POKER_HAND_SIZE {
+        DrawAnalysis::new(hole, board)?.hit_probability()
+    } else {
+        0.0
+    };
+    Ok(StrengthBar::new(
+        current + (1.0 - current) * potential,
+        [
+            (StrengthKind::Current, current),
+            (StrengthKind::Potential, potential),
+        ],
+    ))
+}
+
+/// Finds the share of the opponent's possible hole cards the player's hand beats, counting ties
+/// as half
+fn current_strength(hole: &[Card], board: &[Card]) -> Result<f64, PokerError> {
+    let known: Vec<Card> = hole.iter().chain(board).copied().collect();
+    check_cards(&known)?;
+    let rank = PokerHandRank::best_of(&known)?;
+    let unseen: Vec<Card> = CardSet::full()
+        .difference(known.iter().copied().collect())
+        .iter()
+        .collect();
+    let mut halves = 0_u32;
+    let mut holdings = 0_u32;
+    for (index, first) in unseen.iter().enumerate() {
+        for second in &unseen[index + 1..] {
+            let opponent: Vec<Card> = board.iter().copied().chain([*first, *second]).collect();
+            halves += match rank.cmp(&PokerHandRank::best_of(&opponent)?) {
+                std::cmp::Ordering::Greater => 2,
+                std::cmp::Ordering::Equal => 1,
+                std::cmp::Ordering::Less => 0,
+            };
+            holdings += 1;
+        }
+    }
+    Ok(f64::from(halves) / f64::from(holdings * 2))
+}
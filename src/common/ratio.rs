@@ -0,0 +1,159 @@
+//! Exact non-negative fractions, for scores that must agree across platforms
+//!
+//! Game engines only ever score with integers, but rates and difficulty scores are fractions.
+//! A [`Ratio`] computes them with integer arithmetic alone, so the same inputs always give the
+//! same result, whatever the platform or floating point quirks
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+/// The denominator floating point values are approximated over
+const APPROXIMATION_DENOMINATOR: u128 = 1 << 32;
+
+/// A non-negative fraction, always kept in lowest terms
+/// # Examples
+/// ```
+/// # use naipe::common::ratio::Ratio;
+/// let third = Ratio::new(2, 6).unwrap();
+/// assert_eq!((third.numerator(), third.denominator()), (1, 3));
+/// assert!(third < Ratio::HALF);
+/// assert_eq!(third.to_string(), "1/3");
+/// assert!(Ratio::new(1, 0).is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ratio {
+    numerator: u64,
+    denominator: u64,
+}
+
+impl Ratio {
+    pub const ZERO: Ratio = Ratio {
+        numerator: 0,
+        denominator: 1,
+    };
+    pub const HALF: Ratio = Ratio {
+        numerator: 1,
+        denominator: 2,
+    };
+    pub const ONE: Ratio = Ratio {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// Creates a fraction, or `None` if the denominator is zero
+    pub fn new(numerator: u64, denominator: u64) -> Option<Ratio> {
+        (denominator > 0).then(|| Ratio::from_wide(numerator.into(), denominator.into()))
+    }
+
+    /// Approximates a floating point value to the nearest multiple of 2<sup>-32</sup>, treating
+    /// negative values and `NaN` as zero
+    /// # Examples
+    /// ```
+    /// # use naipe::common::ratio::Ratio;
+    /// assert_eq!(Ratio::approximate(0.75), Ratio::new(3, 4).unwrap());
+    /// assert_eq!(Ratio::approximate(-1.0), Ratio::ZERO);
+    /// ```
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn approximate(value: f64) -> Ratio {
+        if value.is_nan() || value <= 0.0 {
+            return Ratio::ZERO;
+        }
+        // Scaling by a power of two is exact, so only the rounding approximates
+        let scaled = (value.min(f64::from(u32::MAX)) * 2.0_f64.powi(32)).round() as u128;
+        Ratio::from_wide(scaled, APPROXIMATION_DENOMINATOR)
+    }
+
+    /// Gets the numerator, in lowest terms
+    pub fn numerator(&self) -> u64 {
+        self.numerator
+    }
+
+    /// Gets the denominator, in lowest terms
+    pub fn denominator(&self) -> u64 {
+        self.denominator
+    }
+
+    /// Converts the fraction to the nearest floating point value, for display
+    #[allow(clippy::cast_precision_loss)]
+    pub fn to_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Gets the mean of the fractions, or `None` if there are none
+    ///
+    /// Should the exact mean not fit in 64 bit terms, it is rounded the same way on every platform
+    /// # Examples
+    /// ```
+    /// # use naipe::common::ratio::Ratio;
+    /// let values = [Ratio::ONE, Ratio::new(1, 3).unwrap(), Ratio::ZERO];
+    /// assert_eq!(Ratio::mean(&values), Ratio::new(4, 9));
+    /// assert_eq!(Ratio::mean(&[]), None);
+    /// ```
+    pub fn mean(values: &[Ratio]) -> Option<Ratio> {
+        let (first, rest) = values.split_first()?;
+        let sum = rest.iter().fold(*first, |sum, value| sum.add(*value));
+        let count = u128::try_from(values.len()).unwrap_or(u128::MAX);
+        Some(Ratio::from_wide(
+            sum.numerator.into(),
+            u128::from(sum.denominator).saturating_mul(count),
+        ))
+    }
+
+    /// Adds two fractions, rounding as for [`Ratio::mean`]
+    fn add(self, other: Ratio) -> Ratio {
+        let mut left = u128::from(self.numerator) * u128::from(other.denominator);
+        let mut right = u128::from(other.numerator) * u128::from(self.denominator);
+        let mut denominator = u128::from(self.denominator) * u128::from(other.denominator);
+        while left.checked_add(right).is_none() {
+            left >>= 1;
+            right >>= 1;
+            denominator >>= 1;
+        }
+        Ratio::from_wide(left + right, denominator)
+    }
+
+    /// Creates a fraction from wide terms, halving both until they fit
+    fn from_wide(mut numerator: u128, mut denominator: u128) -> Ratio {
+        let divisor = gcd(numerator, denominator);
+        numerator /= divisor;
+        denominator /= divisor;
+        while numerator > u128::from(u64::MAX) || denominator > u128::from(u64::MAX) {
+            numerator >>= 1;
+            denominator = (denominator >> 1).max(1);
+        }
+        let divisor = gcd(numerator, denominator);
+        Ratio {
+            numerator: u64::try_from(numerator / divisor).unwrap_or(u64::MAX),
+            denominator: u64::try_from(denominator / divisor).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Ratio) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Ratio) -> Ordering {
+        let left = u128::from(self.numerator) * u128::from(other.denominator);
+        let right = u128::from(other.numerator) * u128::from(self.denominator);
+        left.cmp(&right)
+    }
+}
+
+impl Display for Ratio {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+/// Finds the greatest common divisor, treating a zero divisor as one
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
@@ -0,0 +1,101 @@
+//! Models of the shoes and machines cards are dealt from
+//!
+//! A standard shoe is a shuffled [`Deck`](super::deck::Deck), reshuffled according to a
+//! [`ShufflePolicy`](super::shuffle::ShufflePolicy). A [`ContinuousShuffler`] instead takes
+//! cards back after every hand, mixing them in below a small buffer of cards ready to deal
+
+use rand::Rng;
+
+use super::{card::Card, pile::CardPile};
+
+/// A continuous shuffling machine (CSM), returning used cards to the shoe after every hand
+///
+/// The machine keeps a buffer of cards ready to be dealt from the top of the shoe. Returned cards
+/// are dropped into random slots below the buffer, so they can never come out before the
+/// buffered cards, but are otherwise well mixed. With no cut card, the count never runs deep,
+/// which is what the machine is for.
+/// # Examples
+/// ```
+/// # use naipe::common::deck::Deck;
+/// # use naipe::common::shoe::ContinuousShuffler;
+/// use rand::{rngs::StdRng, SeedableRng};
+/// let mut rng = StdRng::seed_from_u64(3);
+/// let mut shoe = Deck::default();
+/// shoe.shuffle(&mut rng);
+/// let mut unloaded = shoe.clone();
+///
+/// let machine = ContinuousShuffler::new(5);
+/// machine.load(&mut shoe, Deck::from_notation("AS AH").unwrap(), &mut rng);
+/// assert_eq!(shoe.len(), 54);
+/// for _ in 0..machine.buffer() {
+///     assert_eq!(shoe.draw(), unloaded.draw());
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContinuousShuffler {
+    buffer: usize,
+}
+
+impl ContinuousShuffler {
+    /// Creates a machine keeping the given number of cards ready to deal
+    pub fn new(buffer: usize) -> ContinuousShuffler {
+        ContinuousShuffler { buffer }
+    }
+
+    /// Gets the number of cards kept ready to deal
+    pub fn buffer(&self) -> usize {
+        self.buffer
+    }
+
+    /// Returns used cards to the shoe, each at a random depth below the buffer
+    ///
+    /// If the shoe holds no more than the buffer, the cards go to the bottom in a random order.
+    /// Any [`CardPile`] may be used as the shoe, though a [`Deck`](super::deck::Deck) is as fast
+    /// as any
+    pub fn load<P: CardPile, R: Rng + ?Sized>(
+        &self,
+        shoe: &mut P,
+        cards: impl IntoIterator<Item = Card>,
+        rng: &mut R,
+    ) {
+        self.load_by(shoe, cards, |depths| rng.gen_range(0..depths));
+    }
+
+    /// Returns used cards to the shoe as [`ContinuousShuffler::load`] does, choosing the depth of
+    /// each card with the given function, from the number of depths below the buffer it may go
+    ///
+    /// Choosing uniformly at random draws the same numbers as [`ContinuousShuffler::load`], so
+    /// each choice may be recorded, such as for an audit
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::Deck;
+    /// # use naipe::common::shoe::ContinuousShuffler;
+    /// use rand::{rngs::StdRng, Rng, SeedableRng};
+    /// let machine = ContinuousShuffler::new(2);
+    /// let (mut shoe, mut chosen) = (Deck::default(), Deck::default());
+    /// let returned = Deck::from_notation("AS AH 3C").unwrap();
+    /// machine.load(&mut shoe, returned.clone(), &mut StdRng::seed_from_u64(1));
+    ///
+    /// let mut rng = StdRng::seed_from_u64(1);
+    /// let mut choices = vec![];
+    /// machine.load_by(&mut chosen, returned, |depths| {
+    ///     choices.push(depths);
+    ///     rng.gen_range(0..depths)
+    /// });
+    /// assert_eq!(chosen, shoe);
+    /// assert_eq!(choices, vec![51, 52, 53]);
+    /// ```
+    pub fn load_by<P: CardPile, F: FnMut(usize) -> usize>(
+        &self,
+        shoe: &mut P,
+        cards: impl IntoIterator<Item = Card>,
+        mut choose: F,
+    ) {
+        for card in cards {
+            let shallowest = self.buffer.min(shoe.len());
+            let depth = shallowest + choose(shoe.len() - shallowest + 1);
+            shoe.insert(depth.min(shoe.len()), card);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Policies for when a game gathers its cards and reshuffles
+//!
+//! Casinos differ in how often they shuffle, from every hand to deep into a shoe. Games take a
+//! [`ShufflePolicy`] instead of hardcoding one, so simulations can compare procedures
+
+/// When a dealing shoe or deck is reshuffled between hands
+///
+/// Whatever the policy, a game still reshuffles its discards if it runs out of cards mid-hand
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ShufflePolicy {
+    /// Every card is gathered and reshuffled before each hand
+    BetweenHands,
+    /// A cut card is placed after the given percentage of the cards, and the cards are
+    /// reshuffled before the next hand once it is reached
+    CutCard { penetration: u8 },
+    /// Cards are only reshuffled when the game is asked to, or when they run out
+    OnDemand,
+    /// Used cards are returned after every hand to a
+    /// [`ContinuousShuffler`](super::shoe::ContinuousShuffler) keeping the given number of
+    /// cards ready to deal, so the cards are never gathered and reshuffled
+    Continuous { buffer: u8 },
+}
+
+impl ShufflePolicy {
+    /// Finds if the cards should be reshuffled before the next hand, with some of them left
+    /// undealt
+    /// # Examples
+    /// ```
+    /// # use naipe::common::shuffle::ShufflePolicy;
+    /// let policy = ShufflePolicy::CutCard { penetration: 75 };
+    /// assert!(!policy.should_reshuffle(78, 312));
+    /// assert!(policy.should_reshuffle(77, 312));
+    /// assert!(ShufflePolicy::BetweenHands.should_reshuffle(312, 312));
+    /// assert!(!ShufflePolicy::OnDemand.should_reshuffle(1, 312));
+    /// ```
+    pub fn should_reshuffle(&self, remaining: usize, total: usize) -> bool {
+        match self {
+            ShufflePolicy::BetweenHands => true,
+            ShufflePolicy::CutCard { penetration } => {
+                let behind_cut = 100 - usize::from((*penetration).min(100));
+                remaining * 100 < total * behind_cut
+            }
+            ShufflePolicy::OnDemand | ShufflePolicy::Continuous { .. } => false,
+        }
+    }
+}
+
+impl Default for ShufflePolicy {
+    /// A cut card three quarters of the way through
+    fn default() -> ShufflePolicy {
+        ShufflePolicy::CutCard { penetration: 75 }
+    }
+}
@@ -0,0 +1,111 @@
+//! Normalized hand strength, for drawing strength meters
+//!
+//! Analysis of a hand is summed up as a [`StrengthBar`]: an overall strength from 0.0 to 1.0,
+//! and the components it was built from on the same scale, so frontends can draw a meter for any
+//! supported game without evaluating hands themselves. Poker hands are rated by
+//! [`poker::strength`](super::poker::strength), and Blackjack hands by `BlackjackView::strength`
+
+use std::fmt::{self, Display, Formatter};
+
+/// A part of a hand's strength
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum StrengthKind {
+    /// How the hand stands now
+    Current,
+    /// How likely the hand is to improve, or to survive taking another card
+    Potential,
+}
+
+impl Display for StrengthKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            StrengthKind::Current => write!(f, "current"),
+            StrengthKind::Potential => write!(f, "potential"),
+        }
+    }
+}
+
+/// A single component of a [`StrengthBar`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StrengthComponent {
+    pub kind: StrengthKind,
+    /// The component's strength, from 0.0 to 1.0
+    pub value: f64,
+}
+
+/// The strength of a hand, from 0.0 for hopeless to 1.0 for unbeatable, with its components
+/// # Examples
+/// ```
+/// # use naipe::common::strength::{StrengthBar, StrengthKind};
+/// let bar = StrengthBar::new(1.2, [(StrengthKind::Current, 0.5), (StrengthKind::Potential, -1.0)]);
+/// assert_eq!(bar.value(), 1.0);
+/// assert_eq!(bar.component(StrengthKind::Current), Some(0.5));
+/// assert_eq!(bar.component(StrengthKind::Potential), Some(0.0));
+/// assert_eq!(bar.to_string(), "100% (current 50%, potential 0%)");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StrengthBar {
+    value: f64,
+    components: Vec<StrengthComponent>,
+}
+
+impl StrengthBar {
+    /// Creates a bar from an overall strength and its components, clamping each to 0.0 to 1.0
+    pub fn new(
+        value: f64,
+        components: impl IntoIterator<Item = (StrengthKind, f64)>,
+    ) -> StrengthBar {
+        StrengthBar {
+            value: value.clamp(0.0, 1.0),
+            components: components
+                .into_iter()
+                .map(|(kind, value)| StrengthComponent {
+                    kind,
+                    value: value.clamp(0.0, 1.0),
+                })
+                .collect(),
+        }
+    }
+
+    /// Gets the overall strength, from 0.0 to 1.0
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Gets every component, in the order the analysis gave them
+    pub fn components(&self) -> &[StrengthComponent] {
+        &self.components
+    }
+
+    /// Gets the strength of a single component, if the analysis gave it
+    pub fn component(&self, kind: StrengthKind) -> Option<f64> {
+        self.components
+            .iter()
+            .find(|component| component.kind == kind)
+            .map(|component| component.value)
+    }
+}
+
+impl Display for StrengthBar {
+    /// Writes the strength and its components as whole percentages
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:.0}%", self.value * 100.0)?;
+        for (index, component) in self.components.iter().enumerate() {
+            let separator = if index == 0 { " (" } else { ", " };
+            write!(
+                f,
+                "{separator}{} {:.0}%",
+                component.kind,
+                component.value * 100.0
+            )?;
+        }
+        if !self.components.is_empty() {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
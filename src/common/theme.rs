@@ -0,0 +1,163 @@
+//! Presentation choices frontends can offer, such as themed card backs and four-color decks
+//!
+//! naipe never draws cards itself. A [`RenderMetadata`] carries a player's choices alongside
+//! game state, so every frontend shows the same deck the same way, and the games stay unaware
+//! of how they are shown
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use super::{
+    card::{Card, Suit},
+    locale::Locale,
+};
+
+/// The ink a suit is printed in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum SuitColor {
+    Black,
+    Red,
+    Green,
+    Blue,
+}
+
+impl Display for SuitColor {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SuitColor::Black => write!(f, "black"),
+            SuitColor::Red => write!(f, "red"),
+            SuitColor::Green => write!(f, "green"),
+            SuitColor::Blue => write!(f, "blue"),
+        }
+    }
+}
+
+/// Identifies a design for the backs of the cards
+///
+/// The identifiers are chosen by frontends, which map them to their own artwork
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct CardBack(String);
+
+impl CardBack {
+    /// The identifier of the back used unless another is chosen
+    pub const DEFAULT_ID: &'static str = "classic";
+
+    /// Creates a card back from its identifier
+    pub fn new(id: impl Into<String>) -> CardBack {
+        CardBack(id.into())
+    }
+
+    /// Gets the identifier of the design
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for CardBack {
+    fn default() -> CardBack {
+        CardBack::new(CardBack::DEFAULT_ID)
+    }
+}
+
+impl Display for CardBack {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// How a deck is shown: its back, and the color of each suit
+///
+/// Suits are red and black unless the four-color mode is on, which prints diamonds blue and
+/// clubs green as many poker sites do. A color chosen for a single suit overrides both
+/// # Examples
+/// ```
+/// # use naipe::common::card::Suit;
+/// # use naipe::common::theme::{CardBack, DeckTheme, SuitColor};
+/// let theme = DeckTheme::default();
+/// assert_eq!(theme.suit_color(Suit::Diamond), SuitColor::Red);
+/// assert_eq!(theme.card_back().id(), "classic");
+///
+/// let theme = DeckTheme::default()
+///     .four_color(true)
+///     .suit_color_override(Suit::Heart, SuitColor::Black)
+///     .back(CardBack::new("casino-green"));
+/// assert_eq!(theme.suit_color(Suit::Diamond), SuitColor::Blue);
+/// assert_eq!(theme.suit_color(Suit::Club), SuitColor::Green);
+/// assert_eq!(theme.suit_color(Suit::Heart), SuitColor::Black);
+/// assert_eq!(theme.card_color("QS".parse().unwrap()), SuitColor::Black);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeckTheme {
+    back: CardBack,
+    four_color: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    suit_colors: BTreeMap<Suit, SuitColor>,
+}
+
+impl DeckTheme {
+    /// Sets the design for the backs of the cards
+    #[must_use]
+    pub fn back(mut self, back: CardBack) -> DeckTheme {
+        self.back = back;
+        self
+    }
+
+    /// Sets whether diamonds are printed blue and clubs green
+    #[must_use]
+    pub fn four_color(mut self, four_color: bool) -> DeckTheme {
+        self.four_color = four_color;
+        self
+    }
+
+    /// Prints a suit in the given color, whatever the color mode
+    #[must_use]
+    pub fn suit_color_override(mut self, suit: Suit, color: SuitColor) -> DeckTheme {
+        self.suit_colors.insert(suit, color);
+        self
+    }
+
+    /// Gets the design for the backs of the cards
+    pub fn card_back(&self) -> &CardBack {
+        &self.back
+    }
+
+    /// Finds if diamonds are printed blue and clubs green
+    pub fn is_four_color(&self) -> bool {
+        self.four_color
+    }
+
+    /// Gets the color a suit is printed in
+    pub fn suit_color(&self, suit: Suit) -> SuitColor {
+        if let Some(color) = self.suit_colors.get(&suit) {
+            return *color;
+        }
+        match suit {
+            Suit::Diamond if self.four_color => SuitColor::Blue,
+            Suit::Club if self.four_color => SuitColor::Green,
+            suit if suit.is_red() => SuitColor::Red,
+            _ => SuitColor::Black,
+        }
+    }
+
+    /// Gets the color a card is printed in, which jokers take from their suit as well
+    pub fn card_color(&self, card: Card) -> SuitColor {
+        self.suit_color(card.get_suit())
+    }
+}
+
+/// Everything a frontend needs to know to show cards the way a player asked
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RenderMetadata {
+    /// The deck's back and suit colors
+    pub theme: DeckTheme,
+    /// The language to name cards and hands in
+    pub locale: Locale,
+}
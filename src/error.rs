@@ -0,0 +1,190 @@
+//! A single error type for applications using several parts of the crate
+//!
+//! Each module keeps its own error type, and every one of them converts into [`Error`], so
+//! code calling into several modules can return [`Result`] and use `?` throughout
+
+use thiserror::Error;
+
+use crate::common::{
+    card::ParseCardError,
+    deck::{DeckDealError, DeckPermutationError},
+    diagram::DealNotationError,
+    inference::InferenceError,
+    order::OrderError,
+    poker::PokerError,
+};
+#[cfg(feature = "blackjack")]
+use crate::games::blackjack::{rules::RuleConflict, table::TableError, BlackjackError};
+#[cfg(feature = "hearts")]
+use crate::games::hearts::HeartsError;
+#[cfg(feature = "memory")]
+use crate::games::memory::MemoryError;
+#[cfg(feature = "solitaire")]
+use crate::games::solitaire::{daily::DailyDealError, SolitaireError};
+#[cfg(feature = "war")]
+use crate::games::war::WarSetupError;
+#[cfg(any(
+    feature = "blackjack",
+    feature = "hearts",
+    feature = "memory",
+    feature = "solitaire",
+    feature = "war"
+))]
+use crate::games::{
+    dynamic::DynGameError, event::ReplayError, preflop::ChartError, registry::RegistryError,
+    scenario::ScenarioError, series::SeriesError, tournament::TournamentError, tricks::TrickError,
+    ClaimError, ConcedeError,
+};
+
+/// Any error from the crate
+/// # Examples
+/// ```
+/// use naipe::common::{
+///     deck::Deck,
+///     poker::{HandCategory, PokerHandRank},
+/// };
+/// fn best_of_deal(notation: &str) -> naipe::Result<PokerHandRank> {
+///     let mut deck = Deck::from_notation(notation)?;
+///     let hand = deck.deal_cards(1, 5)?.remove(0);
+///     Ok(PokerHandRank::best_of(&hand)?)
+/// }
+/// let royal = best_of_deal("AS KS QS JS 10S").unwrap();
+/// assert_eq!(royal.category(), HandCategory::StraightFlush);
+/// assert!(matches!(best_of_deal("AS KS"), Err(naipe::Error::Deal(_))));
+/// assert!(matches!(best_of_deal("AS 1S"), Err(naipe::Error::Card(_))));
+/// ```
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    Card(#[from] ParseCardError),
+    #[error(transparent)]
+    Deal(#[from] DeckDealError),
+    #[error(transparent)]
+    Permutation(#[from] DeckPermutationError),
+    #[error(transparent)]
+    DealNotation(#[from] DealNotationError),
+    #[error(transparent)]
+    Order(#[from] OrderError),
+    #[error(transparent)]
+    Poker(#[from] PokerError),
+    #[error(transparent)]
+    Inference(#[from] InferenceError),
+    #[cfg(any(
+        feature = "blackjack",
+        feature = "hearts",
+        feature = "memory",
+        feature = "solitaire",
+        feature = "war"
+    ))]
+    #[error(transparent)]
+    Trick(#[from] TrickError),
+    #[cfg(any(
+        feature = "blackjack",
+        feature = "hearts",
+        feature = "memory",
+        feature = "solitaire",
+        feature = "war"
+    ))]
+    #[error(transparent)]
+    Concede(#[from] ConcedeError),
+    #[cfg(any(
+        feature = "blackjack",
+        feature = "hearts",
+        feature = "memory",
+        feature = "solitaire",
+        feature = "war"
+    ))]
+    #[error(transparent)]
+    Claim(#[from] ClaimError),
+    #[cfg(any(
+        feature = "blackjack",
+        feature = "hearts",
+        feature = "memory",
+        feature = "solitaire",
+        feature = "war"
+    ))]
+    #[error(transparent)]
+    DynGame(#[from] DynGameError),
+    #[cfg(any(
+        feature = "blackjack",
+        feature = "hearts",
+        feature = "memory",
+        feature = "solitaire",
+        feature = "war"
+    ))]
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+    #[cfg(any(
+        feature = "blackjack",
+        feature = "hearts",
+        feature = "memory",
+        feature = "solitaire",
+        feature = "war"
+    ))]
+    #[error(transparent)]
+    Replay(#[from] ReplayError),
+    #[cfg(any(
+        feature = "blackjack",
+        feature = "hearts",
+        feature = "memory",
+        feature = "solitaire",
+        feature = "war"
+    ))]
+    #[error(transparent)]
+    Scenario(#[from] ScenarioError),
+    #[cfg(any(
+        feature = "blackjack",
+        feature = "hearts",
+        feature = "memory",
+        feature = "solitaire",
+        feature = "war"
+    ))]
+    #[error(transparent)]
+    Series(#[from] SeriesError),
+    #[cfg(any(
+        feature = "blackjack",
+        feature = "hearts",
+        feature = "memory",
+        feature = "solitaire",
+        feature = "war"
+    ))]
+    #[error(transparent)]
+    Tournament(#[from] TournamentError),
+    #[cfg(any(
+        feature = "blackjack",
+        feature = "hearts",
+        feature = "memory",
+        feature = "solitaire",
+        feature = "war"
+    ))]
+    #[error(transparent)]
+    Chart(#[from] ChartError),
+    #[cfg(feature = "blackjack")]
+    #[error(transparent)]
+    Blackjack(#[from] BlackjackError),
+    #[cfg(feature = "blackjack")]
+    #[error(transparent)]
+    Rules(#[from] RuleConflict),
+    #[cfg(feature = "blackjack")]
+    #[error(transparent)]
+    Table(#[from] TableError),
+    #[cfg(feature = "hearts")]
+    #[error(transparent)]
+    Hearts(#[from] HeartsError),
+    #[cfg(feature = "memory")]
+    #[error(transparent)]
+    Memory(#[from] MemoryError),
+    #[cfg(feature = "solitaire")]
+    #[error(transparent)]
+    DailyDeal(#[from] DailyDealError),
+    #[cfg(feature = "solitaire")]
+    #[error(transparent)]
+    Solitaire(#[from] SolitaireError),
+    #[cfg(feature = "war")]
+    #[error(transparent)]
+    WarSetup(#[from] WarSetupError),
+}
+
+/// A result whose error is any error from the crate
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,49 @@
+//! Agents that make decisions for players in a game
+//!
+//! Games implementing [`AgentGame`] ask an [`Agent`] in each seat for decisions, so that seats can
+//! be filled by computer strategies or by interactive input
+
+/// A decision maker for a single seat in a game
+///
+/// Any closure taking a view and returning an action is an agent
+/// # Examples
+/// ```
+/// # use naipe::games::agent::Agent;
+/// let mut always_stand = |_view: &u32| "stand";
+/// assert_eq!(always_stand.choose_action(&17), "stand");
+/// ```
+pub trait Agent<View, Action> {
+    /// Chooses an action given what this seat can see of the game
+    fn choose_action(&mut self, view: &View) -> Action;
+}
+
+impl<View, Action, F: FnMut(&View) -> Action> Agent<View, Action> for F {
+    fn choose_action(&mut self, view: &View) -> Action {
+        self(view)
+    }
+}
+
+/// A game where each seat's decisions are made by an [`Agent`]
+pub trait AgentGame {
+    /// What a single seat is able to see of the game
+    type View;
+    /// A decision made by a seat
+    type Action;
+    type TickOk;
+    type TickError;
+
+    /// Gets the number of seats, and so the number of agents needed to play
+    fn seat_count(&self) -> usize;
+
+    /// Gets what the given seat is able to see of the game
+    fn view(&self, seat: usize) -> Self::View;
+
+    /// Tick the game, advancing play and asking agents for any decisions needed
+    ///
+    /// Agents are given in seat order
+    #[allow(clippy::missing_errors_doc)]
+    fn tick_with_agents(
+        &mut self,
+        agents: &mut [&mut dyn Agent<Self::View, Self::Action>],
+    ) -> Result<Self::TickOk, Self::TickError>;
+}
@@ -0,0 +1,127 @@
+//! Records of where randomness entered a game, for fairness audits
+//!
+//! In audit mode, games and bots record every random decision as a [`RandomnessRecord`], naming
+//! its source along with the seed and stream position of the generator it was drawn from. Anyone
+//! holding a record can re-derive its outcome with [`RandomnessRecord::verify`], showing that it
+//! came from the generator and nowhere else.
+//!
+//! Every game that shuffles can be audited, reporting its records as `Randomness` events, and
+//! seeded games can be created in audit mode with [`AuditedGame`] so their deal is recorded too.
+//! Auditing draws the same random numbers, so an audited game plays out as it would otherwise.
+
+use rand::{Rng, SeedableRng};
+
+use super::{GameRng, SeededGame};
+use crate::common::deck::Deck;
+
+/// A seeded game that can be created in audit mode, recording the deal and every later random
+/// decision as events
+/// # Examples
+/// ```
+/// # use naipe::games::audit::AuditedGame;
+/// # use naipe::games::event::EventSource;
+/// # use naipe::games::hearts::{HeartsEvent, HeartsGame};
+/// # use naipe::games::SeededGame;
+/// let game = HeartsGame::audited_from_seed(4);
+/// let HeartsEvent::Randomness(record) = &game.events()[0] else {
+///     panic!("the deal is recorded");
+/// };
+/// assert!(record.verify());
+/// // The audited game is dealt the same cards
+/// assert_eq!(game.hand(0), HeartsGame::from_seed(4).hand(0));
+/// ```
+pub trait AuditedGame: SeededGame {
+    /// Creates a new game in audit mode, seeding its internal generator from the given one
+    fn audited_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self;
+
+    /// Creates a new game in audit mode from a fixed seed, dealt as [`SeededGame::from_seed`]
+    /// deals it
+    fn audited_from_seed(seed: u64) -> Self {
+        Self::audited_with_rng(&mut GameRng::seed_from_u64(seed))
+    }
+}
+
+/// The result of a random decision
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum RandomOutcome {
+    /// A shuffle, giving the position in the old order of each card in the new order
+    Permutation(Vec<usize>),
+    /// A choice among a number of options, counting from zero
+    Choice { options: usize, chosen: usize },
+}
+
+/// A random decision, and where its randomness came from
+/// # Examples
+/// ```
+/// # use naipe::common::deck::Deck;
+/// # use naipe::games::audit::{RandomOutcome, RandomnessRecord};
+/// # use naipe::games::GameRng;
+/// use rand::SeedableRng;
+/// let mut rng = GameRng::seed_from_u64(8);
+/// let mut deck = Deck::default();
+/// let record = RandomnessRecord::shuffle("deal", &mut deck, &mut rng);
+/// assert_eq!(record.word_position, 0);
+/// assert!(record.verify());
+///
+/// let (chosen, record) = RandomnessRecord::choose("bot", 3, &mut rng);
+/// assert!(record.word_position > 0);
+/// assert_eq!(record.outcome, RandomOutcome::Choice { options: 3, chosen });
+/// assert!(record.verify());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RandomnessRecord {
+    /// What the randomness was used for, such as a deal or a bot's choice
+    pub source: String,
+    /// The seed of the generator drawn from
+    pub seed: [u8; 32],
+    /// How many 32 bit words the generator had produced before the decision
+    pub word_position: u128,
+    pub outcome: RandomOutcome,
+}
+
+impl RandomnessRecord {
+    /// Shuffles the deck, recording the permutation applied
+    pub fn shuffle(source: &str, deck: &mut Deck, rng: &mut GameRng) -> RandomnessRecord {
+        let (seed, word_position) = (rng.get_seed(), rng.get_word_pos());
+        RandomnessRecord {
+            source: source.to_string(),
+            seed,
+            word_position,
+            outcome: RandomOutcome::Permutation(deck.shuffle_permutation(rng)),
+        }
+    }
+
+    /// Chooses one of a number of options, counting from zero, recording the choice
+    /// # Panics
+    /// If there are no options to choose from
+    pub fn choose(source: &str, options: usize, rng: &mut GameRng) -> (usize, RandomnessRecord) {
+        let (seed, word_position) = (rng.get_seed(), rng.get_word_pos());
+        let chosen = rng.gen_range(0..options);
+        let record = RandomnessRecord {
+            source: source.to_string(),
+            seed,
+            word_position,
+            outcome: RandomOutcome::Choice { options, chosen },
+        };
+        (chosen, record)
+    }
+
+    /// Re-derives the outcome from the seed and stream position, checking it matches
+    pub fn verify(&self) -> bool {
+        let mut rng = GameRng::from_seed(self.seed);
+        rng.set_word_pos(self.word_position);
+        match &self.outcome {
+            RandomOutcome::Permutation(permutation) => {
+                let mut derived: Vec<usize> = (0..permutation.len()).collect();
+                rand::seq::SliceRandom::shuffle(derived.as_mut_slice(), &mut rng);
+                derived == *permutation
+            }
+            RandomOutcome::Choice { options, chosen } => {
+                *options > 0 && rng.gen_range(0..*options) == *chosen
+            }
+        }
+    }
+}
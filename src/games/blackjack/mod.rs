@@ -0,0 +1,1177 @@
+//! An implementation of the card game Blackjack
+//!
+//! Each seat plays one hand against the dealer, with decisions made by agents.
+//! House rules are configured through [`rules::BlackjackRules`], and players may buy in and
+//! cash out between rounds through a [`table::BlackjackTable`].
+
+pub mod rules;
+pub mod table;
+
+use std::{
+    cmp::Ordering,
+    mem,
+    num::{NonZeroU16, NonZeroUsize},
+};
+
+use log::debug;
+use rand::{Rng, SeedableRng};
+use thiserror::Error;
+
+use crate::common::{
+    card::{Card, Rank},
+    deck::{Deck, DeckDealError},
+    shoe::ContinuousShuffler,
+    shuffle::ShufflePolicy,
+    strength::{StrengthBar, StrengthKind},
+};
+use crate::games::{
+    agent::{Agent, AgentGame},
+    audit::RandomnessRecord,
+    dynamic::ErasedGame,
+    event::EventSource,
+    info::{GameInfo, GameMetadata, GameTag},
+    GameRng,
+};
+
+use self::rules::{BlackjackPayout, BlackjackRules, RuleConflict};
+
+/// The most seats at a typical table
+const MAX_SEATS: usize = 7;
+
+/// The best total of a hand without going over
+const BLACKJACK: u8 = 21;
+
+/// The total no card can bust, below which a hand has no strength on a meter
+const STRENGTH_FLOOR: u8 = 11;
+
+/// The value of a blackjack hand
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandValue {
+    /// The best total of the hand, counting an ace as 11 when that does not bust
+    pub total: u8,
+    /// Whether an ace is being counted as 11
+    pub soft: bool,
+}
+
+impl HandValue {
+    /// Values a set of cards, counting aces as 1 or 11
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::games::blackjack::HandValue;
+    /// let soft = HandValue::of(&[
+    ///     Card::new(Suit::Spade, Rank::Ace),
+    ///     Card::new(Suit::Heart, Rank::Six),
+    /// ]);
+    /// assert_eq!(soft, HandValue { total: 17, soft: true });
+    ///
+    /// let hard = HandValue::of(&[
+    ///     Card::new(Suit::Spade, Rank::Ace),
+    ///     Card::new(Suit::Heart, Rank::Six),
+    ///     Card::new(Suit::Club, Rank::King),
+    /// ]);
+    /// assert_eq!(hard, HandValue { total: 17, soft: false });
+    /// ```
+    pub fn of(cards: &[Card]) -> HandValue {
+        let hard_total: u8 = cards.iter().map(|card| card_value(card.get_rank())).sum();
+        let has_ace = cards.iter().any(|card| card.get_rank() == Rank::Ace);
+        if has_ace && hard_total + 10 <= BLACKJACK {
+            HandValue {
+                total: hard_total + 10,
+                soft: true,
+            }
+        } else {
+            HandValue {
+                total: hard_total,
+                soft: false,
+            }
+        }
+    }
+
+    /// Finds if the hand has gone over 21
+    pub fn is_bust(&self) -> bool {
+        self.total > BLACKJACK
+    }
+}
+
+/// Gets the value of a card, counting aces as 1
+///
+/// Jokers are never dealt in Blackjack, so they count for nothing
+fn card_value(rank: Rank) -> u8 {
+    match rank {
+        Rank::Ace => 1,
+        Rank::Two => 2,
+        Rank::Three => 3,
+        Rank::Four => 4,
+        Rank::Five => 5,
+        Rank::Six => 6,
+        Rank::Seven => 7,
+        Rank::Eight => 8,
+        Rank::Nine => 9,
+        Rank::Ten | Rank::Jack | Rank::Queen | Rank::King => 10,
+        Rank::Joker => 0,
+    }
+}
+
+/// Checks the rules make a playable game, at a table with room for the seats
+fn validate_table(rules: &BlackjackRules, seats: NonZeroUsize) -> Result<(), RuleConflict> {
+    rules.validate()?;
+    if seats.get() > MAX_SEATS {
+        return Err(RuleConflict::TooManySeats(seats.get()));
+    }
+    Ok(())
+}
+
+/// Finds if the cards are a natural blackjack: 21 with two cards
+pub fn is_blackjack(cards: &[Card]) -> bool {
+    cards.len() == 2 && HandValue::of(cards).total == BLACKJACK
+}
+
+/// A decision made by a player on their hand
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum BlackjackAction {
+    /// Take another card
+    Hit,
+    /// Take no more cards
+    Stand,
+    /// Double the wager, then take exactly one more card
+    Double,
+}
+
+/// What a seat can see of a round of Blackjack
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlackjackView {
+    /// The cards in this seat's hand
+    pub hand: Vec<Card>,
+    /// The value of this seat's hand
+    pub value: HandValue,
+    /// The dealer's face up card, if the round has been dealt
+    pub dealer_upcard: Option<Card>,
+    /// Whether this seat may double down
+    pub can_double: bool,
+    /// This seat's current wager
+    pub wager: u32,
+}
+
+impl BlackjackView {
+    /// Rates this seat's hand for a strength meter
+    ///
+    /// The current strength scales the total from nothing at 11 or under up to full at 21, and a
+    /// bust is worth nothing. The potential is the chance that one more card keeps the hand at 21
+    /// or under, counting every rank as equally likely. Whether to draw is the player's choice,
+    /// so the overall strength is the current strength alone
+    /// # Examples
+    /// ```
+    /// # use naipe::common::strength::StrengthKind;
+    /// # use naipe::games::blackjack::{BlackjackView, HandValue};
+    /// let hand = vec!["10S".parse().unwrap(), "6H".parse().unwrap()];
+    /// let view = BlackjackView {
+    ///     value: HandValue::of(&hand),
+    ///     hand,
+    ///     dealer_upcard: None,
+    ///     can_double: false,
+    ///     wager: 10,
+    /// };
+    /// let bar = view.strength();
+    /// assert!((bar.value() - 0.5).abs() < 1e-9);
+    /// // Ace through five keep a hard 16 alive
+    /// assert!((bar.component(StrengthKind::Potential).unwrap() - 5.0 / 13.0).abs() < 1e-9);
+    /// ```
+    pub fn strength(&self) -> StrengthBar {
+        let current = if self.value.is_bust() {
+            0.0
+        } else {
+            f64::from(self.value.total.saturating_sub(STRENGTH_FLOOR))
+                / f64::from(BLACKJACK - STRENGTH_FLOOR)
+        };
+        let ranks = Rank::all_ranks();
+        let safe = ranks
+            .iter()
+            .filter(|rank| self.value.soft || self.value.total + card_value(**rank) <= BLACKJACK)
+            .count();
+        #[allow(clippy::cast_precision_loss)]
+        let potential = if self.value.is_bust() {
+            0.0
+        } else {
+            safe as f64 / ranks.len() as f64
+        };
+        StrengthBar::new(
+            current,
+            [
+                (StrengthKind::Current, current),
+                (StrengthKind::Potential, potential),
+            ],
+        )
+    }
+}
+
+/// How a seat's hand finished against the dealer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum BlackjackOutcome {
+    /// A natural blackjack, paid according to the table's [`BlackjackPayout`]
+    Blackjack,
+    Win,
+    Push,
+    Lose,
+}
+
+/// The settled result of a seat's hand
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeatResult {
+    pub outcome: BlackjackOutcome,
+    /// The final wager, including any double
+    pub wager: u32,
+    /// How much the table pays for a natural blackjack
+    pub blackjack_payout: BlackjackPayout,
+}
+
+impl SeatResult {
+    /// Gets the amount won (or lost, if negative) by the seat
+    ///
+    /// Fractional blackjack payouts are rounded down, in the house's favour
+    /// # Examples
+    /// ```
+    /// # use naipe::games::blackjack::{BlackjackOutcome, SeatResult};
+    /// # use naipe::games::blackjack::rules::BlackjackPayout;
+    /// let result = SeatResult {
+    ///     outcome: BlackjackOutcome::Blackjack,
+    ///     wager: 10,
+    ///     blackjack_payout: BlackjackPayout::ThreeToTwo,
+    /// };
+    /// assert_eq!(result.net(), 15);
+    /// let result = SeatResult {
+    ///     outcome: BlackjackOutcome::Lose,
+    ///     wager: 10,
+    ///     blackjack_payout: BlackjackPayout::ThreeToTwo,
+    /// };
+    /// assert_eq!(result.net(), -10);
+    /// ```
+    pub fn net(&self) -> i64 {
+        let wager = i64::from(self.wager);
+        match self.outcome {
+            BlackjackOutcome::Blackjack => self.blackjack_payout.pay(wager),
+            BlackjackOutcome::Win => wager,
+            BlackjackOutcome::Push => 0,
+            BlackjackOutcome::Lose => -wager,
+        }
+    }
+}
+
+/// Something that happened at the table, reported through [`EventSource::events`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BlackjackEvent {
+    /// In audit mode, the shoe was shuffled, or a card was returned to the continuous shuffling
+    /// machine at a random depth
+    Randomness(RandomnessRecord),
+}
+
+/// A single seat's hand in a round
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Seat {
+    hand: Vec<Card>,
+    wager: u32,
+    finished: bool,
+}
+
+/// The stage of the current round
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Phase {
+    /// Waiting for the round to be dealt
+    Betting,
+    /// Waiting on a decision from the given seat
+    Playing(usize),
+    /// All seats have finished, and the dealer plays out their hand
+    Dealer,
+    /// The round is over, and results are available
+    Settled,
+}
+
+/// Game state for a table of Blackjack
+///
+/// Each call to [`AgentGame::tick_with_agents`] deals the round, makes one decision, or plays the
+/// dealer's hand, returning `true` once the round is settled. [`BlackjackGame::new_round`] then
+/// clears the table for the next round from the same shoe.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlackjackGame {
+    rules: BlackjackRules,
+    shoe: Deck,
+    discards: Deck,
+    shoe_size: usize,
+    wagers: Vec<u32>,
+    /// The most each seat may have at stake, including a double, where it is limited
+    #[cfg_attr(feature = "serde", serde(default))]
+    bankrolls: Vec<Option<u32>>,
+    seats: Vec<Seat>,
+    dealer: Vec<Card>,
+    phase: Phase,
+    results: Vec<SeatResult>,
+    rng: GameRng,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<BlackjackEvent>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    audit: bool,
+    /// Whether the events have been given by a tick, so the next event starts a new list
+    #[cfg_attr(feature = "serde", serde(skip))]
+    delivered: bool,
+}
+
+impl BlackjackGame {
+    /// Creates a table with the given number of seats, dealing from a shuffled shoe
+    /// of the given number of decks, with otherwise default rules
+    ///
+    /// Every seat starts with a wager of one unit. A table seats at most seven, and any more
+    /// seats are not opened
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::blackjack::BlackjackGame;
+    /// use std::num::{NonZeroU16, NonZeroUsize};
+    /// let game = BlackjackGame::new(NonZeroU16::new(6).unwrap(), NonZeroUsize::new(3).unwrap());
+    /// assert_eq!(game.shoe_len(), 312);
+    /// let game = BlackjackGame::new(NonZeroU16::new(1).unwrap(), NonZeroUsize::new(30).unwrap());
+    /// assert_eq!(game.seat_count(), 7);
+    /// ```
+    pub fn new(decks: NonZeroU16, seats: NonZeroUsize) -> BlackjackGame {
+        let rules = BlackjackRules {
+            decks,
+            ..BlackjackRules::default()
+        };
+        BlackjackGame::open(rules, seats.get().min(MAX_SEATS), GameRng::from_entropy())
+    }
+
+    /// Creates a table with the given number of seats and house rules
+    /// # Errors
+    /// A [`RuleConflict`] if the rules do not make a playable game, or
+    /// [`RuleConflict::TooManySeats`] if there are more than seven seats
+    /// # Examples
+    /// ```
+    /// # use naipe::games::blackjack::BlackjackGame;
+    /// # use naipe::games::blackjack::rules::{BlackjackPayout, BlackjackRules};
+    /// use std::num::NonZeroUsize;
+    /// let rules = BlackjackRules {
+    ///     dealer_hits_soft_17: false,
+    ///     blackjack_payout: BlackjackPayout::SixToFive,
+    ///     ..BlackjackRules::default()
+    /// };
+    /// let game = BlackjackGame::with_rules(rules, NonZeroUsize::new(2).unwrap()).unwrap();
+    /// assert_eq!(game.rules(), &rules);
+    ///
+    /// let rules = BlackjackRules { double_after_split: true, ..BlackjackRules::default() };
+    /// assert!(BlackjackGame::with_rules(rules, NonZeroUsize::new(2).unwrap()).is_err());
+    ///
+    /// let seats = NonZeroUsize::new(8).unwrap();
+    /// assert!(BlackjackGame::with_rules(BlackjackRules::default(), seats).is_err());
+    /// ```
+    pub fn with_rules(
+        rules: BlackjackRules,
+        seats: NonZeroUsize,
+    ) -> Result<BlackjackGame, RuleConflict> {
+        validate_table(&rules, seats)?;
+        Ok(BlackjackGame::open(
+            rules,
+            seats.get(),
+            GameRng::from_entropy(),
+        ))
+    }
+
+    /// Creates a table with the given number of seats and house rules, shuffling with a
+    /// generator seeded from the given one
+    /// # Errors
+    /// As for [`BlackjackGame::with_rules`]
+    /// # Examples
+    /// ```
+    /// # use naipe::games::blackjack::BlackjackGame;
+    /// # use naipe::games::blackjack::rules::BlackjackRules;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// use std::num::NonZeroUsize;
+    /// let seats = NonZeroUsize::new(1).unwrap();
+    /// let rules = BlackjackRules::default();
+    /// let game_1 = BlackjackGame::with_rules_and_rng(rules, seats, &mut StdRng::seed_from_u64(7));
+    /// let game_2 = BlackjackGame::with_rules_and_rng(rules, seats, &mut StdRng::seed_from_u64(7));
+    /// assert_eq!(game_1.unwrap(), game_2.unwrap());
+    /// ```
+    pub fn with_rules_and_rng<R: Rng + ?Sized>(
+        rules: BlackjackRules,
+        seats: NonZeroUsize,
+        rng: &mut R,
+    ) -> Result<BlackjackGame, RuleConflict> {
+        validate_table(&rules, seats)?;
+        let rng = GameRng::from_seed(rng.gen());
+        Ok(BlackjackGame::open(rules, seats.get(), rng))
+    }
+
+    fn open(rules: BlackjackRules, seats: usize, mut rng: GameRng) -> BlackjackGame {
+        let mut shoe = Deck::new(rules.decks);
+        shoe.shuffle(&mut rng);
+        BlackjackGame {
+            rules,
+            shoe_size: shoe.len(),
+            shoe,
+            discards: Deck::new_empty(),
+            wagers: vec![1; seats],
+            bankrolls: vec![None; seats],
+            seats: vec![],
+            dealer: vec![],
+            phase: Phase::Betting,
+            results: vec![],
+            rng,
+            events: vec![],
+            audit: false,
+            delivered: false,
+        }
+    }
+
+    /// Puts the table in audit mode, recording the opening shuffle of the shoe where it can
+    ///
+    /// The opening shuffle is re-derived from the seed of the table's generator, and only
+    /// recorded if nothing else has been drawn from the generator or changed in the shoe since
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::blackjack::{BlackjackAction, BlackjackEvent, BlackjackGame, BlackjackView};
+    /// # use naipe::games::blackjack::rules::BlackjackRules;
+    /// # use naipe::games::event::EventSource;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// use std::num::NonZeroUsize;
+    /// let seats = NonZeroUsize::new(1).unwrap();
+    /// let rules = BlackjackRules::default();
+    /// let rng = &mut StdRng::seed_from_u64(3);
+    /// let mut game = BlackjackGame::with_rules_and_rng(rules, seats, rng).unwrap().audited();
+    /// let BlackjackEvent::Randomness(record) = &game.events()[0] else {
+    ///     panic!("the opening shuffle is recorded");
+    /// };
+    /// assert!(record.verify());
+    ///
+    /// // The record is delivered by the first tick, which deals from the same shoe
+    /// let mut stand = |_: &BlackjackView| BlackjackAction::Stand;
+    /// let plain = &mut BlackjackGame::with_rules_and_rng(rules, seats, &mut StdRng::seed_from_u64(3));
+    /// let plain = plain.as_mut().unwrap();
+    /// game.tick_with_agents(&mut [&mut stand]).unwrap();
+    /// plain.tick_with_agents(&mut [&mut stand]).unwrap();
+    /// assert_eq!(game.events().len(), 1);
+    /// assert_eq!(game.seat_hand(0), plain.seat_hand(0));
+    /// ```
+    #[must_use]
+    pub fn audited(mut self) -> BlackjackGame {
+        self.audit = true;
+        let mut rng = GameRng::from_seed(self.rng.get_seed());
+        let mut shoe = Deck::new(self.rules.decks);
+        let record = RandomnessRecord::shuffle("shoe", &mut shoe, &mut rng);
+        if rng.get_word_pos() == self.rng.get_word_pos() && shoe == self.shoe {
+            self.emit(BlackjackEvent::Randomness(record));
+        }
+        self
+    }
+
+    /// Sets whether every later shuffle, and every card returned to a continuous shuffling
+    /// machine, is recorded as a [`BlackjackEvent::Randomness`] event
+    ///
+    /// Auditing draws the same random numbers, so play is unchanged
+    pub fn set_audit(&mut self, audit: bool) {
+        self.audit = audit;
+    }
+
+    /// Finds if every shuffle is recorded
+    pub fn is_audited(&self) -> bool {
+        self.audit
+    }
+
+    /// Adds an event, starting a new list if the last one has been given by a tick
+    fn emit(&mut self, event: BlackjackEvent) {
+        if self.delivered {
+            self.events.clear();
+            self.delivered = false;
+        }
+        self.events.push(event);
+    }
+
+    /// Sets the wager a seat places from the next round dealt
+    /// # Errors
+    /// [`BlackjackError::UnknownSeat`] if there is no such seat
+    pub fn set_wager(&mut self, seat: usize, wager: u32) -> Result<(), BlackjackError> {
+        let slot = self
+            .wagers
+            .get_mut(seat)
+            .ok_or(BlackjackError::UnknownSeat(seat))?;
+        *slot = wager;
+        Ok(())
+    }
+
+    /// Limits how much a seat may have at stake, refusing it a double that would go over the
+    /// limit, or lifts the limit with `None`
+    /// # Errors
+    /// [`BlackjackError::UnknownSeat`] if there is no such seat
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::blackjack::{BlackjackAction, BlackjackGame, BlackjackView};
+    /// # use naipe::games::blackjack::rules::BlackjackRules;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// use std::num::NonZeroUsize;
+    /// let seats = NonZeroUsize::new(1).unwrap();
+    /// let mut game =
+    ///     BlackjackGame::with_rules_and_rng(BlackjackRules::default(), seats, &mut StdRng::seed_from_u64(3))
+    ///         .unwrap();
+    /// game.set_wager(0, 10).unwrap();
+    /// game.set_bankroll(0, Some(15)).unwrap();
+    /// let mut stand = |_: &BlackjackView| BlackjackAction::Stand;
+    /// game.tick_with_agents(&mut [&mut stand]).unwrap();
+    /// assert!(!game.view(0).can_double);
+    /// ```
+    pub fn set_bankroll(
+        &mut self,
+        seat: usize,
+        bankroll: Option<u32>,
+    ) -> Result<(), BlackjackError> {
+        if seat >= self.wagers.len() {
+            return Err(BlackjackError::UnknownSeat(seat));
+        }
+        // Saves from before bankrolls were kept have none
+        self.bankrolls.resize(self.wagers.len(), None);
+        self.bankrolls[seat] = bankroll;
+        Ok(())
+    }
+
+    /// Adds a seat to the table between rounds, returning its index
+    /// # Errors
+    /// [`BlackjackError::RoundInProgress`] if a round has been dealt and not cleared, or
+    /// [`BlackjackError::TableFull`] if all seven seats are taken
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::blackjack::BlackjackGame;
+    /// use std::num::{NonZeroU16, NonZeroUsize};
+    /// let mut game = BlackjackGame::new(NonZeroU16::new(1).unwrap(), NonZeroUsize::new(1).unwrap());
+    /// assert_eq!(game.join(5).unwrap(), 1);
+    /// assert_eq!(game.seat_count(), 2);
+    /// game.leave(0).unwrap();
+    /// assert_eq!(game.view(0).wager, 5);
+    ///
+    /// while game.seat_count() < 7 {
+    ///     game.join(1).unwrap();
+    /// }
+    /// assert!(game.join(1).is_err());
+    /// ```
+    pub fn join(&mut self, wager: u32) -> Result<usize, BlackjackError> {
+        if self.phase != Phase::Betting {
+            return Err(BlackjackError::RoundInProgress);
+        }
+        if self.wagers.len() >= MAX_SEATS {
+            return Err(BlackjackError::TableFull);
+        }
+        self.bankrolls.resize(self.wagers.len(), None);
+        self.wagers.push(wager);
+        self.bankrolls.push(None);
+        Ok(self.wagers.len() - 1)
+    }
+
+    /// Removes a seat from the table between rounds, moving every later seat down by one
+    /// # Errors
+    /// [`BlackjackError::UnknownSeat`] if there is no such seat, or
+    /// [`BlackjackError::RoundInProgress`] if a round has been dealt and not cleared
+    pub fn leave(&mut self, seat: usize) -> Result<(), BlackjackError> {
+        if seat >= self.wagers.len() {
+            return Err(BlackjackError::UnknownSeat(seat));
+        }
+        if self.phase != Phase::Betting {
+            return Err(BlackjackError::RoundInProgress);
+        }
+        self.wagers.remove(seat);
+        if seat < self.bankrolls.len() {
+            self.bankrolls.remove(seat);
+        }
+        Ok(())
+    }
+
+    /// Places cards on top of the shoe, to be dealt in order from the next round,
+    /// such as to set up a teaching scenario
+    ///
+    /// The stacked cards are taken from where they lie in the shoe, so the shoe holds the same
+    /// cards as before. Cards are dealt one to each seat in turn and then to the dealer, twice
+    /// over, before any hits
+    /// # Errors
+    /// [`BlackjackError::RoundInProgress`] if a round has been dealt and not cleared, or
+    /// [`BlackjackError::NotInShoe`] if a card is not left in the shoe, leaving the shoe as it was
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::blackjack::{BlackjackAction, BlackjackGame, BlackjackView};
+    /// use naipe::common::{card::Card, deck::Deck};
+    /// use std::num::{NonZeroU16, NonZeroUsize};
+    /// let mut game = BlackjackGame::new(NonZeroU16::new(1).unwrap(), NonZeroUsize::new(1).unwrap());
+    /// // A single deck has only one ace of spades
+    /// assert!(game.stack_shoe(Deck::from_notation("AS AS").unwrap()).is_err());
+    /// // A soft 18 against a dealer's nine
+    /// game.stack_shoe(Deck::from_notation("AS 9H 7D 10C").unwrap()).unwrap();
+    /// let mut stand = |_: &BlackjackView| BlackjackAction::Stand;
+    /// game.tick_with_agents(&mut [&mut stand]).unwrap();
+    /// let hand: Vec<Card> = vec!["AS".parse().unwrap(), "7D".parse().unwrap()];
+    /// assert_eq!(game.seat_hand(0), Some(hand.as_slice()));
+    /// assert_eq!(game.dealer_hand()[0], "9H".parse().unwrap());
+    /// assert_eq!(game.shoe_len(), 48);
+    /// ```
+    pub fn stack_shoe(&mut self, cards: Deck) -> Result<(), BlackjackError> {
+        if self.phase != Phase::Betting {
+            return Err(BlackjackError::RoundInProgress);
+        }
+        let mut rest: Vec<Card> = self.shoe.clone().into_iter().collect();
+        for card in cards.clone() {
+            let at = rest
+                .iter()
+                .rposition(|left| *left == card)
+                .ok_or(BlackjackError::NotInShoe(card))?;
+            rest.remove(at);
+        }
+        self.shoe = Deck::new_empty();
+        self.shoe.extend(rest.into_iter().chain(cards));
+        Ok(())
+    }
+
+    /// Clears the table after a settled round, reshuffling the shoe if the table's
+    /// [`ShufflePolicy`] calls for it, or returning the round's cards to the continuous
+    /// shuffling machine
+    ///
+    /// Does nothing if the current round is not yet settled
+    /// # Examples
+    /// ```
+    /// # use naipe::common::shuffle::ShufflePolicy;
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::blackjack::{BlackjackAction, BlackjackGame, BlackjackView};
+    /// # use naipe::games::blackjack::rules::BlackjackRules;
+    /// use std::num::NonZeroUsize;
+    /// let rules = BlackjackRules {
+    ///     shuffle: ShufflePolicy::Continuous { buffer: 8 },
+    ///     ..BlackjackRules::default()
+    /// };
+    /// let mut game = BlackjackGame::with_rules(rules, NonZeroUsize::new(1).unwrap()).unwrap();
+    /// let mut stand = |_: &BlackjackView| BlackjackAction::Stand;
+    /// for _ in 0..100 {
+    ///     while !game.tick_with_agents(&mut [&mut stand]).unwrap() {}
+    ///     game.new_round();
+    ///     assert_eq!(game.shoe_len(), 312);
+    /// }
+    /// ```
+    pub fn new_round(&mut self) {
+        if self.phase != Phase::Settled {
+            return;
+        }
+        for seat in mem::take(&mut self.seats) {
+            self.discards.extend(seat.hand);
+        }
+        self.discards.extend(mem::take(&mut self.dealer));
+        self.results.clear();
+        if let ShufflePolicy::Continuous { buffer } = self.rules.shuffle {
+            let discards = mem::replace(&mut self.discards, Deck::new_empty());
+            let machine = ContinuousShuffler::new(buffer.into());
+            if self.audit {
+                let mut records = vec![];
+                let rng = &mut self.rng;
+                machine.load_by(&mut self.shoe, discards, |depths| {
+                    let (depth, record) =
+                        RandomnessRecord::choose("continuous shuffler", depths, rng);
+                    records.push(record);
+                    depth
+                });
+                for record in records {
+                    self.emit(BlackjackEvent::Randomness(record));
+                }
+            } else {
+                machine.load(&mut self.shoe, discards, &mut self.rng);
+            }
+        } else if self
+            .rules
+            .shuffle
+            .should_reshuffle(self.shoe.len(), self.shoe_size)
+        {
+            self.gather_and_shuffle();
+        }
+        self.phase = Phase::Betting;
+    }
+
+    /// Gathers the discards back into the shoe and shuffles it, between rounds
+    /// # Errors
+    /// [`BlackjackError::RoundInProgress`] if a round has been dealt and not cleared
+    /// # Examples
+    /// ```
+    /// # use naipe::common::shuffle::ShufflePolicy;
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::blackjack::{BlackjackAction, BlackjackGame, BlackjackView};
+    /// # use naipe::games::blackjack::rules::BlackjackRules;
+    /// use std::num::NonZeroUsize;
+    /// let rules = BlackjackRules { shuffle: ShufflePolicy::OnDemand, ..BlackjackRules::default() };
+    /// let mut game = BlackjackGame::with_rules(rules, NonZeroUsize::new(1).unwrap()).unwrap();
+    /// let mut stand = |_: &BlackjackView| BlackjackAction::Stand;
+    /// while !game.tick_with_agents(&mut [&mut stand]).unwrap() {}
+    /// game.new_round();
+    /// assert!(game.shoe_len() < 312);
+    /// game.reshuffle().unwrap();
+    /// assert_eq!(game.shoe_len(), 312);
+    /// ```
+    pub fn reshuffle(&mut self) -> Result<(), BlackjackError> {
+        if self.phase != Phase::Betting {
+            return Err(BlackjackError::RoundInProgress);
+        }
+        self.gather_and_shuffle();
+        Ok(())
+    }
+
+    /// Moves the discards back into the shoe, and shuffles it
+    fn gather_and_shuffle(&mut self) {
+        debug!("Reshuffling the shoe");
+        self.shoe
+            .extend(mem::replace(&mut self.discards, Deck::new_empty()));
+        if self.audit {
+            let record = RandomnessRecord::shuffle("shoe", &mut self.shoe, &mut self.rng);
+            self.emit(BlackjackEvent::Randomness(record));
+        } else {
+            self.shoe.shuffle(&mut self.rng);
+        }
+    }
+
+    /// Gets the house rules for this table
+    pub fn rules(&self) -> &BlackjackRules {
+        &self.rules
+    }
+
+    /// Gets the number of cards left in the shoe
+    pub fn shoe_len(&self) -> usize {
+        self.shoe.len()
+    }
+
+    /// Gets the cards in a seat's hand for the current round
+    pub fn seat_hand(&self, seat: usize) -> Option<&[Card]> {
+        self.seats.get(seat).map(|seat| seat.hand.as_slice())
+    }
+
+    /// Gets the dealer's cards for the current round
+    pub fn dealer_hand(&self) -> &[Card] {
+        &self.dealer
+    }
+
+    /// Finds if the current round is over
+    pub fn is_settled(&self) -> bool {
+        self.phase == Phase::Settled
+    }
+
+    /// Gets the result for each seat, once the round is settled
+    pub fn results(&self) -> &[SeatResult] {
+        &self.results
+    }
+
+    /// Draws a card, shuffling the discards back into the shoe if it runs out
+    fn draw(&mut self) -> Result<Card, BlackjackError> {
+        if self.shoe.is_empty() {
+            debug!("Shoe ran out mid-round, reshuffling discards");
+            self.gather_and_shuffle();
+        }
+        self.shoe
+            .draw()
+            .ok_or(BlackjackError::Deal(DeckDealError::NotEnoughCards))
+    }
+
+    fn deal(&mut self) -> Result<(), BlackjackError> {
+        // A deal that ran out of cards part way leaves its cards out, to be gathered up again
+        for seat in mem::take(&mut self.seats) {
+            self.discards.extend(seat.hand);
+        }
+        self.discards.extend(mem::take(&mut self.dealer));
+        self.seats = self
+            .wagers
+            .iter()
+            .map(|wager| Seat {
+                hand: vec![],
+                wager: *wager,
+                finished: false,
+            })
+            .collect();
+        for _ in 0..2 {
+            for seat in 0..self.seats.len() {
+                let card = self.draw()?;
+                self.seats[seat].hand.push(card);
+            }
+            let card = self.draw()?;
+            self.dealer.push(card);
+        }
+        for seat in &mut self.seats {
+            seat.finished = is_blackjack(&seat.hand);
+        }
+        debug!("Dealer shows {}", self.dealer[0]);
+
+        if is_blackjack(&self.dealer) {
+            debug!("Dealer has blackjack");
+            self.settle();
+        } else {
+            self.phase = self.next_phase(0);
+        }
+        Ok(())
+    }
+
+    /// Finds the phase following the given seat's turn
+    fn next_phase(&self, from_seat: usize) -> Phase {
+        (from_seat..self.seats.len())
+            .find(|seat| !self.seats[*seat].finished)
+            .map_or(Phase::Dealer, Phase::Playing)
+    }
+
+    fn act(&mut self, seat: usize, action: BlackjackAction) -> Result<(), BlackjackError> {
+        if action == BlackjackAction::Double && !self.can_double(seat) {
+            return Err(BlackjackError::IllegalAction(action));
+        }
+        debug!("Seat {seat} chose {action:?}");
+        match action {
+            BlackjackAction::Hit => {
+                let card = self.draw()?;
+                self.seats[seat].hand.push(card);
+                self.seats[seat].finished =
+                    HandValue::of(&self.seats[seat].hand).total >= BLACKJACK;
+            }
+            BlackjackAction::Stand => self.seats[seat].finished = true,
+            BlackjackAction::Double => {
+                let doubled = self.seats[seat]
+                    .wager
+                    .checked_mul(2)
+                    .ok_or(BlackjackError::IllegalAction(action))?;
+                let card = self.draw()?;
+                self.seats[seat].wager = doubled;
+                self.seats[seat].hand.push(card);
+                self.seats[seat].finished = true;
+            }
+        }
+        self.phase = self.next_phase(seat);
+        Ok(())
+    }
+
+    /// Finds if the seat may double, which needs the rules to allow it and the seat to afford it
+    fn can_double(&self, seat: usize) -> bool {
+        let bankroll = self.bankrolls.get(seat).copied().flatten();
+        self.seats.get(seat).is_some_and(|seat| {
+            seat.hand.len() == 2
+                && !seat.finished
+                && self.rules.doubling.allows(HandValue::of(&seat.hand))
+                && seat
+                    .wager
+                    .checked_mul(2)
+                    .is_some_and(|doubled| bankroll.is_none_or(|bankroll| doubled <= bankroll))
+        })
+    }
+
+    /// Plays out the dealer's hand, if any seat is still live
+    fn play_dealer(&mut self) -> Result<(), BlackjackError> {
+        let any_live = self
+            .seats
+            .iter()
+            .any(|seat| !HandValue::of(&seat.hand).is_bust() && !is_blackjack(&seat.hand));
+        if any_live {
+            loop {
+                let value = HandValue::of(&self.dealer);
+                let hits_17 = value.soft && self.rules.dealer_hits_soft_17;
+                if value.total > 17 || (value.total == 17 && !hits_17) {
+                    break;
+                }
+                let card = self.draw()?;
+                self.dealer.push(card);
+            }
+        }
+        debug!("Dealer finishes with {}", HandValue::of(&self.dealer).total);
+        self.settle();
+        Ok(())
+    }
+
+    fn settle(&mut self) {
+        let dealer_value = HandValue::of(&self.dealer);
+        let dealer_blackjack = is_blackjack(&self.dealer);
+        self.results = self
+            .seats
+            .iter()
+            .map(|seat| {
+                let value = HandValue::of(&seat.hand);
+                let outcome = if value.is_bust() {
+                    BlackjackOutcome::Lose
+                } else if is_blackjack(&seat.hand) {
+                    if dealer_blackjack {
+                        BlackjackOutcome::Push
+                    } else {
+                        BlackjackOutcome::Blackjack
+                    }
+                } else if dealer_blackjack {
+                    BlackjackOutcome::Lose
+                } else if dealer_value.is_bust() {
+                    BlackjackOutcome::Win
+                } else {
+                    match value.total.cmp(&dealer_value.total) {
+                        Ordering::Greater => BlackjackOutcome::Win,
+                        Ordering::Equal => BlackjackOutcome::Push,
+                        Ordering::Less => BlackjackOutcome::Lose,
+                    }
+                };
+                SeatResult {
+                    outcome,
+                    wager: seat.wager,
+                    blackjack_payout: self.rules.blackjack_payout,
+                }
+            })
+            .collect();
+        self.phase = Phase::Settled;
+    }
+}
+
+impl GameInfo for BlackjackGame {
+    fn info() -> GameMetadata {
+        GameMetadata {
+            name: "Blackjack",
+            summary:
+                "Each player draws cards to get closer to 21 than the dealer without going over",
+            min_players: 1,
+            max_players: MAX_SEATS,
+            decks: BlackjackRules::default().decks.get(),
+            minutes: 2,
+            tags: &[GameTag::Casino],
+        }
+    }
+}
+
+impl ErasedGame for BlackjackGame {
+    /// Writes the view as `hand=<cards> total=<total> soft=<bool> dealer=<card> double=<bool>
+    /// wager=<chips>`, with the dealer's card `none` before the deal
+    fn encode_view(view: &BlackjackView) -> String {
+        format!(
+            "hand=[{}] total={} soft={} dealer={} double={} wager={}",
+            view.hand
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(","),
+            view.value.total,
+            view.value.soft,
+            view.dealer_upcard
+                .map_or_else(|| "none".to_string(), |card| card.to_string()),
+            view.can_double,
+            view.wager
+        )
+    }
+
+    /// Reads `hit`, `stand`, or `double`, or their first letters, ignoring case
+    fn decode_action(action: &str) -> Option<BlackjackAction> {
+        match action.trim().to_lowercase().as_str() {
+            "h" | "hit" => Some(BlackjackAction::Hit),
+            "s" | "stand" => Some(BlackjackAction::Stand),
+            "d" | "double" => Some(BlackjackAction::Double),
+            _ => None,
+        }
+    }
+
+    /// Stands
+    fn fallback_action(_view: &BlackjackView) -> BlackjackAction {
+        BlackjackAction::Stand
+    }
+
+    /// Describes each seat's result once the round is settled
+    fn outcome(&self) -> Option<String> {
+        if !self.is_settled() {
+            return None;
+        }
+        Some(
+            self.results
+                .iter()
+                .enumerate()
+                .map(|(seat, result)| {
+                    format!(
+                        "Seat {}: {:?} ({:+})",
+                        seat + 1,
+                        result.outcome,
+                        result.net()
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(", "),
+        )
+    }
+}
+
+impl AgentGame for BlackjackGame {
+    type View = BlackjackView;
+    type Action = BlackjackAction;
+    type TickOk = bool;
+    type TickError = BlackjackError;
+
+    fn seat_count(&self) -> usize {
+        self.wagers.len()
+    }
+
+    fn view(&self, seat: usize) -> BlackjackView {
+        let hand = self
+            .seats
+            .get(seat)
+            .map(|seat| seat.hand.clone())
+            .unwrap_or_default();
+        BlackjackView {
+            value: HandValue::of(&hand),
+            hand,
+            dealer_upcard: self.dealer.first().copied(),
+            can_double: self.can_double(seat),
+            wager: self
+                .seats
+                .get(seat)
+                .map_or(self.wagers.get(seat).copied().unwrap_or_default(), |seat| {
+                    seat.wager
+                }),
+        }
+    }
+
+    /// Advances the round by one step, returning whether the round is settled
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::blackjack::{basic_strategy, BlackjackGame};
+    /// use std::num::{NonZeroU16, NonZeroUsize};
+    /// let mut game = BlackjackGame::new(NonZeroU16::new(1).unwrap(), NonZeroUsize::new(1).unwrap());
+    /// let mut player = basic_strategy;
+    /// while !game.tick_with_agents(&mut [&mut player]).unwrap() {}
+    /// assert_eq!(game.results().len(), 1);
+    /// game.new_round();
+    /// assert!(!game.is_settled());
+    /// ```
+    fn tick_with_agents(
+        &mut self,
+        agents: &mut [&mut dyn Agent<BlackjackView, BlackjackAction>],
+    ) -> Result<bool, BlackjackError> {
+        if agents.len() != self.seat_count() {
+            return Err(BlackjackError::WrongAgentCount(agents.len()));
+        }
+        // Events from between rounds stay pending until this tick delivers them
+        if self.delivered {
+            self.events.clear();
+        }
+        self.delivered = true;
+        match self.phase {
+            Phase::Betting => self.deal()?,
+            Phase::Playing(seat) => {
+                let action = agents[seat].choose_action(&self.view(seat));
+                self.act(seat, action)?;
+            }
+            Phase::Dealer => self.play_dealer()?,
+            Phase::Settled => {}
+        }
+        Ok(self.is_settled())
+    }
+}
+
+impl EventSource for BlackjackGame {
+    type Event = BlackjackEvent;
+
+    /// Gets the events from the most recent tick, after any from between rounds since it
+    /// # Examples
+    /// ```
+    /// # use naipe::common::shuffle::ShufflePolicy;
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::blackjack::{BlackjackAction, BlackjackEvent, BlackjackGame, BlackjackView};
+    /// # use naipe::games::blackjack::rules::BlackjackRules;
+    /// # use naipe::games::event::EventSource;
+    /// use std::num::NonZeroUsize;
+    /// let rules = BlackjackRules {
+    ///     shuffle: ShufflePolicy::Continuous { buffer: 8 },
+    ///     ..BlackjackRules::default()
+    /// };
+    /// let mut game = BlackjackGame::with_rules(rules, NonZeroUsize::new(1).unwrap()).unwrap();
+    /// game.set_audit(true);
+    /// let mut stand = |_: &BlackjackView| BlackjackAction::Stand;
+    /// while !game.tick_with_agents(&mut [&mut stand]).unwrap() {}
+    /// game.new_round();
+    /// // Every card of the round went back into the shoe at a recorded depth
+    /// let returned = game.events().len();
+    /// assert!(returned >= 4);
+    /// assert!(game
+    ///     .events()
+    ///     .iter()
+    ///     .all(|event| matches!(event, BlackjackEvent::Randomness(record) if record.verify())));
+    /// game.tick_with_agents(&mut [&mut stand]).unwrap();
+    /// assert_eq!(game.events().len(), returned);
+    /// game.tick_with_agents(&mut [&mut stand]).unwrap();
+    /// assert!(game.events().is_empty());
+    /// ```
+    fn events(&self) -> &[BlackjackEvent] {
+        &self.events
+    }
+}
+
+/// A simplified basic strategy for a dealer hitting soft 17, without splitting
+///
+/// Usable directly as an [`Agent`]
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::games::blackjack::{basic_strategy, BlackjackAction, BlackjackView, HandValue};
+/// let hand = vec![Card::new(Suit::Spade, Rank::Six), Card::new(Suit::Heart, Rank::Five)];
+/// let view = BlackjackView {
+///     value: HandValue::of(&hand),
+///     hand,
+///     dealer_upcard: Some(Card::new(Suit::Club, Rank::Ten)),
+///     can_double: true,
+///     wager: 1,
+/// };
+/// assert_eq!(basic_strategy(&view), BlackjackAction::Double);
+/// ```
+pub fn basic_strategy(view: &BlackjackView) -> BlackjackAction {
+    let dealer = view.dealer_upcard.map_or(10, |card| match card.get_rank() {
+        Rank::Ace => 11,
+        rank => card_value(rank),
+    });
+    let HandValue { total, soft } = view.value;
+    let (action, double_fallback) = if soft {
+        match total {
+            13 | 14 if (5..=6).contains(&dealer) => (BlackjackAction::Double, BlackjackAction::Hit),
+            15 | 16 if (4..=6).contains(&dealer) => (BlackjackAction::Double, BlackjackAction::Hit),
+            17 if (3..=6).contains(&dealer) => (BlackjackAction::Double, BlackjackAction::Hit),
+            18 if (2..=6).contains(&dealer) => (BlackjackAction::Double, BlackjackAction::Stand),
+            18 if dealer >= 9 => (BlackjackAction::Hit, BlackjackAction::Hit),
+            19 if dealer == 6 => (BlackjackAction::Double, BlackjackAction::Stand),
+            ..=17 => (BlackjackAction::Hit, BlackjackAction::Hit),
+            _ => (BlackjackAction::Stand, BlackjackAction::Stand),
+        }
+    } else {
+        match total {
+            9 if (3..=6).contains(&dealer) => (BlackjackAction::Double, BlackjackAction::Hit),
+            10 if dealer <= 9 => (BlackjackAction::Double, BlackjackAction::Hit),
+            11 => (BlackjackAction::Double, BlackjackAction::Hit),
+            12 if (4..=6).contains(&dealer) => (BlackjackAction::Stand, BlackjackAction::Stand),
+            13..=16 if dealer <= 6 => (BlackjackAction::Stand, BlackjackAction::Stand),
+            ..=16 => (BlackjackAction::Hit, BlackjackAction::Hit),
+            _ => (BlackjackAction::Stand, BlackjackAction::Stand),
+        }
+    };
+    if action == BlackjackAction::Double && !view.can_double {
+        double_fallback
+    } else {
+        action
+    }
+}
+
+/// Errors related to playing Blackjack
+#[derive(Copy, Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum BlackjackError {
+    #[error("Expected one agent per seat, but got {0}")]
+    WrongAgentCount(usize),
+    #[error("No player sits in seat {0}")]
+    UnknownSeat(usize),
+    #[error("{0:?} is not allowed now")]
+    IllegalAction(BlackjackAction),
+    #[error("A round is in progress")]
+    RoundInProgress,
+    #[error("{0} is not left in the shoe")]
+    NotInShoe(Card),
+    #[error("Every seat at the table is taken")]
+    TableFull,
+    #[error(transparent)]
+    Deal(#[from] DeckDealError),
+}
@@ -0,0 +1,172 @@
+//! Configurable house rules for Blackjack
+//!
+//! Rules are checked as a whole before a table is opened, so that contradictory or unsupported
+//! combinations are reported instead of producing a nonsense game
+
+use std::num::NonZeroU16;
+
+use thiserror::Error;
+
+use super::HandValue;
+use crate::common::shuffle::ShufflePolicy;
+
+/// How much a natural blackjack pays
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum BlackjackPayout {
+    /// Pays 3:2, the traditional payout
+    ThreeToTwo,
+    /// Pays 6:5, common on single deck tables
+    SixToFive,
+    /// Pays 1:1, the same as any other win
+    EvenMoney,
+}
+
+impl BlackjackPayout {
+    /// Gets the amount paid on a wager, rounding fractional units down in the house's favour
+    /// # Examples
+    /// ```
+    /// # use naipe::games::blackjack::rules::BlackjackPayout;
+    /// assert_eq!(BlackjackPayout::ThreeToTwo.pay(10), 15);
+    /// assert_eq!(BlackjackPayout::SixToFive.pay(10), 12);
+    /// assert_eq!(BlackjackPayout::EvenMoney.pay(10), 10);
+    /// ```
+    pub fn pay(&self, wager: i64) -> i64 {
+        match self {
+            BlackjackPayout::ThreeToTwo => wager * 3 / 2,
+            BlackjackPayout::SixToFive => wager * 6 / 5,
+            BlackjackPayout::EvenMoney => wager,
+        }
+    }
+}
+
+/// Which starting hands may be doubled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum DoubleRule {
+    /// Any first two cards may be doubled
+    AnyTwo,
+    /// Only hard or soft totals of 9, 10, or 11 may be doubled
+    NineToEleven,
+    /// Only totals of 10 or 11 may be doubled
+    TenOrEleven,
+    /// Doubling is not allowed
+    Never,
+}
+
+impl DoubleRule {
+    /// Finds if a two card hand with the given value may be doubled
+    pub fn allows(&self, value: HandValue) -> bool {
+        match self {
+            DoubleRule::AnyTwo => true,
+            DoubleRule::NineToEleven => (9..=11).contains(&value.total),
+            DoubleRule::TenOrEleven => (10..=11).contains(&value.total),
+            DoubleRule::Never => false,
+        }
+    }
+}
+
+/// A set of house rules for a Blackjack table
+///
+/// The default rules are a six deck shoe cut three quarters of the way through, dealer hits
+/// soft 17, blackjack pays 3:2, doubling on any two cards, and no splitting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlackjackRules {
+    /// The number of decks in the shoe
+    pub decks: NonZeroU16,
+    /// Whether the dealer hits a soft 17 (H17), rather than standing (S17)
+    pub dealer_hits_soft_17: bool,
+    /// How much a natural blackjack pays
+    pub blackjack_payout: BlackjackPayout,
+    /// Which hands may be doubled
+    pub doubling: DoubleRule,
+    /// Whether pairs may be split
+    pub splitting: bool,
+    /// Whether hands may be doubled after a split (DAS)
+    pub double_after_split: bool,
+    /// When the shoe is reshuffled between rounds
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shuffle: ShufflePolicy,
+}
+
+impl BlackjackRules {
+    /// Finds every problem with this combination of rules
+    /// # Examples
+    /// ```
+    /// # use naipe::games::blackjack::rules::{BlackjackRules, DoubleRule, RuleConflict};
+    /// let rules = BlackjackRules {
+    ///     doubling: DoubleRule::Never,
+    ///     double_after_split: true,
+    ///     ..BlackjackRules::default()
+    /// };
+    /// assert_eq!(
+    ///     rules.conflicts(),
+    ///     vec![
+    ///         RuleConflict::DoubleAfterSplitWithoutSplitting,
+    ///         RuleConflict::DoubleAfterSplitWithoutDoubling,
+    ///     ]
+    /// );
+    /// assert!(BlackjackRules::default().conflicts().is_empty());
+    /// ```
+    pub fn conflicts(&self) -> Vec<RuleConflict> {
+        let mut conflicts = vec![];
+        if self.double_after_split && !self.splitting {
+            conflicts.push(RuleConflict::DoubleAfterSplitWithoutSplitting);
+        }
+        if self.double_after_split && self.doubling == DoubleRule::Never {
+            conflicts.push(RuleConflict::DoubleAfterSplitWithoutDoubling);
+        }
+        if self.splitting {
+            conflicts.push(RuleConflict::Unsupported("splitting"));
+        }
+        if let ShufflePolicy::CutCard { penetration } = self.shuffle {
+            if !(1..=100).contains(&penetration) {
+                conflicts.push(RuleConflict::InvalidPenetration(penetration));
+            }
+        }
+        conflicts
+    }
+
+    /// Checks that this combination of rules makes a playable game
+    /// # Errors
+    /// The first [`RuleConflict`] found, if any
+    pub fn validate(&self) -> Result<(), RuleConflict> {
+        match self.conflicts().first() {
+            Some(conflict) => Err(*conflict),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for BlackjackRules {
+    fn default() -> BlackjackRules {
+        BlackjackRules {
+            decks: NonZeroU16::new(6).unwrap(),
+            dealer_hits_soft_17: true,
+            blackjack_payout: BlackjackPayout::ThreeToTwo,
+            doubling: DoubleRule::AnyTwo,
+            splitting: false,
+            double_after_split: false,
+            shuffle: ShufflePolicy::default(),
+        }
+    }
+}
+
+/// A contradictory or unsupported combination of house rules
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RuleConflict {
+    #[error("Doubling after splitting is allowed, but splitting is not")]
+    DoubleAfterSplitWithoutSplitting,
+    #[error("Doubling after splitting is allowed, but doubling is not")]
+    DoubleAfterSplitWithoutDoubling,
+    #[error("The {0} rule is not supported")]
+    Unsupported(&'static str),
+    #[error("The cut card must be placed between 1% and 100% of the way into the shoe, not {0}%")]
+    InvalidPenetration(u8),
+    #[error("A table seats at most seven players, not {0}")]
+    TooManySeats(usize),
+}
@@ -0,0 +1,263 @@
+//! A casual Blackjack session, where players come and go between rounds
+//!
+//! The table tracks each player's chips, taking wagers and paying out as rounds settle
+
+use rand::{Rng, SeedableRng};
+use thiserror::Error;
+
+use super::{
+    rules::{BlackjackRules, RuleConflict},
+    BlackjackAction, BlackjackError, BlackjackGame, BlackjackView, SeatResult,
+};
+use crate::games::{
+    agent::{Agent, AgentGame},
+    id::TableId,
+    GameRng,
+};
+
+/// A player sitting at a [`BlackjackTable`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TablePlayer {
+    name: String,
+    chips: i64,
+    wager: u32,
+}
+
+impl TablePlayer {
+    /// Gets the player's name, unique at the table
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the player's chips
+    ///
+    /// Players may only double down when they hold enough chips to cover the doubled wager, so
+    /// this never goes below zero
+    pub fn chips(&self) -> i64 {
+        self.chips
+    }
+
+    /// Gets the wager the player places each round
+    pub fn wager(&self) -> u32 {
+        self.wager
+    }
+}
+
+/// A Blackjack table that players buy in to and cash out of between rounds
+///
+/// Players sit in the order they bought in, and move down a seat when a player before them leaves
+/// # Examples
+/// ```
+/// # use naipe::games::blackjack::{basic_strategy, BlackjackAction, BlackjackView};
+/// # use naipe::games::blackjack::rules::BlackjackRules;
+/// # use naipe::games::blackjack::table::BlackjackTable;
+/// use rand::{rngs::StdRng, SeedableRng};
+/// let mut table =
+///     BlackjackTable::with_rng(BlackjackRules::default(), &mut StdRng::seed_from_u64(1)).unwrap();
+/// table.buy_in("alice", 100, 10).unwrap();
+/// table.buy_in("bob", 50, 5).unwrap();
+///
+/// let mut alice = basic_strategy;
+/// let mut bob = |_: &BlackjackView| BlackjackAction::Stand;
+/// let results = table.play_round(&mut [&mut alice, &mut bob]).unwrap();
+/// assert_eq!(results.len(), 2);
+///
+/// let chips = table.cash_out("bob").unwrap();
+/// assert_eq!(chips, 50 + results[1].net());
+/// assert_eq!(table.seat_of("alice"), Some(0));
+/// assert_eq!(table.seat_of("bob"), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlackjackTable {
+    game: BlackjackGame,
+    players: Vec<TablePlayer>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    id: Option<TableId>,
+}
+
+impl BlackjackTable {
+    /// Opens an empty table with the given house rules
+    /// # Errors
+    /// A [`RuleConflict`] if the rules do not make a playable game
+    pub fn new(rules: BlackjackRules) -> Result<BlackjackTable, RuleConflict> {
+        BlackjackTable::with_rng(rules, &mut GameRng::from_entropy())
+    }
+
+    /// Opens an empty table with the given house rules, shuffling with a generator seeded from
+    /// the given one
+    /// # Errors
+    /// A [`RuleConflict`] if the rules do not make a playable game
+    pub fn with_rng<R: Rng + ?Sized>(
+        rules: BlackjackRules,
+        rng: &mut R,
+    ) -> Result<BlackjackTable, RuleConflict> {
+        rules.validate()?;
+        Ok(BlackjackTable {
+            game: BlackjackGame::open(rules, 0, GameRng::from_seed(rng.gen())),
+            players: vec![],
+            id: None,
+        })
+    }
+
+    /// Names the table, such as to find it again among stored sessions
+    /// # Examples
+    /// ```
+    /// # use naipe::games::blackjack::rules::BlackjackRules;
+    /// # use naipe::games::blackjack::table::BlackjackTable;
+    /// # use naipe::games::id::TableId;
+    /// let mut table = BlackjackTable::new(BlackjackRules::default()).unwrap();
+    /// assert_eq!(table.id(), None);
+    /// table.set_id(TableId::new("high-rollers"));
+    /// assert_eq!(table.id().map(TableId::as_str), Some("high-rollers"));
+    /// ```
+    pub fn set_id(&mut self, id: TableId) {
+        self.id = Some(id);
+    }
+
+    /// Gets the table's name, if it has one
+    pub fn id(&self) -> Option<&TableId> {
+        self.id.as_ref()
+    }
+
+    /// Seats a new player with the given chips and wager, returning their seat
+    /// # Errors
+    /// [`TableError::AlreadySeated`] if a player with the same name is already seated,
+    /// [`TableError::InsufficientChips`] if the player cannot cover their wager, or
+    /// [`TableError::Game`] if a round is in progress
+    pub fn buy_in(
+        &mut self,
+        name: impl Into<String>,
+        chips: u32,
+        wager: u32,
+    ) -> Result<usize, TableError> {
+        let name = name.into();
+        if self.seat_of(&name).is_some() {
+            return Err(TableError::AlreadySeated(name));
+        }
+        if wager > chips {
+            return Err(TableError::InsufficientChips(name));
+        }
+        let seat = self.game.join(wager)?;
+        self.players.push(TablePlayer {
+            name,
+            chips: i64::from(chips),
+            wager,
+        });
+        Ok(seat)
+    }
+
+    /// Adds chips for a seated player
+    /// # Errors
+    /// [`TableError::NotSeated`] if no player has the name
+    pub fn rebuy(&mut self, name: &str, chips: u32) -> Result<(), TableError> {
+        let seat = self.seat(name)?;
+        self.players[seat].chips += i64::from(chips);
+        Ok(())
+    }
+
+    /// Changes the wager a seated player places from the next round
+    /// # Errors
+    /// [`TableError::NotSeated`] if no player has the name, or
+    /// [`TableError::InsufficientChips`] if the player cannot cover the wager
+    pub fn set_wager(&mut self, name: &str, wager: u32) -> Result<(), TableError> {
+        let seat = self.seat(name)?;
+        if i64::from(wager) > self.players[seat].chips {
+            return Err(TableError::InsufficientChips(name.to_string()));
+        }
+        self.game.set_wager(seat, wager)?;
+        self.players[seat].wager = wager;
+        Ok(())
+    }
+
+    /// Removes a player from the table, returning their chips
+    /// # Errors
+    /// [`TableError::NotSeated`] if no player has the name, or
+    /// [`TableError::Game`] if a round is in progress
+    pub fn cash_out(&mut self, name: &str) -> Result<i64, TableError> {
+        let seat = self.seat(name)?;
+        self.game.leave(seat)?;
+        Ok(self.players.remove(seat).chips)
+    }
+
+    /// Gets the seat of the named player, if they are at the table
+    pub fn seat_of(&self, name: &str) -> Option<usize> {
+        self.players.iter().position(|player| player.name == name)
+    }
+
+    /// Gets the players at the table, in seat order
+    pub fn players(&self) -> &[TablePlayer] {
+        &self.players
+    }
+
+    /// Gets the game being played at the table
+    pub fn game(&self) -> &BlackjackGame {
+        &self.game
+    }
+
+    /// Plays a whole round with one agent per seat, paying out each player's winnings
+    /// # Errors
+    /// [`TableError::InsufficientChips`] if a player cannot cover their wager, or
+    /// [`TableError::Game`] if the round could not be played
+    ///
+    /// Players are only allowed to double down if their chips cover the doubled wager
+    /// # Examples
+    /// ```
+    /// # use naipe::games::blackjack::{BlackjackAction, BlackjackView};
+    /// # use naipe::games::blackjack::rules::BlackjackRules;
+    /// # use naipe::games::blackjack::table::BlackjackTable;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// let mut table =
+    ///     BlackjackTable::with_rng(BlackjackRules::default(), &mut StdRng::seed_from_u64(2)).unwrap();
+    /// table.buy_in("carol", 15, 10).unwrap();
+    /// let mut carol = |view: &BlackjackView| {
+    ///     assert!(!view.can_double);
+    ///     BlackjackAction::Stand
+    /// };
+    /// table.play_round(&mut [&mut carol]).unwrap();
+    /// assert!(table.players()[0].chips() >= 0);
+    /// ```
+    pub fn play_round(
+        &mut self,
+        agents: &mut [&mut dyn Agent<BlackjackView, BlackjackAction>],
+    ) -> Result<Vec<SeatResult>, TableError> {
+        if let Some(player) = self
+            .players
+            .iter()
+            .find(|player| i64::from(player.wager) > player.chips)
+        {
+            return Err(TableError::InsufficientChips(player.name.clone()));
+        }
+        for (seat, player) in self.players.iter().enumerate() {
+            let bankroll = u32::try_from(player.chips).unwrap_or(u32::MAX);
+            self.game.set_bankroll(seat, Some(bankroll))?;
+        }
+        while !self.game.tick_with_agents(agents)? {}
+        let results = self.game.results().to_vec();
+        for (player, result) in self.players.iter_mut().zip(&results) {
+            player.chips += result.net();
+        }
+        self.game.new_round();
+        Ok(results)
+    }
+
+    fn seat(&self, name: &str) -> Result<usize, TableError> {
+        self.seat_of(name)
+            .ok_or_else(|| TableError::NotSeated(name.to_string()))
+    }
+}
+
+/// Errors related to players joining, leaving, and betting at a table
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum TableError {
+    #[error("{0} is already seated")]
+    AlreadySeated(String),
+    #[error("{0} is not seated")]
+    NotSeated(String),
+    #[error("{0} does not have enough chips to cover their wager")]
+    InsufficientChips(String),
+    #[error(transparent)]
+    Game(#[from] BlackjackError),
+}
@@ -0,0 +1,161 @@
+//! Type-erased games, so games of different types can be held together
+//!
+//! [`AgentGame`] has associated types, so it cannot be made into a trait object. [`DynGame`]
+//! erases them by exchanging views, actions, and outcomes as text, and is implemented for every
+//! game implementing [`ErasedGame`].
+
+use std::any::Any;
+use std::fmt::Debug;
+
+use thiserror::Error;
+
+use super::{
+    agent::{Agent, AgentGame},
+    info::{GameInfo, GameMetadata},
+};
+use crate::sealed::Sealed;
+
+/// How a game's views, actions, and outcomes are written as text
+pub trait ErasedGame: AgentGame<TickOk = bool> {
+    /// Writes what a seat can see as text
+    fn encode_view(view: &Self::View) -> String;
+
+    /// Reads an action from text, or `None` if it is not an action of this game
+    fn decode_action(action: &str) -> Option<Self::Action>;
+
+    /// Gets the action taken for a seat whose agent gave text that could not be read
+    fn fallback_action(view: &Self::View) -> Self::Action;
+
+    /// Describes the result, or `None` if play is unfinished
+    fn outcome(&self) -> Option<String>;
+}
+
+/// A game of any type, playable through text
+///
+/// The trait is sealed. Games get it by implementing [`ErasedGame`] and [`GameInfo`]
+/// # Examples
+/// ```
+/// # use naipe::games::agent::Agent;
+/// # use naipe::games::dynamic::DynGame;
+/// # use naipe::games::hearts::HeartsGame;
+/// # use naipe::games::war::WarGame;
+/// # use naipe::games::SeededGame;
+/// // Plays the first legal card in Hearts, while any text flips a card in War
+/// fn first_legal(view: &String) -> String {
+///     view.split("legal=[")
+///         .nth(1)
+///         .and_then(|rest| rest.split([',', ']']).next())
+///         .unwrap_or("flip")
+///         .to_string()
+/// }
+///
+/// let mut games: Vec<Box<dyn DynGame>> = vec![
+///     Box::new(WarGame::from_seed(1)),
+///     Box::new(HeartsGame::from_seed(2)),
+/// ];
+/// for game in &mut games {
+///     let mut players = vec![first_legal as fn(&String) -> String; game.seat_count()];
+///     let mut agents: Vec<&mut dyn Agent<String, String>> = players
+///         .iter_mut()
+///         .map(|player| player as &mut dyn Agent<String, String>)
+///         .collect();
+///     while !game.tick_with_agents(&mut agents).unwrap() {}
+///     println!("{} finished: {}", game.metadata().name, game.outcome().unwrap());
+/// }
+/// ```
+pub trait DynGame: Sealed {
+    /// Gets the description of the game
+    fn metadata(&self) -> GameMetadata;
+
+    /// Gets the number of seats, and so the number of agents needed to play
+    fn seat_count(&self) -> usize;
+
+    /// Gets what the given seat is able to see of the game, as text
+    fn view(&self, seat: usize) -> String;
+
+    /// Tick the game, asking agents for any decisions needed as text
+    ///
+    /// Agents are given in seat order. Text that is not an action of the game is replaced with
+    /// the game's fallback action.
+    /// # Errors
+    /// [`DynGameError::Tick`] describing the game's own error
+    fn tick_with_agents(
+        &mut self,
+        agents: &mut [&mut dyn Agent<String, String>],
+    ) -> Result<bool, DynGameError>;
+
+    /// Describes the result, or `None` if play is unfinished
+    fn outcome(&self) -> Option<String>;
+
+    /// Gets the game as [`Any`], to downcast to its concrete type
+    fn as_any(&self) -> &dyn Any;
+
+    /// Converts the game into [`Any`], to downcast to its concrete type
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<G> Sealed for G
+where
+    G: ErasedGame + GameInfo + 'static,
+    G::TickError: Debug,
+{
+}
+
+impl<G> DynGame for G
+where
+    G: ErasedGame + GameInfo + 'static,
+    G::TickError: Debug,
+{
+    fn metadata(&self) -> GameMetadata {
+        G::info()
+    }
+
+    fn seat_count(&self) -> usize {
+        AgentGame::seat_count(self)
+    }
+
+    fn view(&self, seat: usize) -> String {
+        G::encode_view(&AgentGame::view(self, seat))
+    }
+
+    fn tick_with_agents(
+        &mut self,
+        agents: &mut [&mut dyn Agent<String, String>],
+    ) -> Result<bool, DynGameError> {
+        let mut decoders: Vec<_> = agents
+            .iter_mut()
+            .map(|agent| {
+                move |view: &G::View| {
+                    let action = agent.choose_action(&G::encode_view(view));
+                    G::decode_action(&action).unwrap_or_else(|| G::fallback_action(view))
+                }
+            })
+            .collect();
+        let mut decoders: Vec<&mut dyn Agent<G::View, G::Action>> = decoders
+            .iter_mut()
+            .map(|decoder| decoder as &mut dyn Agent<G::View, G::Action>)
+            .collect();
+        AgentGame::tick_with_agents(self, &mut decoders)
+            .map_err(|err| DynGameError::Tick(format!("{err:?}")))
+    }
+
+    fn outcome(&self) -> Option<String> {
+        ErasedGame::outcome(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// Errors related to playing a type-erased game
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DynGameError {
+    #[error("The game could not advance: {0}")]
+    Tick(String),
+}
@@ -0,0 +1,190 @@
+//! Structured events emitted by games, and replays built from them
+//!
+//! Games implementing [`EventSource`] report what happened during each tick as typed events,
+//! which can be forwarded to any [`EventSink`], or recorded into a [`Replay`]
+
+use thiserror::Error;
+
+use super::{id::ReplayId, Game, SeededGame};
+
+/// A game that reports what happened during each tick as events
+pub trait EventSource {
+    type Event;
+
+    /// Gets the events emitted during the most recent tick
+    fn events(&self) -> &[Self::Event];
+}
+
+/// A subscriber to events emitted by games
+pub trait EventSink<E> {
+    /// Receives a single event
+    fn emit(&mut self, event: E);
+}
+
+impl<E> EventSink<E> for Vec<E> {
+    fn emit(&mut self, event: E) {
+        self.push(event);
+    }
+}
+
+/// Wraps a closure, so it can be used as an [`EventSink`]
+/// # Examples
+/// ```
+/// # use naipe::games::event::{EventSink, FnSink};
+/// let mut count = 0;
+/// let mut sink = FnSink(|_: &str| count += 1);
+/// sink.emit("hello");
+/// sink.emit("world");
+/// assert_eq!(count, 2);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct FnSink<F>(pub F);
+
+impl<E, F: FnMut(E)> EventSink<E> for FnSink<F> {
+    fn emit(&mut self, event: E) {
+        (self.0)(event);
+    }
+}
+
+/// Ticks a game, forwarding the events from the tick to the sink
+/// # Errors
+/// Any error from ticking the game, in which case no events are forwarded
+pub fn tick_into<G, S>(game: &mut G, sink: &mut S) -> Result<G::TickOk, G::TickError>
+where
+    G: Game + EventSource,
+    G::Event: Clone,
+    S: EventSink<G::Event>,
+{
+    let result = game.tick()?;
+    for event in game.events() {
+        sink.emit(event.clone());
+    }
+    Ok(result)
+}
+
+/// A recording of a seeded game, as the seed and every event it emitted
+///
+/// Games driven entirely by their seed can be re-driven from the recording, checking that
+/// the current rules reproduce the same events
+/// # Examples
+/// ```
+/// # use naipe::games::event::{Replay, ReplayError};
+/// # use naipe::games::war::{WarEvent, WarGame};
+/// let mut replay = Replay::record::<WarGame>(11, 100_000).unwrap();
+/// let game: WarGame = replay.verify().unwrap();
+/// assert!(game.player_1_won() || game.player_2_won());
+///
+/// replay.events[0] = WarEvent::GameOver { winner: 0 };
+/// assert_eq!(
+///     replay.verify::<WarGame>(),
+///     Err(ReplayError::Diverged { index: 0 })
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay<E> {
+    /// Names the recording, if it has been stored or shared
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub id: Option<ReplayId>,
+    /// The seed the game was created from
+    pub seed: u64,
+    /// The events of every tick, in order
+    pub events: Vec<E>,
+}
+
+impl<E: Clone + PartialEq> Replay<E> {
+    /// Records a game created from the seed, ticking it until it finishes
+    /// # Errors
+    /// [`ReplayError::TooLong`] if the game does not finish within `max_ticks` ticks, or
+    /// [`ReplayError::TickFailed`] if a tick fails
+    pub fn record<G>(seed: u64, max_ticks: usize) -> Result<Replay<E>, ReplayError>
+    where
+        G: SeededGame + Game<TickOk = bool> + EventSource<Event = E>,
+    {
+        let mut game = G::from_seed(seed);
+        let mut events = vec![];
+        for _ in 0..max_ticks {
+            let finished =
+                tick_into(&mut game, &mut events).map_err(|_| ReplayError::TickFailed)?;
+            if finished {
+                return Ok(Replay {
+                    id: None,
+                    seed,
+                    events,
+                });
+            }
+        }
+        Err(ReplayError::TooLong)
+    }
+
+    /// Names the recording
+    /// # Examples
+    /// ```
+    /// # use naipe::games::event::Replay;
+    /// # use naipe::games::id::ReplayId;
+    /// # use naipe::games::war::{WarEvent, WarGame};
+    /// let replay: Replay<WarEvent> = Replay::record::<WarGame>(4, 100_000).unwrap();
+    /// let replay = replay.with_id(ReplayId::new("first-game"));
+    /// assert_eq!(replay.id.unwrap().as_str(), "first-game");
+    /// ```
+    #[must_use]
+    pub fn with_id(mut self, id: ReplayId) -> Replay<E> {
+        self.id = Some(id);
+        self
+    }
+
+    /// Re-drives the game from the seed, handing each event and the game state after its tick to
+    /// the closure, and checking each event matches the recording
+    /// # Errors
+    /// [`ReplayError::Diverged`] if the game emits a different event than recorded,
+    /// [`ReplayError::TooLong`] if the game emits more events than recorded, or
+    /// [`ReplayError::TickFailed`] if a tick fails
+    pub fn play_back<G, F>(&self, mut on_event: F) -> Result<G, ReplayError>
+    where
+        G: SeededGame + Game<TickOk = bool> + EventSource<Event = E>,
+        F: FnMut(&G, &E),
+    {
+        let mut game = G::from_seed(self.seed);
+        let mut recorded = self.events.iter().enumerate();
+        loop {
+            let finished = game.tick().map_err(|_| ReplayError::TickFailed)?;
+            for event in game.events() {
+                let Some((index, expected)) = recorded.next() else {
+                    return Err(ReplayError::TooLong);
+                };
+                if event != expected {
+                    return Err(ReplayError::Diverged { index });
+                }
+                on_event(&game, event);
+            }
+            if finished {
+                return match recorded.next() {
+                    Some((index, _)) => Err(ReplayError::Diverged { index }),
+                    None => Ok(game),
+                };
+            }
+        }
+    }
+
+    /// Re-drives the game from the seed, checking it reproduces the recording exactly
+    /// # Errors
+    /// As for [`Replay::play_back`]
+    pub fn verify<G>(&self) -> Result<G, ReplayError>
+    where
+        G: SeededGame + Game<TickOk = bool> + EventSource<Event = E>,
+    {
+        self.play_back(|_, _| ())
+    }
+}
+
+/// Errors related to recording and replaying games
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReplayError {
+    #[error("Replay diverged from the recording at event {index}")]
+    Diverged { index: usize },
+    #[error("Game ran longer than the recording allows")]
+    TooLong,
+    #[error("Game failed to tick")]
+    TickFailed,
+}
@@ -0,0 +1,748 @@
+//! An implementation of the card game Hearts, built on the [`crate::games::tricks`] engine
+//!
+//! Four players each take 13 cards, with no passing. The two of clubs leads the first trick,
+//! on which no points may be played unless a player holds nothing else, and hearts may not be led
+//! until one has been played. Each heart counts one point and the queen of spades thirteen, unless
+//! one player takes all 26 and shoots the moon. The game ends once a player reaches 100 points,
+//! and the lowest score wins.
+
+use log::debug;
+use rand::{Rng, SeedableRng};
+use thiserror::Error;
+
+use crate::common::{
+    card::{Card, Rank, Suit},
+    deck::Deck,
+    hand::Hand,
+    inference::VoidTracker,
+};
+use crate::games::{
+    agent::{Agent, AgentGame},
+    audit::{AuditedGame, RandomnessRecord},
+    dynamic::ErasedGame,
+    event::EventSource,
+    info::{GameInfo, GameMetadata, GameTag},
+    penalty::{PenaltyLog, PenaltyTable, Resolution, Violation, ViolationKind},
+    tricks::{check_claim, Objective, Trick, TrickClaim, TrickPlayout, TrickRules},
+    Claim, ClaimError, Concede, ConcedeError, GameOutcome, GameRng, SeededGame,
+};
+
+/// The number of players in a game of Hearts
+pub const HEARTS_SEATS: usize = 4;
+
+/// The game ends once any player's score reaches this many points
+const GAME_OVER_SCORE: u32 = 100;
+
+/// The total points in a deal, all of which must be taken to shoot the moon
+const MOON: u32 = 26;
+
+/// The number of tricks in a deal
+const TRICKS_PER_DEAL: usize = 13;
+
+/// The most positions played out to check a claim, beyond which it is unproven
+const CLAIM_POSITIONS: u64 = 1_000_000;
+
+/// Gets the points a card is worth when taken in a trick
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::games::hearts::card_points;
+/// assert_eq!(card_points(Card::new(Suit::Heart, Rank::Two)), 1);
+/// assert_eq!(card_points(Card::new(Suit::Spade, Rank::Queen)), 13);
+/// assert_eq!(card_points(Card::new(Suit::Club, Rank::Ace)), 0);
+/// ```
+pub fn card_points(card: Card) -> u32 {
+    match (card.get_suit(), card.get_rank()) {
+        (Suit::Heart, _) => 1,
+        (Suit::Spade, Rank::Queen) => 13,
+        _ => 0,
+    }
+}
+
+/// What a player can see of a game of Hearts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeartsView {
+    /// The seat of the player, counting from zero
+    pub seat: usize,
+    pub hand: Hand,
+    /// The cards the player may play now
+    pub legal_plays: Vec<Card>,
+    /// The cards played to the current trick, and who played them
+    pub trick: Vec<(usize, Card)>,
+    pub hearts_broken: bool,
+    /// The points each seat has taken so far this deal
+    pub taken: Vec<u32>,
+    /// Each seat's score from previous deals
+    pub scores: Vec<u32>,
+    /// The suits each seat has shown out of this deal
+    pub voids: VoidTracker,
+}
+
+/// Something that happened during a tick of Hearts
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum HeartsEvent {
+    /// A seat played a card to the trick
+    CardPlayed { seat: usize, card: Card },
+    /// A seat did not follow the suit led, showing it holds none
+    ShowedOut { seat: usize, suit: Suit },
+    /// A seat took the trick, and the points in it
+    TrickTaken { seat: usize, points: u32 },
+    /// The deal ended, leaving each seat with the given score
+    DealScored { scores: Vec<u32> },
+    /// In audit mode, the cards were shuffled for a deal
+    Randomness(RandomnessRecord),
+}
+
+/// Game state for the game of Hearts
+///
+/// Each call to [`AgentGame::tick_with_agents`] asks the seat due to play for a single card,
+/// returning `true` once the game is over
+/// # Examples
+/// ```
+/// # use naipe::games::agent::AgentGame;
+/// # use naipe::games::hearts::{play_lowest, HeartsGame};
+/// # use naipe::games::{GameOutcome, SeededGame};
+/// let mut game = HeartsGame::from_seed(5);
+/// let (mut north, mut east, mut south, mut west) =
+///     (play_lowest, play_lowest, play_lowest, play_lowest);
+/// while !game
+///     .tick_with_agents(&mut [&mut north, &mut east, &mut south, &mut west])
+///     .unwrap()
+/// {}
+/// assert!(game.scores().iter().any(|score| *score >= 100));
+/// if let Some(winner) = game.winner() {
+///     assert!(game.scores().iter().all(|score| *score >= game.scores()[winner]));
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::struct_excessive_bools)]
+pub struct HeartsGame {
+    hands: Vec<Hand>,
+    scores: Vec<u32>,
+    taken: Vec<u32>,
+    trick: Trick,
+    tricks_played: usize,
+    hearts_broken: bool,
+    over: bool,
+    rules: TrickRules,
+    penalties: PenaltyTable,
+    violations: PenaltyLog,
+    rng: GameRng,
+    #[cfg_attr(feature = "serde", serde(default = "no_voids"))]
+    voids: VoidTracker,
+    #[cfg_attr(feature = "serde", serde(default))]
+    conceded: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<HeartsEvent>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    audit: bool,
+    /// Whether the game has ticked, after which the deal's events are no longer pending
+    #[cfg_attr(feature = "serde", serde(default))]
+    ticked: bool,
+}
+
+/// Starts tracking voids for a game saved before voids were tracked
+#[cfg(feature = "serde")]
+fn no_voids() -> VoidTracker {
+    VoidTracker::new(HEARTS_SEATS)
+}
+
+impl HeartsGame {
+    /// Sets the penalties assessed for illegal plays, which only warn by default
+    ///
+    /// Penalty points are added to the offender's score at the end of the deal.
+    /// A dead hand ends the deal at once, with the offender taking every point not yet taken,
+    /// and an aborted deal is redealt without scoring the points taken
+    #[must_use]
+    pub fn with_penalties(mut self, penalties: PenaltyTable) -> HeartsGame {
+        self.penalties = penalties;
+        self
+    }
+
+    /// Sets whether every later deal is recorded as a [`HeartsEvent::Randomness`] event
+    ///
+    /// Auditing draws the same random numbers, so play is unchanged. To record the first deal
+    /// too, create the game with [`AuditedGame`]
+    pub fn set_audit(&mut self, audit: bool) {
+        self.audit = audit;
+    }
+
+    /// Finds if every deal is recorded
+    pub fn is_audited(&self) -> bool {
+        self.audit
+    }
+
+    /// Gets each seat's score from completed deals
+    pub fn scores(&self) -> &[u32] {
+        &self.scores
+    }
+
+    /// Gets the points each seat has taken so far this deal
+    pub fn taken(&self) -> &[u32] {
+        &self.taken
+    }
+
+    /// Gets the cards held by a seat
+    pub fn hand(&self, seat: usize) -> Option<&Hand> {
+        self.hands.get(seat)
+    }
+
+    /// Gets the trick in progress
+    pub fn trick(&self) -> &Trick {
+        &self.trick
+    }
+
+    /// Gets the suits each seat has shown out of this deal, by failing to follow suit
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::hearts::{play_lowest, HeartsGame};
+    /// # use naipe::games::SeededGame;
+    /// let mut game = HeartsGame::from_seed(5);
+    /// let (mut north, mut east, mut south, mut west) =
+    ///     (play_lowest, play_lowest, play_lowest, play_lowest);
+    /// let shown_out = |game: &HeartsGame| (0..4).find(|seat| !game.voids().voids(*seat).is_empty());
+    /// while shown_out(&game).is_none() {
+    ///     game.tick_with_agents(&mut [&mut north, &mut east, &mut south, &mut west])
+    ///         .unwrap();
+    /// }
+    /// let seat = shown_out(&game).unwrap();
+    /// let suit = game.voids().voids(seat)[0];
+    /// assert!(game.hand(seat).unwrap().iter().all(|card| card.get_suit() != suit));
+    /// ```
+    pub fn voids(&self) -> &VoidTracker {
+        &self.voids
+    }
+
+    /// Gets the violations and penalties assessed this deal
+    pub fn violations(&self) -> &PenaltyLog {
+        &self.violations
+    }
+
+    /// Finds if a player has reached 100 points, ending the game
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    /// Gets every card the seat may play now, which is empty if it is not their turn
+    pub fn legal_plays(&self, seat: usize) -> Vec<Card> {
+        self.playout().legal_plays(seat)
+    }
+
+    /// Gets the trick play of the deal so far, to play out the rest
+    fn playout(&self) -> HeartsPlayout {
+        HeartsPlayout {
+            hands: self.hands.clone(),
+            trick: self.trick.clone(),
+            tricks_played: self.tricks_played,
+            hearts_broken: self.hearts_broken,
+            rules: self.rules.clone(),
+        }
+    }
+
+    fn deal(&mut self) {
+        let mut deck = Deck::default();
+        if self.audit {
+            let record = RandomnessRecord::shuffle("deal", &mut deck, &mut self.rng);
+            self.events.push(HeartsEvent::Randomness(record));
+        } else {
+            deck.shuffle(&mut self.rng);
+        }
+        let mut hands = vec![Hand::new(); HEARTS_SEATS];
+        deck.deal_all_cards_to_hands(&mut hands).unwrap();
+        let two_of_clubs = Card::new(Suit::Club, Rank::Two);
+        let leader = hands
+            .iter()
+            .position(|hand| hand.contains(two_of_clubs))
+            .unwrap_or_default();
+        self.hands = hands;
+        self.taken = vec![0; HEARTS_SEATS];
+        self.trick = Trick::new(leader, HEARTS_SEATS);
+        self.tricks_played = 0;
+        self.hearts_broken = false;
+        self.violations.clear();
+        self.voids = VoidTracker::new(HEARTS_SEATS);
+    }
+
+    fn play(&mut self, seat: usize, card: Card) -> Result<(), Violation> {
+        if !self.legal_plays(seat).contains(&card) {
+            self.trick
+                .check_play(seat, card, &self.hands[seat], &self.rules)?;
+            let kind = if self.trick.plays().is_empty() {
+                ViolationKind::IllegalLead
+            } else {
+                ViolationKind::IllegalCard
+            };
+            return Err(Violation::with_card(seat, kind, card));
+        }
+        let led = self.trick.led_suit();
+        self.trick
+            .play_tracked(
+                seat,
+                card,
+                &mut self.hands[seat],
+                &self.rules,
+                &mut self.voids,
+            )
+            .map_err(|_| Violation::with_card(seat, ViolationKind::IllegalCard, card))?;
+        debug!("Seat {seat} played {card}");
+        self.events.push(HeartsEvent::CardPlayed { seat, card });
+        if let Some(suit) = led.filter(|suit| *suit != card.get_suit()) {
+            self.events.push(HeartsEvent::ShowedOut { seat, suit });
+        }
+        self.hearts_broken |= card.get_suit() == Suit::Heart;
+        if let Some(winner) = self.trick.winner(&self.rules) {
+            let points: u32 = self.trick.cards().into_iter().map(card_points).sum();
+            debug!("Seat {winner} takes the trick, for {points} points");
+            self.events.push(HeartsEvent::TrickTaken {
+                seat: winner,
+                points,
+            });
+            self.taken[winner] += points;
+            self.tricks_played += 1;
+            self.trick = Trick::new(winner, HEARTS_SEATS);
+            if self.tricks_played == TRICKS_PER_DEAL {
+                self.end_deal(None);
+            }
+        }
+        Ok(())
+    }
+
+    /// Scores the deal, optionally with a dead hand taking every point not yet taken,
+    /// then deals again unless the game is over
+    fn end_deal(&mut self, dead_hand: Option<usize>) {
+        if let Some(seat) = dead_hand {
+            let remaining = MOON - self.taken.iter().sum::<u32>();
+            self.taken[seat] += remaining;
+        } else if let Some(shooter) = self.taken.iter().position(|taken| *taken == MOON) {
+            debug!("Seat {shooter} shot the moon");
+            for (seat, taken) in self.taken.iter_mut().enumerate() {
+                *taken = if seat == shooter { 0 } else { MOON };
+            }
+        }
+        self.apply_penalties(&self.taken.clone());
+        self.events.push(HeartsEvent::DealScored {
+            scores: self.scores.clone(),
+        });
+        self.over = self.scores.iter().any(|score| *score >= GAME_OVER_SCORE);
+        if !self.over {
+            self.deal();
+        }
+    }
+
+    fn apply_penalties(&mut self, taken: &[u32]) {
+        let adjustments = self.violations.score_adjustments(HEARTS_SEATS);
+        for ((score, taken), adjustment) in self.scores.iter_mut().zip(taken).zip(adjustments) {
+            *score = (*score + taken).saturating_add_signed(adjustment);
+        }
+    }
+}
+
+/// The trick play of a deal, without its scoring, as played out to check a claim
+#[derive(Clone, Debug)]
+struct HeartsPlayout {
+    hands: Vec<Hand>,
+    trick: Trick,
+    tricks_played: usize,
+    hearts_broken: bool,
+    rules: TrickRules,
+}
+
+impl HeartsPlayout {
+    /// Gets every card the seat may play now, under the restrictions on leading and on the first
+    /// trick
+    fn legal_plays(&self, seat: usize) -> Vec<Card> {
+        let Some(hand) = self.hands.get(seat) else {
+            return vec![];
+        };
+        if self.trick.next_seat() != Some(seat) || hand.is_empty() {
+            return vec![];
+        }
+        let plays = self.trick.legal_plays(hand, &self.rules);
+        let first_trick = self.tricks_played == 0;
+        if self.trick.plays().is_empty() {
+            if first_trick {
+                return vec![Card::new(Suit::Club, Rank::Two)];
+            }
+            if !self.hearts_broken {
+                return prefer(plays, |card| card.get_suit() != Suit::Heart);
+            }
+            return plays;
+        }
+        if first_trick {
+            return prefer(plays, |card| card_points(*card) == 0);
+        }
+        plays
+    }
+}
+
+impl TrickPlayout for HeartsPlayout {
+    fn current_trick(&self) -> &Trick {
+        &self.trick
+    }
+
+    fn tricks_left(&self) -> usize {
+        TRICKS_PER_DEAL - self.tricks_played
+    }
+
+    fn playable(&self) -> Vec<Card> {
+        self.trick
+            .next_seat()
+            .map(|seat| self.legal_plays(seat))
+            .unwrap_or_default()
+    }
+
+    fn play_out(&mut self, card: Card) -> Option<usize> {
+        let seat = self.trick.next_seat()?;
+        self.trick
+            .play(seat, card, self.hands.get_mut(seat)?, &self.rules)
+            .ok()?;
+        self.hearts_broken |= card.get_suit() == Suit::Heart;
+        let winner = self.trick.winner(&self.rules)?;
+        self.tricks_played += 1;
+        self.trick = Trick::new(winner, HEARTS_SEATS);
+        Some(winner)
+    }
+}
+
+/// Keeps only the plays matching the predicate, unless none do
+fn prefer<F: Fn(&Card) -> bool>(plays: Vec<Card>, predicate: F) -> Vec<Card> {
+    if plays.iter().any(&predicate) {
+        plays.into_iter().filter(predicate).collect()
+    } else {
+        plays
+    }
+}
+
+/// A simple strategy, playing the lowest ranked legal card
+///
+/// Falls back to the two of clubs when no play is legal, which only happens out of turn
+pub fn play_lowest(view: &HeartsView) -> Card {
+    view.legal_plays
+        .iter()
+        .copied()
+        .min_by_key(|card| (card.get_rank(), card.get_suit()))
+        .unwrap_or(Card::new(Suit::Club, Rank::Two))
+}
+
+impl HeartsGame {
+    fn open<R: Rng + ?Sized>(rng: &mut R, audit: bool) -> HeartsGame {
+        let mut game = HeartsGame {
+            hands: vec![],
+            scores: vec![0; HEARTS_SEATS],
+            taken: vec![0; HEARTS_SEATS],
+            trick: Trick::new(0, HEARTS_SEATS),
+            tricks_played: 0,
+            hearts_broken: false,
+            over: false,
+            rules: TrickRules {
+                objective: Objective::Avoid,
+                ..TrickRules::default()
+            },
+            penalties: PenaltyTable::default(),
+            violations: PenaltyLog::new(),
+            rng: GameRng::from_seed(rng.gen()),
+            voids: VoidTracker::new(HEARTS_SEATS),
+            conceded: None,
+            events: vec![],
+            audit,
+            ticked: false,
+        };
+        game.deal();
+        game
+    }
+}
+
+impl SeededGame for HeartsGame {
+    /// Creates a game of Hearts, dealing with a generator seeded from the given one
+    fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> HeartsGame {
+        HeartsGame::open(rng, false)
+    }
+}
+
+impl AuditedGame for HeartsGame {
+    /// Creates a game of Hearts recording every deal, with the first deal's record given by
+    /// [`EventSource::events`] before the first tick
+    fn audited_with_rng<R: Rng + ?Sized>(rng: &mut R) -> HeartsGame {
+        HeartsGame::open(rng, true)
+    }
+}
+
+impl GameInfo for HeartsGame {
+    fn info() -> GameMetadata {
+        GameMetadata {
+            name: "Hearts",
+            summary: "Four players take tricks, trying to avoid hearts and the queen of spades",
+            min_players: HEARTS_SEATS,
+            max_players: HEARTS_SEATS,
+            decks: 1,
+            minutes: 30,
+            tags: &[GameTag::TrickTaking, GameTag::Evasion],
+        }
+    }
+}
+
+impl Default for HeartsGame {
+    /// Creates a game of Hearts seeded from [`rand::thread_rng`]
+    fn default() -> HeartsGame {
+        HeartsGame::new_with_rng(&mut rand::thread_rng())
+    }
+}
+
+impl AgentGame for HeartsGame {
+    type View = HeartsView;
+    type Action = Card;
+    type TickOk = bool;
+    type TickError = HeartsError;
+
+    fn seat_count(&self) -> usize {
+        HEARTS_SEATS
+    }
+
+    fn view(&self, seat: usize) -> HeartsView {
+        HeartsView {
+            seat,
+            hand: self.hands.get(seat).cloned().unwrap_or_default(),
+            legal_plays: self.legal_plays(seat),
+            trick: self.trick.plays().to_vec(),
+            hearts_broken: self.hearts_broken,
+            taken: self.taken.clone(),
+            scores: self.scores.clone(),
+            voids: self.voids.clone(),
+        }
+    }
+
+    /// Asks the seat due to play for a card, and plays it
+    ///
+    /// An illegal play is assessed under the table's penalties and returned as an error.
+    /// Unless the penalty ends the deal, the same seat is asked again on the next tick
+    fn tick_with_agents(
+        &mut self,
+        agents: &mut [&mut dyn Agent<HeartsView, Card>],
+    ) -> Result<bool, HeartsError> {
+        if agents.len() != HEARTS_SEATS {
+            return Err(HeartsError::WrongAgentCount(agents.len()));
+        }
+        // The first deal's events stay pending until the first tick delivers them
+        if self.ticked {
+            self.events.clear();
+        }
+        self.ticked = true;
+        if self.over {
+            return Ok(true);
+        }
+        let seat = self.trick.next_seat().unwrap_or_default();
+        let card = agents[seat].choose_action(&self.view(seat));
+        if let Err(violation) = self.play(seat, card) {
+            debug!("{violation}");
+            let penalty = self.violations.record(violation, &self.penalties);
+            match penalty.resolution {
+                Resolution::Continue => {}
+                Resolution::DeadHand => self.end_deal(Some(seat)),
+                Resolution::AbortDeal => {
+                    self.apply_penalties(&[0; HEARTS_SEATS]);
+                    self.events.push(HeartsEvent::DealScored {
+                        scores: self.scores.clone(),
+                    });
+                    self.over = self.scores.iter().any(|score| *score >= GAME_OVER_SCORE);
+                    if !self.over {
+                        self.deal();
+                    }
+                }
+            }
+            return Err(HeartsError::Illegal(violation));
+        }
+        Ok(self.over)
+    }
+}
+
+impl ErasedGame for HeartsGame {
+    /// Writes the view as `seat=<seat> hand=<cards> legal=<cards> trick=<seat:card,...>
+    /// hearts_broken=<bool> taken=<points,...> scores=<points,...>`
+    fn encode_view(view: &HeartsView) -> String {
+        let join = |values: Vec<String>| values.join(",");
+        format!(
+            "seat={} hand={} legal=[{}] trick=[{}] hearts_broken={} taken={} scores={}",
+            view.seat,
+            view.hand,
+            join(view.legal_plays.iter().map(ToString::to_string).collect()),
+            join(
+                view.trick
+                    .iter()
+                    .map(|(seat, card)| format!("{seat}:{card}"))
+                    .collect()
+            ),
+            view.hearts_broken,
+            join(view.taken.iter().map(ToString::to_string).collect()),
+            join(view.scores.iter().map(ToString::to_string).collect()),
+        )
+    }
+
+    /// Reads a card, such as `QS` or `Q♠`
+    fn decode_action(action: &str) -> Option<Card> {
+        action.parse().ok()
+    }
+
+    /// Plays the lowest legal card
+    fn fallback_action(view: &HeartsView) -> Card {
+        play_lowest(view)
+    }
+
+    fn outcome(&self) -> Option<String> {
+        if !self.over {
+            return None;
+        }
+        let scores = self
+            .scores
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(",");
+        Some(match self.winner() {
+            Some(winner) => format!("Player {} won with scores {scores}", winner + 1),
+            None => format!("Tied with scores {scores}"),
+        })
+    }
+}
+
+impl GameOutcome for HeartsGame {
+    /// Gets the seat with the lowest score once the game is over, unless it is tied
+    ///
+    /// A seat that conceded never wins
+    fn winner(&self) -> Option<usize> {
+        if !self.over {
+            return None;
+        }
+        let mut scores = self.scores.clone();
+        if let Some(seat) = self.conceded {
+            scores[seat] = u32::MAX;
+        }
+        Objective::Avoid.winner(&scores)
+    }
+}
+
+impl Concede for HeartsGame {
+    /// Concedes the game, with the conceding seat taking every point not yet taken this deal
+    ///
+    /// The deal is scored as for a dead hand, without shooting the moon, and the game ends with
+    /// the lowest score among the other seats winning
+    /// # Examples
+    /// ```
+    /// # use naipe::games::hearts::HeartsGame;
+    /// # use naipe::games::{Concede, GameOutcome, SeededGame};
+    /// let mut game = HeartsGame::from_seed(5);
+    /// game.concede(2).unwrap();
+    /// assert!(game.is_over());
+    /// assert_eq!(game.scores(), &[0, 0, 26, 0]);
+    /// assert_eq!(game.winner(), None);
+    /// assert!(game.concede(0).is_err());
+    /// assert!(HeartsGame::from_seed(5).concede(4).is_err());
+    /// ```
+    fn concede(&mut self, seat: usize) -> Result<(), ConcedeError> {
+        if self.over {
+            return Err(ConcedeError::GameOver);
+        }
+        if seat >= HEARTS_SEATS {
+            return Err(ConcedeError::UnknownSeat(seat));
+        }
+        let remaining = MOON - self.taken.iter().sum::<u32>();
+        self.taken[seat] += remaining;
+        self.apply_penalties(&self.taken.clone());
+        self.conceded = Some(seat);
+        self.over = true;
+        Ok(())
+    }
+}
+
+impl Claim for HeartsGame {
+    /// Claims every remaining trick of the deal, which holds if the seat takes them all however
+    /// the others play, under every restriction on leading and following
+    ///
+    /// The claiming seat takes every point not yet taken, and the deal is scored as though it had
+    /// been played out, shooting the moon if the seat took every point. Claims needing more than a
+    /// million positions played out to check are unproven.
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::hearts::{play_lowest, HeartsGame};
+    /// # use naipe::games::{Claim, ClaimError, SeededGame};
+    /// let mut game = HeartsGame::from_seed(17);
+    /// // With the whole deal to play, no seat can be sure of every trick
+    /// assert_eq!(game.claim(0), Err(ClaimError::Unproven));
+    ///
+    /// let (mut north, mut east, mut south, mut west) =
+    ///     (play_lowest, play_lowest, play_lowest, play_lowest);
+    /// while game.hand(0).unwrap().len() > 2 || !game.trick().plays().is_empty() {
+    ///     game.tick_with_agents(&mut [&mut north, &mut east, &mut south, &mut west])
+    ///         .unwrap();
+    /// }
+    /// // Seat 0 leads holding the aces of spades and hearts, with two tricks left
+    /// assert_eq!(game.hand(0).unwrap().to_string(), "[A♠,A♥]");
+    /// assert_eq!(game.taken(), &[4, 2, 0, 17]);
+    /// assert_eq!(game.claim(1), Err(ClaimError::Unproven));
+    ///
+    /// // The last three hearts go to seat 0, and the next deal starts
+    /// game.claim(0).unwrap();
+    /// assert_eq!(game.scores(), &[7, 2, 0, 17]);
+    /// assert_eq!(game.hand(0).unwrap().len(), 13);
+    /// ```
+    fn claim(&mut self, seat: usize) -> Result<(), ClaimError> {
+        if self.over {
+            return Err(ClaimError::GameOver);
+        }
+        if seat >= HEARTS_SEATS {
+            return Err(ClaimError::UnknownSeat(seat));
+        }
+        let tricks = TRICKS_PER_DEAL - self.tricks_played;
+        let claim = TrickClaim::AtLeast(tricks);
+        if check_claim(&self.playout(), seat, claim, CLAIM_POSITIONS) != Some(true) {
+            return Err(ClaimError::Unproven);
+        }
+        let remaining = MOON - self.taken.iter().sum::<u32>();
+        self.taken[seat] += remaining;
+        self.end_deal(None);
+        Ok(())
+    }
+}
+
+impl EventSource for HeartsGame {
+    type Event = HeartsEvent;
+
+    /// Gets the events from the most recent tick
+    ///
+    /// In audit mode, the first deal's record is given before the first tick, and again at the
+    /// start of the first tick's events
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::event::EventSource;
+    /// # use naipe::games::hearts::{play_lowest, HeartsEvent, HeartsGame};
+    /// # use naipe::games::SeededGame;
+    /// let mut game = HeartsGame::from_seed(5);
+    /// let (mut north, mut east, mut south, mut west) =
+    ///     (play_lowest, play_lowest, play_lowest, play_lowest);
+    /// game.tick_with_agents(&mut [&mut north, &mut east, &mut south, &mut west])
+    ///     .unwrap();
+    /// let two_of_clubs = "2C".parse().unwrap();
+    /// assert!(matches!(
+    ///     game.events(),
+    ///     [HeartsEvent::CardPlayed { card, .. }] if *card == two_of_clubs
+    /// ));
+    /// ```
+    fn events(&self) -> &[HeartsEvent] {
+        &self.events
+    }
+}
+
+/// Errors related to playing Hearts
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HeartsError {
+    #[error("Expected {HEARTS_SEATS} agents, but got {0}")]
+    WrongAgentCount(usize),
+    #[error("Illegal play: {0}")]
+    Illegal(Violation),
+}
@@ -0,0 +1,127 @@
+//! Hooks for keeping full replays of only the games worth a closer look
+//!
+//! Detectors are named predicates over every event of a finished game, so long simulations can
+//! keep the rare games of interest without recording everything
+
+use super::{
+    event::{EventSource, Replay, ReplayError},
+    Game, SeededGame,
+};
+
+/// A named predicate, checked against the events of each finished game
+type Detector<E> = (String, Box<dyn Fn(&[E]) -> bool>);
+
+/// A game that matched at least one detector
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Highlight<E> {
+    /// The full replay of the game
+    pub replay: Replay<E>,
+    /// The names of every detector the game matched
+    pub matched: Vec<String>,
+}
+
+/// Plays seeded games, capturing replays of those matching any registered detector
+/// # Examples
+/// ```
+/// # use naipe::games::highlight::Highlights;
+/// # use naipe::games::war::WarEvent;
+/// # use naipe::games::war::WarGame;
+/// use naipe::common::card::Rank;
+/// let mut highlights = Highlights::new(100_000)
+///     .detect("war over aces", |events: &[WarEvent]| {
+///         events
+///             .iter()
+///             .any(|event| matches!(event, WarEvent::WarStarted { rank: Rank::Ace }))
+///     })
+///     .detect("marathon", |events: &[WarEvent]| {
+///         let rounds = events
+///             .iter()
+///             .filter(|event| matches!(event, WarEvent::PileCaptured { .. }))
+///             .count();
+///         rounds > 1_000
+///     });
+/// for seed in 0..20 {
+///     highlights.play::<WarGame>(seed).unwrap();
+/// }
+/// assert_eq!(highlights.games_played(), 20);
+/// assert!(highlights.matching("war over aces").count() > 0);
+/// for highlight in highlights.captured() {
+///     assert!(!highlight.matched.is_empty());
+///     highlight.replay.verify::<WarGame>().unwrap();
+/// }
+/// ```
+pub struct Highlights<E> {
+    detectors: Vec<Detector<E>>,
+    max_ticks: usize,
+    games_played: u64,
+    captured: Vec<Highlight<E>>,
+}
+
+impl<E: Clone + PartialEq> Highlights<E> {
+    /// Creates a set of hooks with no detectors, giving up on games longer than `max_ticks`
+    pub fn new(max_ticks: usize) -> Highlights<E> {
+        Highlights {
+            detectors: vec![],
+            max_ticks,
+            games_played: 0,
+            captured: vec![],
+        }
+    }
+
+    /// Registers a named detector, which is given every event of each finished game
+    #[must_use]
+    pub fn detect<F>(mut self, name: impl Into<String>, predicate: F) -> Highlights<E>
+    where
+        F: Fn(&[E]) -> bool + 'static,
+    {
+        self.detectors.push((name.into(), Box::new(predicate)));
+        self
+    }
+
+    /// Plays a game created from the seed, keeping its replay if any detector matches
+    ///
+    /// Gets the new highlight, if the game was captured
+    /// # Errors
+    /// As for [`Replay::record`]
+    pub fn play<G>(&mut self, seed: u64) -> Result<Option<&Highlight<E>>, ReplayError>
+    where
+        G: SeededGame + Game<TickOk = bool> + EventSource<Event = E>,
+    {
+        let replay = Replay::record::<G>(seed, self.max_ticks)?;
+        self.games_played += 1;
+        let matched: Vec<String> = self
+            .detectors
+            .iter()
+            .filter(|(_, predicate)| predicate(&replay.events))
+            .map(|(name, _)| name.clone())
+            .collect();
+        if matched.is_empty() {
+            return Ok(None);
+        }
+        self.captured.push(Highlight { replay, matched });
+        Ok(self.captured.last())
+    }
+
+    /// Gets the number of games played, captured or not
+    pub fn games_played(&self) -> u64 {
+        self.games_played
+    }
+
+    /// Gets every game captured so far, in the order played
+    pub fn captured(&self) -> &[Highlight<E>] {
+        &self.captured
+    }
+
+    /// Gets the captured games that matched the named detector
+    pub fn matching<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Highlight<E>> {
+        self.captured
+            .iter()
+            .filter(move |highlight| highlight.matched.iter().any(|matched| matched == name))
+    }
+
+    /// Consumes the hooks, returning the captured games
+    pub fn into_captured(self) -> Vec<Highlight<E>> {
+        self.captured
+    }
+}
@@ -0,0 +1,113 @@
+//! Identifiers for games, tables, and replays
+//!
+//! Each kind of identifier is its own type, so a table's ID can't be passed where a replay's is
+//! expected. Identifiers are strings underneath, and fresh ones are random UUIDs
+
+use std::{
+    convert::Infallible,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use rand::Rng;
+
+/// Defines a string-backed identifier type
+macro_rules! string_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "serde", serde(transparent))]
+        pub struct $name(String);
+
+        impl $name {
+            /// Creates an identifier from any string
+            pub fn new(id: impl Into<String>) -> $name {
+                $name(id.into())
+            }
+
+            /// Creates a random version 4 UUID identifier
+            pub fn random<R: Rng + ?Sized>(rng: &mut R) -> $name {
+                $name(random_uuid(rng))
+            }
+
+            /// Gets the identifier as a string
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<$name, Infallible> {
+                Ok($name::new(s))
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> $name {
+                $name(id)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> String {
+                id.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+    };
+}
+
+string_id!(
+    /// Identifies a single game, such as one built by a
+    /// [`GameRegistry`](super::registry::GameRegistry)
+    /// # Examples
+    /// ```
+    /// # use naipe::games::id::GameId;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// let id = GameId::random(&mut StdRng::seed_from_u64(1));
+    /// assert_eq!(id.as_str().len(), 36);
+    /// assert_eq!(id.as_str().as_bytes()[14], b'4');
+    /// assert_eq!(id.to_string().parse::<GameId>(), Ok(id));
+    /// ```
+    GameId
+);
+
+string_id!(
+    /// Identifies a table hosting a session of games, such as a Blackjack table
+    TableId
+);
+
+string_id!(
+    /// Identifies a recorded [`Replay`](super::event::Replay)
+    ReplayId
+);
+
+/// Creates a random version 4 UUID, in its usual hyphenated form
+fn random_uuid<R: Rng + ?Sized>(rng: &mut R) -> String {
+    let mut bytes: [u8; 16] = rng.gen();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = format!("{:032x}", u128::from_be_bytes(bytes));
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
@@ -0,0 +1,117 @@
+//! Machine-readable descriptions of the bundled games
+//!
+//! Every bundled game implements [`GameInfo`], and [`bundled_games`] lists the games enabled by
+//! the crate's features, so launchers can list and filter games without hardcoding them
+
+use std::fmt::{self, Display, Formatter};
+
+/// A category a game belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum GameTag {
+    /// Players bet against a dealer or the house
+    Casino,
+    /// Cards are compared directly, with no decisions to make
+    Comparing,
+    /// Players each play a card to tricks, won by the highest card
+    TrickTaking,
+    /// Players try to avoid taking certain cards
+    Evasion,
+    /// Played alone
+    Solitaire,
+    /// The outcome is decided by the deal alone
+    Luck,
+    /// Suitable for young children
+    Children,
+}
+
+impl Display for GameTag {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            GameTag::Casino => write!(f, "casino"),
+            GameTag::Comparing => write!(f, "comparing"),
+            GameTag::TrickTaking => write!(f, "trick-taking"),
+            GameTag::Evasion => write!(f, "evasion"),
+            GameTag::Solitaire => write!(f, "solitaire"),
+            GameTag::Luck => write!(f, "luck"),
+            GameTag::Children => write!(f, "children"),
+        }
+    }
+}
+
+/// A description of a game, for listing it in a launcher
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GameMetadata {
+    pub name: &'static str,
+    /// A sentence describing how the game is played
+    pub summary: &'static str,
+    pub min_players: usize,
+    pub max_players: usize,
+    /// The number of standard 52 card decks played with by default
+    pub decks: u16,
+    /// A rough estimate of how long a game takes, in minutes
+    pub minutes: u32,
+    pub tags: &'static [GameTag],
+}
+
+impl GameMetadata {
+    /// Finds if the game can be played by the given number of players
+    pub fn supports_players(&self, players: usize) -> bool {
+        (self.min_players..=self.max_players).contains(&players)
+    }
+
+    /// Finds if the game belongs to the category
+    pub fn has_tag(&self, tag: GameTag) -> bool {
+        self.tags.contains(&tag)
+    }
+}
+
+/// A game that can describe itself
+pub trait GameInfo {
+    /// Gets the description of the game
+    fn info() -> GameMetadata;
+}
+
+/// Gets the description of every game enabled by the crate's features, in alphabetical order
+/// # Examples
+/// ```
+/// # use naipe::games::info::{bundled_games, GameTag};
+/// let for_four: Vec<&str> = bundled_games()
+///     .into_iter()
+///     .filter(|game| game.supports_players(4) && game.has_tag(GameTag::TrickTaking))
+///     .map(|game| game.name)
+///     .collect();
+/// assert_eq!(for_four, vec!["Hearts"]);
+/// ```
+pub fn bundled_games() -> Vec<GameMetadata> {
+    vec![
+        #[cfg(feature = "solitaire")]
+        <crate::games::solitaire::accordion::AccordionGame as GameInfo>::info(),
+        #[cfg(feature = "blackjack")]
+        <crate::games::blackjack::BlackjackGame as GameInfo>::info(),
+        #[cfg(feature = "solitaire")]
+        <crate::games::solitaire::golf::GolfGame as GameInfo>::info(),
+        #[cfg(feature = "hearts")]
+        <crate::games::hearts::HeartsGame as GameInfo>::info(),
+        #[cfg(feature = "memory")]
+        <crate::games::memory::MemoryGame as GameInfo>::info(),
+        #[cfg(feature = "war")]
+        <crate::games::war::WarGame as GameInfo>::info(),
+    ]
+}
+
+/// Finds the description of an enabled game by its name, ignoring case
+/// # Examples
+/// ```
+/// # use naipe::games::info::find_game;
+/// let war = find_game("war").unwrap();
+/// assert_eq!((war.min_players, war.max_players), (2, 2));
+/// assert!(find_game("Canasta").is_none());
+/// ```
+pub fn find_game(name: &str) -> Option<GameMetadata> {
+    bundled_games()
+        .into_iter()
+        .find(|game| game.name.eq_ignore_ascii_case(name))
+}
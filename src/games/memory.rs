@@ -0,0 +1,548 @@
+//! An implementation of Memory, also called Concentration
+//!
+//! Cards are laid face down in a grid. On each turn a player flips two cards, keeping them as a
+//! pair if they share a rank and a color, and flipping again after a pair. Otherwise the cards
+//! are turned back face down and the next player takes a turn. The game ends once every pair is
+//! taken, and the player holding the most pairs wins.
+//!
+//! Grids smaller than a whole deck are filled from a stripped deck, taking pairs from the aces
+//! upward, black before red
+
+use rand::{Rng, SeedableRng};
+use thiserror::Error;
+
+use crate::common::{
+    card::{Card, Rank, Suit},
+    deck::Deck,
+};
+use crate::games::{
+    agent::{Agent, AgentGame},
+    audit::{AuditedGame, RandomnessRecord},
+    dynamic::ErasedGame,
+    event::EventSource,
+    info::{GameInfo, GameMetadata, GameTag},
+    tricks::Objective,
+    GameOutcome, GameRng, SeededGame,
+};
+
+/// The most players a game of Memory may have
+pub const MAX_MEMORY_PLAYERS: usize = 6;
+
+/// The number of pairs in a standard deck
+const DECK_PAIRS: usize = 26;
+
+/// Gets the cards for a grid holding the given number of pairs, in the order they are stripped
+/// from the deck
+/// # Examples
+/// ```
+/// # use naipe::games::memory::memory_cards;
+/// let cards: Vec<String> = memory_cards(3).iter().map(ToString::to_string).collect();
+/// assert_eq!(cards, vec!["A♠", "A♣", "A♥", "A♦", "2♠", "2♣"]);
+/// assert_eq!(memory_cards(26).len(), 52);
+/// ```
+pub fn memory_cards(pairs: usize) -> Vec<Card> {
+    Rank::all_ranks()
+        .into_iter()
+        .flat_map(|rank| {
+            [[Suit::Spade, Suit::Club], [Suit::Heart, Suit::Diamond]]
+                .map(|suits| suits.map(|suit| Card::new(suit, rank)))
+        })
+        .take(pairs)
+        .flatten()
+        .collect()
+}
+
+/// Finds if two cards make a pair, sharing a rank and a color
+pub fn is_pair(card: Card, other: Card) -> bool {
+    card.get_rank() == other.get_rank() && card.get_suit().is_red() == other.get_suit().is_red()
+}
+
+/// A cell of the grid, as a player sees it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum MemoryCell {
+    FaceDown,
+    /// Flipped during the current turn
+    FaceUp(Card),
+    /// Taken as part of a pair
+    Taken,
+}
+
+/// What was found by flipping a card
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Flip {
+    /// The first card of a turn
+    First(Card),
+    /// The second card of a turn made a pair with the first, and the player flips again
+    Match([Card; 2]),
+    /// The second card of a turn did not make a pair, and the turn passes
+    Miss([Card; 2]),
+}
+
+/// What a player can see of a game of Memory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryView {
+    /// The seat of the player, counting from zero
+    pub seat: usize,
+    /// The seat whose turn it is
+    pub turn: usize,
+    /// The number of cells in each row
+    pub columns: usize,
+    /// Every cell of the grid, row by row
+    pub cells: Vec<MemoryCell>,
+    /// The cells shown by the latest flips, with their cards, including a missed pair that has
+    /// since been turned back face down
+    pub shown: Vec<(usize, Card)>,
+    /// The pairs taken by each seat
+    pub scores: Vec<usize>,
+}
+
+impl MemoryView {
+    /// Gets every cell that may be flipped now
+    pub fn face_down(&self) -> Vec<usize> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| **cell == MemoryCell::FaceDown)
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+/// Something that happened in a game of Memory, reported through [`EventSource::events`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MemoryEvent {
+    /// In audit mode, the cards were shuffled before being laid out
+    Randomness(RandomnessRecord),
+}
+
+/// A game of Memory
+///
+/// Each agent is asked for a single cell to flip, and so is asked twice on each turn, seeing the
+/// first card before choosing the second
+/// # Examples
+/// ```
+/// # use naipe::games::memory::{Flip, MemoryGame};
+/// let mut game = MemoryGame::builder().grid(2, 2).build_from_seed(3).unwrap();
+/// assert_eq!(game.pairs_left(), 2);
+/// // The first two aces flipped make a pair only if they share a color
+/// let Flip::First(first) = game.flip(0).unwrap() else {
+///     panic!("the turn starts with one card");
+/// };
+/// match game.flip(1).unwrap() {
+///     Flip::Match(pair) => assert_eq!(pair[0], first),
+///     Flip::Miss(_) => assert_eq!(game.turn(), 1),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryGame {
+    cells: Vec<Option<Card>>,
+    columns: usize,
+    first: Option<usize>,
+    shown: Vec<(usize, Card)>,
+    scores: Vec<usize>,
+    turn: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<MemoryEvent>,
+}
+
+impl MemoryGame {
+    /// Starts building a game with options other than the default grid of a whole deck
+    pub fn builder() -> MemoryGameBuilder {
+        MemoryGameBuilder::default()
+    }
+
+    /// Gets the number of rows in the grid
+    pub fn rows(&self) -> usize {
+        self.cells.len() / self.columns
+    }
+
+    /// Gets the number of cells in each row
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Gets the cell at the given row and column, counting from zero, as every player sees it
+    pub fn cell(&self, row: usize, column: usize) -> Option<MemoryCell> {
+        if column >= self.columns {
+            return None;
+        }
+        let index = row * self.columns + column;
+        let card = self.cells.get(index)?;
+        Some(match card {
+            None => MemoryCell::Taken,
+            Some(card) if self.first == Some(index) => MemoryCell::FaceUp(*card),
+            Some(_) => MemoryCell::FaceDown,
+        })
+    }
+
+    /// Gets the seat whose turn it is
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    /// Gets the pairs taken by each seat
+    pub fn scores(&self) -> &[usize] {
+        &self.scores
+    }
+
+    /// Gets the number of pairs still in the grid
+    pub fn pairs_left(&self) -> usize {
+        self.cells.iter().flatten().count() / 2
+    }
+
+    /// Finds if every pair has been taken
+    pub fn is_over(&self) -> bool {
+        self.pairs_left() == 0
+    }
+
+    /// Flips the card in a cell, counting from zero row by row, for the seat whose turn it is
+    /// # Errors
+    /// [`MemoryError::GameOver`] if every pair has been taken, or [`MemoryError::IllegalFlip`]
+    /// if the cell is off the grid, taken, or already flipped this turn
+    pub fn flip(&mut self, cell: usize) -> Result<Flip, MemoryError> {
+        if self.is_over() {
+            return Err(MemoryError::GameOver);
+        }
+        let card = self
+            .cells
+            .get(cell)
+            .copied()
+            .flatten()
+            .filter(|_| self.first != Some(cell))
+            .ok_or(MemoryError::IllegalFlip(cell))?;
+        let Some(first) = self.first.take() else {
+            self.first = Some(cell);
+            self.shown = vec![(cell, card)];
+            return Ok(Flip::First(card));
+        };
+        let (_, first_card) = self.shown[0];
+        self.shown.push((cell, card));
+        let cards = [first_card, card];
+        if is_pair(first_card, card) {
+            self.cells[first] = None;
+            self.cells[cell] = None;
+            self.scores[self.turn] += 1;
+            Ok(Flip::Match(cards))
+        } else {
+            self.turn = (self.turn + 1) % self.scores.len();
+            Ok(Flip::Miss(cards))
+        }
+    }
+}
+
+impl Default for MemoryGame {
+    /// Creates a two player game over a whole deck, with a random seed
+    fn default() -> MemoryGame {
+        MemoryGame::new_with_rng(&mut rand::thread_rng())
+    }
+}
+
+impl SeededGame for MemoryGame {
+    /// Creates a two player game over a whole deck, shuffled by a generator seeded from the
+    /// given one
+    fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> MemoryGame {
+        MemoryGame::builder()
+            .build_with_rng(rng)
+            .expect("The default grid and players are valid")
+    }
+}
+
+impl AuditedGame for MemoryGame {
+    /// Creates a two player game over a whole deck, recording the shuffle before the cards are
+    /// laid out
+    fn audited_with_rng<R: Rng + ?Sized>(rng: &mut R) -> MemoryGame {
+        MemoryGame::builder()
+            .audit()
+            .build_with_rng(rng)
+            .expect("The default grid and players are valid")
+    }
+}
+
+impl AgentGame for MemoryGame {
+    type View = MemoryView;
+    /// The cell to flip
+    type Action = usize;
+    /// Whether the game is over
+    type TickOk = bool;
+    type TickError = MemoryError;
+
+    fn seat_count(&self) -> usize {
+        self.scores.len()
+    }
+
+    fn view(&self, seat: usize) -> MemoryView {
+        MemoryView {
+            seat,
+            turn: self.turn,
+            columns: self.columns,
+            cells: (0..self.cells.len())
+                .filter_map(|index| self.cell(index / self.columns, index % self.columns))
+                .collect(),
+            shown: self.shown.clone(),
+            scores: self.scores.clone(),
+        }
+    }
+
+    /// Asks the seat whose turn it is for a cell, and flips it
+    fn tick_with_agents(
+        &mut self,
+        agents: &mut [&mut dyn Agent<MemoryView, usize>],
+    ) -> Result<bool, MemoryError> {
+        if agents.len() != self.scores.len() {
+            return Err(MemoryError::WrongAgentCount {
+                expected: self.scores.len(),
+                actual: agents.len(),
+            });
+        }
+        // Only the deal emits events, which the first tick leaves behind
+        self.events.clear();
+        if !self.is_over() {
+            let cell = agents[self.turn].choose_action(&self.view(self.turn));
+            self.flip(cell)?;
+        }
+        Ok(self.is_over())
+    }
+}
+
+impl EventSource for MemoryGame {
+    type Event = MemoryEvent;
+
+    /// Gets the events from before the first tick, which are only the record of the shuffle in
+    /// audit mode
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::event::EventSource;
+    /// # use naipe::games::memory::{MemoryEvent, MemoryGame, MemoryView};
+    /// let mut game = MemoryGame::builder().audit().build_from_seed(6).unwrap();
+    /// let MemoryEvent::Randomness(record) = &game.events()[0] else {
+    ///     panic!("the shuffle is recorded");
+    /// };
+    /// assert!(record.verify());
+    /// // The audited game lays out the same cards
+    /// let mut plain = MemoryGame::builder().build_from_seed(6).unwrap();
+    /// assert_eq!(game.clone().flip(0), plain.flip(0));
+    ///
+    /// let mut first = |view: &MemoryView| view.face_down()[0];
+    /// let mut second = first;
+    /// game.tick_with_agents(&mut [&mut first, &mut second]).unwrap();
+    /// assert!(game.events().is_empty());
+    /// ```
+    fn events(&self) -> &[MemoryEvent] {
+        &self.events
+    }
+}
+
+impl ErasedGame for MemoryGame {
+    /// Writes the view as `seat=<seat> turn=<seat> columns=<count> cells=[<cell>,...]
+    /// shown=[<cell>:<card>,...] scores=<pairs,...>`, with each cell written as `?` when face
+    /// down, `-` when taken, or its card when face up
+    fn encode_view(view: &MemoryView) -> String {
+        let join = |values: Vec<String>| values.join(",");
+        format!(
+            "seat={} turn={} columns={} cells=[{}] shown=[{}] scores={}",
+            view.seat,
+            view.turn,
+            view.columns,
+            join(
+                view.cells
+                    .iter()
+                    .map(|cell| match cell {
+                        MemoryCell::FaceDown => "?".to_string(),
+                        MemoryCell::FaceUp(card) => card.to_string(),
+                        MemoryCell::Taken => "-".to_string(),
+                    })
+                    .collect()
+            ),
+            join(
+                view.shown
+                    .iter()
+                    .map(|(cell, card)| format!("{cell}:{card}"))
+                    .collect()
+            ),
+            join(view.scores.iter().map(ToString::to_string).collect()),
+        )
+    }
+
+    /// Reads the cell to flip, counting from zero row by row
+    fn decode_action(action: &str) -> Option<usize> {
+        action.trim().parse().ok()
+    }
+
+    /// Flips the first face down cell
+    fn fallback_action(view: &MemoryView) -> usize {
+        view.face_down().first().copied().unwrap_or_default()
+    }
+
+    fn outcome(&self) -> Option<String> {
+        if !self.is_over() {
+            return None;
+        }
+        let scores = self
+            .scores
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(",");
+        Some(match self.winner() {
+            Some(winner) => format!("Player {} won with pairs {scores}", winner + 1),
+            None => format!("Tied with pairs {scores}"),
+        })
+    }
+}
+
+impl GameOutcome for MemoryGame {
+    /// Gets the seat holding the most pairs once the game is over, unless it is tied
+    fn winner(&self) -> Option<usize> {
+        if !self.is_over() {
+            return None;
+        }
+        Objective::Take.winner(&self.scores)
+    }
+}
+
+impl GameInfo for MemoryGame {
+    fn info() -> GameMetadata {
+        GameMetadata {
+            name: "Memory",
+            summary: "Flip cards two at a time to find pairs of the same rank and color",
+            min_players: 1,
+            max_players: MAX_MEMORY_PLAYERS,
+            decks: 1,
+            minutes: 15,
+            tags: &[GameTag::Children],
+        }
+    }
+}
+
+/// A builder for a game of Memory, choosing its grid and players
+/// # Examples
+/// ```
+/// # use naipe::games::memory::{MemoryError, MemoryGame};
+/// let game = MemoryGame::builder().grid(4, 4).players(3).build_from_seed(1).unwrap();
+/// assert_eq!((game.rows(), game.columns()), (4, 4));
+/// assert_eq!(game.scores(), &[0, 0, 0]);
+/// assert_eq!(
+///     MemoryGame::builder().grid(3, 3).build_from_seed(1),
+///     Err(MemoryError::InvalidGrid { rows: 3, columns: 3 })
+/// );
+/// assert_eq!(
+///     MemoryGame::builder().grid(usize::MAX, 2).build_from_seed(1),
+///     Err(MemoryError::InvalidGrid { rows: usize::MAX, columns: 2 })
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryGameBuilder {
+    rows: usize,
+    columns: usize,
+    players: usize,
+    audit: bool,
+}
+
+impl Default for MemoryGameBuilder {
+    /// Starts from two players and a grid of a whole deck, in four rows of thirteen
+    fn default() -> MemoryGameBuilder {
+        MemoryGameBuilder {
+            rows: 4,
+            columns: 13,
+            players: 2,
+            audit: false,
+        }
+    }
+}
+
+impl MemoryGameBuilder {
+    /// Sets the size of the grid, which is filled from a deck stripped down to fit
+    #[must_use]
+    pub fn grid(mut self, rows: usize, columns: usize) -> MemoryGameBuilder {
+        self.rows = rows;
+        self.columns = columns;
+        self
+    }
+
+    /// Sets the number of players
+    #[must_use]
+    pub fn players(mut self, players: usize) -> MemoryGameBuilder {
+        self.players = players;
+        self
+    }
+
+    /// Records the shuffle, given as a [`MemoryEvent::Randomness`] event until the first tick
+    ///
+    /// Auditing draws the same random numbers, so the cards are laid out as they would be
+    /// otherwise
+    #[must_use]
+    pub fn audit(mut self) -> MemoryGameBuilder {
+        self.audit = true;
+        self
+    }
+
+    /// Builds the game, shuffling with a generator seeded from the given one
+    /// # Errors
+    /// [`MemoryError::InvalidGrid`] unless the grid holds an even number of cards, no more than a
+    /// whole deck, or [`MemoryError::PlayerCount`] if there are no players or too many
+    pub fn build_with_rng<R: Rng + ?Sized>(self, rng: &mut R) -> Result<MemoryGame, MemoryError> {
+        let invalid = MemoryError::InvalidGrid {
+            rows: self.rows,
+            columns: self.columns,
+        };
+        let size = self.rows.checked_mul(self.columns).ok_or(invalid)?;
+        if size == 0 || !size.is_multiple_of(2) || size / 2 > DECK_PAIRS {
+            return Err(invalid);
+        }
+        if !(1..=MAX_MEMORY_PLAYERS).contains(&self.players) {
+            return Err(MemoryError::PlayerCount(self.players));
+        }
+        let mut deck = Deck::new_empty();
+        for card in memory_cards(size / 2) {
+            deck.add(card);
+        }
+        let mut rng = GameRng::from_seed(rng.gen());
+        let mut events = vec![];
+        if self.audit {
+            let record = RandomnessRecord::shuffle("deal", &mut deck, &mut rng);
+            events.push(MemoryEvent::Randomness(record));
+        } else {
+            deck.shuffle(&mut rng);
+        }
+        Ok(MemoryGame {
+            cells: std::iter::from_fn(|| deck.draw()).map(Some).collect(),
+            columns: self.columns,
+            first: None,
+            shown: vec![],
+            scores: vec![0; self.players],
+            turn: 0,
+            events,
+        })
+    }
+
+    /// Builds the game from a fixed seed, which deals identically every time
+    /// # Errors
+    /// As for [`MemoryGameBuilder::build_with_rng`]
+    pub fn build_from_seed(self, seed: u64) -> Result<MemoryGame, MemoryError> {
+        self.build_with_rng(&mut GameRng::seed_from_u64(seed))
+    }
+}
+
+/// Errors related to setting up and playing Memory
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MemoryError {
+    #[error("A grid of {rows} by {columns} does not hold an even number of cards from one deck")]
+    InvalidGrid { rows: usize, columns: usize },
+    #[error("Memory is played by 1 to {MAX_MEMORY_PLAYERS} players, not {0}")]
+    PlayerCount(usize),
+    #[error("Cell {0} cannot be flipped")]
+    IllegalFlip(usize),
+    #[error("The game is already over")]
+    GameOver,
+    #[error("Expected {expected} agents, but got {actual}")]
+    WrongAgentCount { expected: usize, actual: usize },
+}
@@ -1,8 +1,40 @@
 //! A collection of basic games included with naipe
 
+pub mod agent;
+pub mod audit;
+#[cfg(feature = "blackjack")]
+pub mod blackjack;
+pub mod dynamic;
+pub mod event;
+#[cfg(feature = "hearts")]
+pub mod hearts;
+pub mod highlight;
+pub mod id;
+pub mod info;
+#[cfg(feature = "memory")]
+pub mod memory;
+pub mod penalty;
+pub mod preflop;
+pub mod registry;
+pub mod scenario;
+pub mod series;
+pub mod simulation;
+#[cfg(feature = "solitaire")]
+pub mod solitaire;
+pub mod tournament;
+pub mod tricks;
 #[cfg(feature = "war")]
 pub mod war;
 
+use rand::{Rng, SeedableRng};
+use thiserror::Error;
+
+/// The random number generator games keep in their state
+///
+/// Games draw all of their randomness from their own generator, so that a game created from a
+/// seed always plays out the same way, including after being saved and restored
+pub type GameRng = rand_chacha::ChaCha8Rng;
+
 pub trait Game {
     type TickOk;
     type TickError;
@@ -11,3 +43,66 @@ pub trait Game {
     #[allow(clippy::missing_errors_doc)]
     fn tick(&mut self) -> Result<Self::TickOk, Self::TickError>;
 }
+
+/// A game that can be created from an injected random number generator
+pub trait SeededGame: Sized {
+    /// Creates a new game, seeding its internal generator from the given one
+    fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self;
+
+    /// Creates a new game from a fixed seed, which plays out identically every time
+    fn from_seed(seed: u64) -> Self {
+        Self::new_with_rng(&mut GameRng::seed_from_u64(seed))
+    }
+}
+
+/// A game that can report who won once it is over
+pub trait GameOutcome {
+    /// Gets the seat of the winner (counting from zero), or `None` if the game is unfinished
+    /// or drawn
+    fn winner(&self) -> Option<usize>;
+}
+
+/// A game that a player may concede, ending it in favour of their opponents
+pub trait Concede {
+    /// Concedes the game on behalf of the player in the given seat (counting from zero),
+    /// finalizing the game as though it had been played out
+    /// # Errors
+    /// [`ConcedeError::UnknownSeat`] if no player sits in the seat, or
+    /// [`ConcedeError::GameOver`] if the game has already finished
+    fn concede(&mut self, seat: usize) -> Result<(), ConcedeError>;
+}
+
+/// A game in which a player may claim the rest of play, such as every remaining trick, ending it
+/// early once the claim is shown to hold however the other players play
+pub trait Claim {
+    /// Claims the rest of play on behalf of the player in the given seat (counting from zero),
+    /// checking the claim by playing out every defence, and finalizing the game as though it had
+    /// been played out if it holds
+    /// # Errors
+    /// [`ClaimError::UnknownSeat`] if no player sits in the seat,
+    /// [`ClaimError::GameOver`] if the game has already finished, or
+    /// [`ClaimError::Unproven`] if the claim could not be shown to hold, leaving the game unchanged
+    fn claim(&mut self, seat: usize) -> Result<(), ClaimError>;
+}
+
+/// Errors related to claiming the rest of a game
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ClaimError {
+    #[error("No player sits in seat {0}")]
+    UnknownSeat(usize),
+    #[error("The game is already over")]
+    GameOver,
+    #[error("The claim could not be shown to hold against every defence")]
+    Unproven,
+}
+
+/// Errors related to conceding a game
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConcedeError {
+    #[error("No player sits in seat {0}")]
+    UnknownSeat(usize),
+    #[error("The game is already over")]
+    GameOver,
+}
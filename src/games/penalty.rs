@@ -0,0 +1,262 @@
+//! Rule violations, and the penalties assessed for them
+//!
+//! Games report each broken rule as a [`Violation`], and a [`PenaltyPolicy`] decides the score
+//! adjustment and whether the deal continues, is played on with a dead hand, or is thrown in
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::common::card::Card;
+
+/// The kinds of rule a player may break
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ViolationKind {
+    /// Failing to follow suit while holding a card of the suit led
+    Renege,
+    /// Showing a card that should have stayed hidden
+    ExposedCard,
+    /// Playing or acting when it was another player's turn
+    OutOfTurn,
+    /// Leading a card the rules forbid leading, such as hearts before they are broken
+    IllegalLead,
+    /// Playing a card the player does not hold, or may not play for another reason
+    IllegalCard,
+}
+
+impl Display for ViolationKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ViolationKind::Renege => write!(f, "renege"),
+            ViolationKind::ExposedCard => write!(f, "exposed card"),
+            ViolationKind::OutOfTurn => write!(f, "play out of turn"),
+            ViolationKind::IllegalLead => write!(f, "illegal lead"),
+            ViolationKind::IllegalCard => write!(f, "illegal card"),
+        }
+    }
+}
+
+/// A rule broken by the player in a seat
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Violation {
+    /// The seat of the offending player, counting from zero
+    pub seat: usize,
+    pub kind: ViolationKind,
+    /// The card involved, if any
+    pub card: Option<Card>,
+}
+
+impl Violation {
+    /// Creates a violation with no card involved
+    pub fn new(seat: usize, kind: ViolationKind) -> Violation {
+        Violation {
+            seat,
+            kind,
+            card: None,
+        }
+    }
+
+    /// Creates a violation involving the given card
+    pub fn with_card(seat: usize, kind: ViolationKind, card: Card) -> Violation {
+        Violation {
+            seat,
+            kind,
+            card: Some(card),
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Seat {}: {}", self.seat, self.kind)?;
+        if let Some(card) = self.card {
+            write!(f, " ({card})")?;
+        }
+        Ok(())
+    }
+}
+
+/// What happens to the deal after a violation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Resolution {
+    /// Play continues as normal
+    Continue,
+    /// The offender's hand is dead, and they take no further part in the deal
+    DeadHand,
+    /// The deal is thrown in, to be redealt
+    AbortDeal,
+}
+
+/// The consequence of a violation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Penalty {
+    /// Points added to the offender's score, which each game interprets in its own scoring
+    pub points: i32,
+    pub resolution: Resolution,
+}
+
+impl Penalty {
+    /// A penalty with no effect, as a warning
+    pub const WARNING: Penalty = Penalty {
+        points: 0,
+        resolution: Resolution::Continue,
+    };
+
+    /// Creates a penalty adjusting the offender's score, with play continuing
+    pub fn points(points: i32) -> Penalty {
+        Penalty {
+            points,
+            resolution: Resolution::Continue,
+        }
+    }
+
+    /// Changes what happens to the deal after this penalty
+    #[must_use]
+    pub fn then(mut self, resolution: Resolution) -> Penalty {
+        self.resolution = resolution;
+        self
+    }
+}
+
+/// Decides the penalty for each violation
+pub trait PenaltyPolicy {
+    /// Gets the penalty for the violation
+    fn assess(&self, violation: &Violation) -> Penalty;
+}
+
+impl<F: Fn(&Violation) -> Penalty> PenaltyPolicy for F {
+    fn assess(&self, violation: &Violation) -> Penalty {
+        self(violation)
+    }
+}
+
+/// A policy with a fixed penalty for each kind of violation
+/// # Examples
+/// ```
+/// # use naipe::games::penalty::{Penalty, PenaltyPolicy, PenaltyTable, Resolution, Violation, ViolationKind};
+/// let table = PenaltyTable::new(Penalty::WARNING)
+///     .with(ViolationKind::Renege, Penalty::points(-30))
+///     .with(ViolationKind::OutOfTurn, Penalty::WARNING.then(Resolution::AbortDeal));
+/// let renege = Violation::new(2, ViolationKind::Renege);
+/// assert_eq!(table.assess(&renege), Penalty::points(-30));
+/// let exposed = Violation::new(0, ViolationKind::ExposedCard);
+/// assert_eq!(table.assess(&exposed), Penalty::WARNING);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PenaltyTable {
+    penalties: Vec<(ViolationKind, Penalty)>,
+    default: Penalty,
+}
+
+impl PenaltyTable {
+    /// Creates a table giving every kind of violation the default penalty
+    pub fn new(default: Penalty) -> PenaltyTable {
+        PenaltyTable {
+            penalties: vec![],
+            default,
+        }
+    }
+
+    /// Sets the penalty for a kind of violation
+    #[must_use]
+    pub fn with(mut self, kind: ViolationKind, penalty: Penalty) -> PenaltyTable {
+        self.penalties.retain(|(existing, _)| *existing != kind);
+        self.penalties.push((kind, penalty));
+        self
+    }
+}
+
+impl Default for PenaltyTable {
+    /// Creates a table only warning for every violation
+    fn default() -> PenaltyTable {
+        PenaltyTable::new(Penalty::WARNING)
+    }
+}
+
+impl PenaltyPolicy for PenaltyTable {
+    fn assess(&self, violation: &Violation) -> Penalty {
+        self.penalties
+            .iter()
+            .find(|(kind, _)| *kind == violation.kind)
+            .map_or(self.default, |(_, penalty)| *penalty)
+    }
+}
+
+/// The violations and penalties assessed during a deal
+/// # Examples
+/// ```
+/// # use naipe::games::penalty::{Penalty, PenaltyLog, Resolution, Violation, ViolationKind};
+/// let policy = |violation: &Violation| match violation.kind {
+///     ViolationKind::Renege => Penalty::points(-10).then(Resolution::DeadHand),
+///     _ => Penalty::WARNING,
+/// };
+/// let mut log = PenaltyLog::new();
+/// log.record(Violation::new(1, ViolationKind::Renege), &policy);
+/// log.record(Violation::new(3, ViolationKind::ExposedCard), &policy);
+/// assert_eq!(log.score_adjustments(4), vec![0, -10, 0, 0]);
+/// assert!(log.is_dead(1));
+/// assert!(!log.is_aborted());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PenaltyLog {
+    entries: Vec<(Violation, Penalty)>,
+}
+
+impl PenaltyLog {
+    /// Creates an empty log
+    pub fn new() -> PenaltyLog {
+        PenaltyLog::default()
+    }
+
+    /// Assesses a violation under the policy, recording and returning its penalty
+    pub fn record<P: PenaltyPolicy + ?Sized>(
+        &mut self,
+        violation: Violation,
+        policy: &P,
+    ) -> Penalty {
+        let penalty = policy.assess(&violation);
+        self.entries.push((violation, penalty));
+        penalty
+    }
+
+    /// Gets every violation and its penalty, in the order recorded
+    pub fn entries(&self) -> &[(Violation, Penalty)] {
+        &self.entries
+    }
+
+    /// Gets the total penalty points for each of the given number of seats
+    pub fn score_adjustments(&self, seats: usize) -> Vec<i32> {
+        let mut adjustments = vec![0; seats];
+        for (violation, penalty) in &self.entries {
+            if let Some(adjustment) = adjustments.get_mut(violation.seat) {
+                *adjustment += penalty.points;
+            }
+        }
+        adjustments
+    }
+
+    /// Finds if the seat's hand has been declared dead
+    pub fn is_dead(&self, seat: usize) -> bool {
+        self.entries.iter().any(|(violation, penalty)| {
+            violation.seat == seat && penalty.resolution == Resolution::DeadHand
+        })
+    }
+
+    /// Finds if the deal has been thrown in
+    pub fn is_aborted(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|(_, penalty)| penalty.resolution == Resolution::AbortDeal)
+    }
+
+    /// Clears the log for a new deal
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -0,0 +1,558 @@
+//! Preflop strategy charts for Texas hold'em, as published in strategy guides
+//!
+//! A [`PreflopChart`] maps each table position and starting hand to how often to raise, call,
+//! or fold. Charts are parsed from a simple text format, and played by a [`ChartAgent`].
+//!
+//! The text format lists positions in brackets, each followed by lines of hands and what to do
+//! with them. Hands not listed are folded, and `#` starts a comment.
+//! ```text
+//! [BTN]
+//! 22+, A2s+, KTs+, ATo+: raise
+//! 76s, 65s: raise 50 call 50
+//! ```
+//! A hand of two ranks without `s` or `o` stands for both its suited and offsuit forms. A `+`
+//! after a pair takes in every higher pair, and after any other hand every hand with a higher
+//! kicker below the top card, so `A2s+` is every suited ace.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use rand::{Rng, SeedableRng};
+use thiserror::Error;
+
+use super::{agent::Agent, audit::RandomnessRecord, GameRng};
+use crate::common::card::{Card, Rank};
+
+/// The number of rolls frequencies are given out of
+const PERCENT: usize = 100;
+
+/// Every rank of a standard deck, lowest first
+const RANKS: [Rank; 13] = [
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+    Rank::Ace,
+];
+
+/// A seat's position relative to the button
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Position {
+    UnderTheGun,
+    Middle,
+    Hijack,
+    Cutoff,
+    Button,
+    SmallBlind,
+    BigBlind,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Position::UnderTheGun => write!(f, "UTG"),
+            Position::Middle => write!(f, "MP"),
+            Position::Hijack => write!(f, "HJ"),
+            Position::Cutoff => write!(f, "CO"),
+            Position::Button => write!(f, "BTN"),
+            Position::SmallBlind => write!(f, "SB"),
+            Position::BigBlind => write!(f, "BB"),
+        }
+    }
+}
+
+impl FromStr for Position {
+    type Err = ChartError;
+
+    /// Parses a position from its usual abbreviation, ignoring case
+    fn from_str(s: &str) -> Result<Position, ChartError> {
+        match s.to_ascii_uppercase().as_str() {
+            "UTG" => Ok(Position::UnderTheGun),
+            "MP" => Ok(Position::Middle),
+            "HJ" => Ok(Position::Hijack),
+            "CO" => Ok(Position::Cutoff),
+            "BTN" => Ok(Position::Button),
+            "SB" => Ok(Position::SmallBlind),
+            "BB" => Ok(Position::BigBlind),
+            _ => Err(ChartError::InvalidPosition(s.to_string())),
+        }
+    }
+}
+
+/// One of the 169 distinct starting hands, ignoring which suits are held
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::games::preflop::StartingHand;
+/// let hand = StartingHand::from_cards(
+///     Card::new(Suit::Heart, Rank::Ten),
+///     Card::new(Suit::Heart, Rank::Ace),
+/// );
+/// assert_eq!(hand.to_string(), "ATs");
+/// assert_eq!("ats".parse(), Ok(hand));
+/// assert!("AAs".parse::<StartingHand>().is_err());
+/// assert!("AT".parse::<StartingHand>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "String", try_from = "String"))]
+pub struct StartingHand {
+    high: Rank,
+    low: Rank,
+    suited: bool,
+}
+
+impl StartingHand {
+    /// Creates a starting hand, or `None` for a suited pair or a joker
+    pub fn new(first: Rank, second: Rank, suited: bool) -> Option<StartingHand> {
+        let valid = first != Rank::Joker && second != Rank::Joker && !(suited && first == second);
+        valid.then(|| StartingHand {
+            high: first.max(second),
+            low: first.min(second),
+            suited,
+        })
+    }
+
+    /// Gets the starting hand the two hole cards make
+    pub fn from_cards(first: Card, second: Card) -> StartingHand {
+        StartingHand {
+            high: first.get_rank().max(second.get_rank()),
+            low: first.get_rank().min(second.get_rank()),
+            suited: first.get_suit() == second.get_suit() && first.get_rank() != second.get_rank(),
+        }
+    }
+
+    /// Gets the higher rank
+    pub fn high(&self) -> Rank {
+        self.high
+    }
+
+    /// Gets the lower rank, equal to the higher for a pair
+    pub fn low(&self) -> Rank {
+        self.low
+    }
+
+    /// Finds if both cards share a suit
+    pub fn is_suited(&self) -> bool {
+        self.suited
+    }
+
+    /// Finds if both cards share a rank
+    pub fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+}
+
+impl Display for StartingHand {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let letter = |rank| match rank {
+            Rank::Ten => "T".to_string(),
+            rank => rank.to_string(),
+        };
+        write!(f, "{}{}", letter(self.high), letter(self.low))?;
+        match (self.is_pair(), self.suited) {
+            (true, _) => Ok(()),
+            (false, true) => write!(f, "s"),
+            (false, false) => write!(f, "o"),
+        }
+    }
+}
+
+impl FromStr for StartingHand {
+    type Err = ChartError;
+
+    /// Parses a hand such as `AKs`, `AKo`, or `QQ`
+    fn from_str(s: &str) -> Result<StartingHand, ChartError> {
+        let invalid = || ChartError::InvalidHand(s.to_string());
+        let chars: Vec<char> = s.chars().collect();
+        let (ranks, suited) = match chars.as_slice() {
+            [first, second] if first.eq_ignore_ascii_case(second) => ([*first, *second], false),
+            [first, second, 's' | 'S'] => ([*first, *second], true),
+            [first, second, 'o' | 'O'] => ([*first, *second], false),
+            _ => return Err(invalid()),
+        };
+        let [first, second] = ranks.map(|rank| rank.to_string().parse::<Rank>());
+        StartingHand::new(
+            first.map_err(|_| invalid())?,
+            second.map_err(|_| invalid())?,
+            suited,
+        )
+        .ok_or_else(invalid)
+    }
+}
+
+impl From<StartingHand> for String {
+    fn from(hand: StartingHand) -> String {
+        hand.to_string()
+    }
+}
+
+impl TryFrom<String> for StartingHand {
+    type Error = ChartError;
+
+    fn try_from(hand: String) -> Result<StartingHand, ChartError> {
+        hand.parse()
+    }
+}
+
+/// A preflop decision
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum PreflopAction {
+    Fold,
+    Call,
+    Raise,
+}
+
+/// How often to take each action with a hand, as percentages, folding the rest of the time
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionFrequencies {
+    pub raise: u8,
+    pub call: u8,
+}
+
+impl ActionFrequencies {
+    /// Gets the percentage of the time to fold
+    pub fn fold(&self) -> u8 {
+        100_u8.saturating_sub(self.raise.saturating_add(self.call))
+    }
+
+    /// Picks an action at random, with the given frequencies
+    pub fn choose<R: Rng>(&self, rng: &mut R) -> PreflopAction {
+        self.action_at(rng.gen_range(0..PERCENT))
+    }
+
+    /// Picks an action at random as [`ActionFrequencies::choose`] does, recording the roll
+    /// # Examples
+    /// ```
+    /// # use naipe::games::audit::RandomOutcome;
+    /// # use naipe::games::preflop::{ActionFrequencies, PreflopAction};
+    /// # use naipe::games::GameRng;
+    /// use rand::SeedableRng;
+    /// let mixed = ActionFrequencies { raise: 50, call: 50 };
+    /// let (action, record) = mixed.choose_recorded("button", &mut GameRng::seed_from_u64(5));
+    /// assert_eq!(action, mixed.choose(&mut GameRng::seed_from_u64(5)));
+    /// assert!(record.verify());
+    /// let RandomOutcome::Choice { chosen, .. } = record.outcome else {
+    ///     panic!("a roll is a choice");
+    /// };
+    /// assert_eq!(action == PreflopAction::Raise, chosen < 50);
+    /// ```
+    pub fn choose_recorded(
+        &self,
+        source: &str,
+        rng: &mut GameRng,
+    ) -> (PreflopAction, RandomnessRecord) {
+        let (roll, record) = RandomnessRecord::choose(source, PERCENT, rng);
+        (self.action_at(roll), record)
+    }
+
+    /// Gets the action for a roll of a hundred-sided die, counting from zero
+    fn action_at(self, roll: usize) -> PreflopAction {
+        let (raise, call) = (usize::from(self.raise), usize::from(self.call));
+        if roll < raise {
+            PreflopAction::Raise
+        } else if roll - raise < call {
+            PreflopAction::Call
+        } else {
+            PreflopAction::Fold
+        }
+    }
+}
+
+/// A preflop strategy, giving the action frequencies for each position and starting hand
+/// # Examples
+/// ```
+/// # use naipe::games::preflop::{ActionFrequencies, Position, PreflopChart};
+/// let chart = PreflopChart::parse(
+///     "
+///     [UTG]
+///     TT+, AQs+, AKo: raise
+///     [BTN]
+///     22+, A2s+, KTs+, ATo+: raise
+///     76s: raise 50 call 50
+///     ",
+/// )
+/// .unwrap();
+/// let ak = "AKo".parse().unwrap();
+/// let sevens = "77".parse().unwrap();
+/// assert_eq!(chart.get(Position::UnderTheGun, ak).raise, 100);
+/// assert_eq!(chart.get(Position::UnderTheGun, sevens).fold(), 100);
+/// assert_eq!(chart.get(Position::Button, sevens).raise, 100);
+/// assert_eq!(
+///     chart.get(Position::Button, "76s".parse().unwrap()),
+///     ActionFrequencies { raise: 50, call: 50 }
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreflopChart {
+    entries: HashMap<Position, HashMap<StartingHand, ActionFrequencies>>,
+}
+
+impl PreflopChart {
+    /// Creates a chart that folds everything
+    pub fn new() -> PreflopChart {
+        PreflopChart::default()
+    }
+
+    /// Parses a chart from the text format described in the [module documentation](self)
+    /// # Errors
+    /// [`ChartError::NoPosition`] if hands are listed before any position,
+    /// [`ChartError::InvalidLine`] if a line has no `:` between hands and actions, or another
+    /// [`ChartError`] if a position, hand, action, or frequency cannot be read
+    pub fn parse(chart: &str) -> Result<PreflopChart, ChartError> {
+        let mut parsed = PreflopChart::new();
+        let mut position = None;
+        for (index, line) in chart.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                position = Some(name.trim().parse()?);
+                continue;
+            }
+            let position = position.ok_or(ChartError::NoPosition(index + 1))?;
+            let (hands, actions) = line
+                .split_once(':')
+                .ok_or_else(|| ChartError::InvalidLine(line.to_string()))?;
+            let frequencies = parse_frequencies(actions)?;
+            for hand in hands.split(',').map(str::trim) {
+                for hand in expand_hands(hand)? {
+                    parsed.set(position, hand, frequencies);
+                }
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Sets the action frequencies for a hand in a position
+    pub fn set(&mut self, position: Position, hand: StartingHand, frequencies: ActionFrequencies) {
+        self.entries
+            .entry(position)
+            .or_default()
+            .insert(hand, frequencies);
+    }
+
+    /// Gets the action frequencies for a hand in a position, folding any hand not in the chart
+    pub fn get(&self, position: Position, hand: StartingHand) -> ActionFrequencies {
+        self.entries
+            .get(&position)
+            .and_then(|hands| hands.get(&hand))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Gets the positions the chart has any hands for
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.entries.keys().copied()
+    }
+}
+
+/// What a seat sees before the flop, enough to follow a chart
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreflopView {
+    pub position: Position,
+    pub hole: [Card; 2],
+}
+
+/// An agent following a preflop chart, picking among mixed actions at random
+/// # Examples
+/// ```
+/// # use naipe::common::card::{Card, Rank, Suit};
+/// # use naipe::games::agent::Agent;
+/// # use naipe::games::preflop::{ChartAgent, Position, PreflopAction, PreflopChart, PreflopView};
+/// let chart = PreflopChart::parse("[CO]\nQQ+, AK: raise").unwrap();
+/// let mut agent = ChartAgent::from_seed(chart, 3);
+/// let view = PreflopView {
+///     position: Position::Cutoff,
+///     hole: [
+///         Card::new(Suit::Spade, Rank::Ace),
+///         Card::new(Suit::Club, Rank::King),
+///     ],
+/// };
+/// assert_eq!(agent.choose_action(&view), PreflopAction::Raise);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChartAgent {
+    chart: PreflopChart,
+    rng: GameRng,
+    audit: bool,
+    records: Vec<RandomnessRecord>,
+}
+
+impl ChartAgent {
+    /// Creates an agent following the chart, mixing actions with the given generator
+    pub fn new(chart: PreflopChart, rng: GameRng) -> ChartAgent {
+        ChartAgent {
+            chart,
+            rng,
+            audit: false,
+            records: vec![],
+        }
+    }
+
+    /// Puts the agent in audit mode, recording the roll behind every action it chooses
+    ///
+    /// Auditing draws the same random numbers, so the agent acts as it would otherwise
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::{Card, Rank, Suit};
+    /// # use naipe::games::agent::Agent;
+    /// # use naipe::games::preflop::{ChartAgent, Position, PreflopChart, PreflopView};
+    /// let chart = PreflopChart::parse("[BTN]\n76s: raise 50 call 50").unwrap();
+    /// let mut agent = ChartAgent::from_seed(chart.clone(), 9).audited();
+    /// let mut plain = ChartAgent::from_seed(chart, 9);
+    /// let view = PreflopView {
+    ///     position: Position::Button,
+    ///     hole: [
+    ///         Card::new(Suit::Heart, Rank::Seven),
+    ///         Card::new(Suit::Heart, Rank::Six),
+    ///     ],
+    /// };
+    /// for _ in 0..3 {
+    ///     assert_eq!(agent.choose_action(&view), plain.choose_action(&view));
+    /// }
+    /// let records = agent.take_records();
+    /// assert_eq!(records.len(), 3);
+    /// assert!(records.iter().all(|record| record.verify()));
+    /// assert!(agent.records().is_empty());
+    /// ```
+    #[must_use]
+    pub fn audited(mut self) -> ChartAgent {
+        self.audit = true;
+        self
+    }
+
+    /// Gets the records of the rolls made in audit mode, since they were last taken
+    pub fn records(&self) -> &[RandomnessRecord] {
+        &self.records
+    }
+
+    /// Takes the records of the rolls made in audit mode, leaving none behind
+    pub fn take_records(&mut self) -> Vec<RandomnessRecord> {
+        std::mem::take(&mut self.records)
+    }
+
+    /// Creates an agent following the chart, mixing actions with a generator from the seed
+    pub fn from_seed(chart: PreflopChart, seed: u64) -> ChartAgent {
+        ChartAgent::new(chart, GameRng::seed_from_u64(seed))
+    }
+
+    /// Gets the chart followed
+    pub fn chart(&self) -> &PreflopChart {
+        &self.chart
+    }
+}
+
+impl Agent<PreflopView, PreflopAction> for ChartAgent {
+    fn choose_action(&mut self, view: &PreflopView) -> PreflopAction {
+        let hand = StartingHand::from_cards(view.hole[0], view.hole[1]);
+        let frequencies = self.chart.get(view.position, hand);
+        if self.audit {
+            let source = format!("{} {hand}", view.position);
+            let (action, record) = frequencies.choose_recorded(&source, &mut self.rng);
+            self.records.push(record);
+            action
+        } else {
+            frequencies.choose(&mut self.rng)
+        }
+    }
+}
+
+/// Parses actions such as `raise` or `raise 50 call 25`, where an action without a percentage
+/// is always taken
+fn parse_frequencies(actions: &str) -> Result<ActionFrequencies, ChartError> {
+    let mut frequencies = ActionFrequencies::default();
+    let mut tokens = actions.split_whitespace().peekable();
+    while let Some(action) = tokens.next() {
+        let percent = match tokens
+            .peek()
+            .map(|token| token.trim_end_matches('%').parse())
+        {
+            Some(Ok(percent)) => {
+                tokens.next();
+                percent
+            }
+            _ => 100,
+        };
+        match action.to_ascii_lowercase().as_str() {
+            "raise" => frequencies.raise = percent,
+            "call" => frequencies.call = percent,
+            "fold" => {}
+            _ => return Err(ChartError::InvalidAction(action.to_string())),
+        }
+    }
+    if u16::from(frequencies.raise) + u16::from(frequencies.call) > 100 {
+        return Err(ChartError::InvalidFrequency(actions.trim().to_string()));
+    }
+    Ok(frequencies)
+}
+
+/// Expands a hand written without a suffix or with a trailing `+` into every hand it stands for
+fn expand_hands(hand: &str) -> Result<Vec<StartingHand>, ChartError> {
+    let (base, plus) = match hand.strip_suffix('+') {
+        Some(base) => (base, true),
+        None => (hand, false),
+    };
+    let is_unsuffixed = base.chars().count() == 2 && base.parse::<StartingHand>().is_err();
+    if is_unsuffixed {
+        let suffixed = |suffix| format!("{base}{suffix}{}", if plus { "+" } else { "" });
+        let mut hands = expand_hands(&suffixed("s"))?;
+        hands.extend(expand_hands(&suffixed("o"))?);
+        return Ok(hands);
+    }
+    let lowest: StartingHand = base.parse()?;
+    if !plus {
+        return Ok(vec![lowest]);
+    }
+    let kickers = RANKS.iter().copied().filter(|rank| *rank >= lowest.low);
+    Ok(if lowest.is_pair() {
+        kickers
+            .filter_map(|rank| StartingHand::new(rank, rank, false))
+            .collect()
+    } else {
+        kickers
+            .take_while(|rank| *rank < lowest.high)
+            .filter_map(|rank| StartingHand::new(lowest.high, rank, lowest.suited))
+            .collect()
+    })
+}
+
+/// Errors related to reading preflop charts
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChartError {
+    #[error("{0:?} is not a position")]
+    InvalidPosition(String),
+    #[error("{0:?} is not a starting hand")]
+    InvalidHand(String),
+    #[error("{0:?} is not a preflop action")]
+    InvalidAction(String),
+    #[error("The frequencies {0:?} add up to more than 100%")]
+    InvalidFrequency(String),
+    #[error("{0:?} does not separate hands from actions with a colon")]
+    InvalidLine(String),
+    #[error("Line {0} lists hands before naming a position")]
+    NoPosition(usize),
+}
@@ -0,0 +1,433 @@
+//! A runtime registry of games, constructed by name
+//!
+//! Each game is registered as a [`GameFactory`], which builds it from a [`GameConfig`] of string
+//! options, so frontends can offer a choice of every enabled game without naming their types
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use rand::SeedableRng;
+use thiserror::Error;
+
+use super::{dynamic::DynGame, id::GameId, info::GameMetadata, GameRng};
+
+/// The option every bundled game accepts, seeding its random number generator
+pub const SEED_OPTION: &str = "seed";
+
+/// The option naming a game, accepted for every game by the registry itself
+pub const ID_OPTION: &str = "id";
+
+/// String options for building a game, such as parsed from a command line or a config file
+/// # Examples
+/// ```
+/// # use naipe::games::registry::GameConfig;
+/// let config = GameConfig::parse("seed=42, decks=2").unwrap();
+/// assert_eq!(config.get("decks"), Some("2"));
+/// assert_eq!(config.seed().unwrap(), Some(42));
+/// assert!(GameConfig::parse("decks").is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameConfig {
+    options: BTreeMap<String, String>,
+}
+
+impl GameConfig {
+    /// Creates a config with no options set
+    pub fn new() -> GameConfig {
+        GameConfig::default()
+    }
+
+    /// Parses `key=value` options, separated by commas or whitespace
+    /// # Errors
+    /// [`RegistryError::InvalidConfig`] if an option has no value
+    pub fn parse(config: &str) -> Result<GameConfig, RegistryError> {
+        config
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|option| !option.is_empty())
+            .try_fold(GameConfig::new(), |config, option| {
+                let (key, value) = option
+                    .split_once('=')
+                    .ok_or_else(|| RegistryError::InvalidConfig(option.to_string()))?;
+                Ok(config.with(key.trim(), value.trim()))
+            })
+    }
+
+    /// Sets an option
+    #[must_use]
+    pub fn with(mut self, key: &str, value: &str) -> GameConfig {
+        self.options.insert(key.to_lowercase(), value.to_string());
+        self
+    }
+
+    /// Gets the value of an option, if set
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.options.get(key).map(String::as_str)
+    }
+
+    /// Gets the value of an option parsed as the given type, if set
+    /// # Errors
+    /// [`RegistryError::InvalidOption`] if the option is set but cannot be parsed
+    pub fn parsed<T: FromStr>(&self, key: &str) -> Result<Option<T>, RegistryError> {
+        self.get(key)
+            .map(|value| {
+                value.parse().map_err(|_| RegistryError::InvalidOption {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+            })
+            .transpose()
+    }
+
+    /// Gets the seed option, if set
+    /// # Errors
+    /// As for [`GameConfig::parsed`]
+    pub fn seed(&self) -> Result<Option<u64>, RegistryError> {
+        self.parsed(SEED_OPTION)
+    }
+
+    /// Gets the ID option, if set
+    pub fn id(&self) -> Option<GameId> {
+        self.get(ID_OPTION).map(GameId::new)
+    }
+
+    /// Creates a random number generator from the seed option, or from entropy if unset
+    /// # Errors
+    /// As for [`GameConfig::parsed`]
+    pub fn rng(&self) -> Result<GameRng, RegistryError> {
+        Ok(self
+            .seed()?
+            .map_or_else(GameRng::from_entropy, GameRng::seed_from_u64))
+    }
+
+    /// Gets the names of every option set
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.options.keys().map(String::as_str)
+    }
+}
+
+/// Builds a game from a config
+pub trait GameFactory {
+    /// Gets the description of the game built
+    fn info(&self) -> GameMetadata;
+
+    /// Gets the names of the options the game accepts
+    fn options(&self) -> &'static [&'static str];
+
+    /// Builds a new game, which can be played through text or downcast to its concrete type
+    /// # Errors
+    /// A [`RegistryError`] if the config's options are invalid for the game
+    fn create(&self, config: &GameConfig) -> Result<Box<dyn DynGame>, RegistryError>;
+}
+
+/// Every game that can be built by name
+/// # Examples
+/// ```
+/// # use naipe::games::registry::{GameConfig, GameRegistry};
+/// # use naipe::games::war::WarGame;
+/// let registry = GameRegistry::bundled();
+/// assert!(registry.names().any(|name| name == "War"));
+///
+/// let config = GameConfig::new().with("seed", "7");
+/// let game = registry.create("war", &config).unwrap();
+/// let war = game.into_any().downcast::<WarGame>().unwrap();
+/// assert_eq!(*war, naipe::games::SeededGame::from_seed(7));
+///
+/// assert!(registry.create("war", &GameConfig::new().with("decks", "2")).is_err());
+/// assert!(registry.create("canasta", &config).is_err());
+/// ```
+#[derive(Default)]
+pub struct GameRegistry {
+    factories: Vec<Box<dyn GameFactory>>,
+}
+
+impl GameRegistry {
+    /// Creates a registry with no games
+    pub fn new() -> GameRegistry {
+        GameRegistry::default()
+    }
+
+    /// Creates a registry of every game enabled by the crate's features
+    /// # Examples
+    /// ```
+    /// # use naipe::games::info::bundled_games;
+    /// # use naipe::games::registry::{GameConfig, GameRegistry};
+    /// let registry = GameRegistry::bundled();
+    /// let config = GameConfig::new().with("seed", "3");
+    /// for game in bundled_games() {
+    ///     let created = registry.create(game.name, &config).unwrap();
+    ///     assert_eq!(created.metadata().name, game.name);
+    ///     assert!(!created.view(0).is_empty());
+    /// }
+    /// # #[cfg(feature = "memory")]
+    /// # {
+    /// let grid = GameConfig::new().with("rows", "2").with("columns", "3");
+    /// let memory = registry.create("memory", &grid).unwrap();
+    /// assert!(memory.view(0).contains("cells=[?,?,?,?,?,?]"));
+    /// assert!(registry.create("memory", &GameConfig::new().with("rows", "2")).is_err());
+    /// # }
+    /// ```
+    pub fn bundled() -> GameRegistry {
+        let registry = GameRegistry::new();
+        #[cfg(feature = "solitaire")]
+        let registry = registry.register(bundled::AccordionFactory);
+        #[cfg(feature = "blackjack")]
+        let registry = registry.register(bundled::BlackjackFactory);
+        #[cfg(feature = "solitaire")]
+        let registry = registry.register(bundled::GolfFactory);
+        #[cfg(feature = "hearts")]
+        let registry = registry.register(bundled::HeartsFactory);
+        #[cfg(feature = "memory")]
+        let registry = registry.register(bundled::MemoryFactory);
+        #[cfg(feature = "war")]
+        let registry = registry.register(bundled::WarFactory);
+        registry
+    }
+
+    /// Adds a game, replacing any registered game of the same name
+    #[must_use]
+    pub fn register<F: GameFactory + 'static>(mut self, factory: F) -> GameRegistry {
+        let name = factory.info().name;
+        self.factories
+            .retain(|existing| !existing.info().name.eq_ignore_ascii_case(name));
+        self.factories.push(Box::new(factory));
+        self
+    }
+
+    /// Gets the names of every registered game, in the order registered
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.factories.iter().map(|factory| factory.info().name)
+    }
+
+    /// Gets the factory for a game by its name, ignoring case
+    pub fn get(&self, name: &str) -> Option<&dyn GameFactory> {
+        self.factories
+            .iter()
+            .find(|factory| factory.info().name.eq_ignore_ascii_case(name))
+            .map(AsRef::as_ref)
+    }
+
+    /// Builds a game by its name, ignoring case
+    /// # Errors
+    /// [`RegistryError::UnknownGame`] if no game has the name,
+    /// [`RegistryError::UnknownOption`] if the config sets an option the game does not accept,
+    /// or another [`RegistryError`] if the game cannot be built from the config
+    pub fn create(
+        &self,
+        name: &str,
+        config: &GameConfig,
+    ) -> Result<Box<dyn DynGame>, RegistryError> {
+        let factory = self
+            .get(name)
+            .ok_or_else(|| RegistryError::UnknownGame(name.to_string()))?;
+        if let Some(unknown) = config
+            .keys()
+            .find(|key| *key != ID_OPTION && !factory.options().contains(key))
+        {
+            return Err(RegistryError::UnknownOption(unknown.to_string()));
+        }
+        factory.create(config)
+    }
+
+    /// Builds a game by its name, as for [`GameRegistry::create`], along with its ID
+    ///
+    /// The ID is taken from the config's [`ID_OPTION`], or a random one is made if it is unset
+    /// # Errors
+    /// As for [`GameRegistry::create`]
+    /// # Examples
+    /// ```
+    /// # use naipe::games::registry::{GameConfig, GameRegistry};
+    /// let registry = GameRegistry::bundled();
+    /// let config = GameConfig::parse("seed=3, id=lobby-7").unwrap();
+    /// let (id, _game) = registry.create_identified("war", &config).unwrap();
+    /// assert_eq!(id.as_str(), "lobby-7");
+    ///
+    /// let (id, _game) = registry.create_identified("war", &GameConfig::new()).unwrap();
+    /// assert_eq!(id.as_str().len(), 36);
+    /// ```
+    pub fn create_identified(
+        &self,
+        name: &str,
+        config: &GameConfig,
+    ) -> Result<(GameId, Box<dyn DynGame>), RegistryError> {
+        let game = self.create(name, config)?;
+        let id = config
+            .id()
+            .unwrap_or_else(|| GameId::random(&mut rand::thread_rng()));
+        Ok((id, game))
+    }
+}
+
+/// Factories for the games bundled with naipe
+#[cfg(any(
+    feature = "blackjack",
+    feature = "hearts",
+    feature = "memory",
+    feature = "solitaire",
+    feature = "war"
+))]
+mod bundled {
+    use super::{DynGame, GameConfig, GameFactory, GameMetadata, RegistryError, SEED_OPTION};
+    use crate::games::info::GameInfo;
+
+    #[cfg(feature = "solitaire")]
+    pub(super) struct AccordionFactory;
+
+    #[cfg(feature = "solitaire")]
+    impl GameFactory for AccordionFactory {
+        fn info(&self) -> GameMetadata {
+            crate::games::solitaire::accordion::AccordionGame::info()
+        }
+
+        fn options(&self) -> &'static [&'static str] {
+            &[SEED_OPTION]
+        }
+
+        fn create(&self, config: &GameConfig) -> Result<Box<dyn DynGame>, RegistryError> {
+            use crate::games::{solitaire::accordion::AccordionGame, SeededGame};
+
+            Ok(Box::new(AccordionGame::new_with_rng(&mut config.rng()?)))
+        }
+    }
+
+    #[cfg(feature = "blackjack")]
+    pub(super) struct BlackjackFactory;
+
+    #[cfg(feature = "blackjack")]
+    impl GameFactory for BlackjackFactory {
+        fn info(&self) -> GameMetadata {
+            crate::games::blackjack::BlackjackGame::info()
+        }
+
+        fn options(&self) -> &'static [&'static str] {
+            &[SEED_OPTION, "decks", "seats"]
+        }
+
+        fn create(&self, config: &GameConfig) -> Result<Box<dyn DynGame>, RegistryError> {
+            use crate::games::blackjack::{rules::BlackjackRules, BlackjackGame};
+            use std::num::{NonZeroU16, NonZeroUsize};
+
+            let mut rules = BlackjackRules::default();
+            if let Some(decks) = config.parsed::<NonZeroU16>("decks")? {
+                rules.decks = decks;
+            }
+            let seats = config
+                .parsed::<NonZeroUsize>("seats")?
+                .unwrap_or(NonZeroUsize::MIN);
+            let game = BlackjackGame::with_rules_and_rng(rules, seats, &mut config.rng()?)
+                .map_err(|conflict| RegistryError::Setup(conflict.to_string()))?;
+            Ok(Box::new(game))
+        }
+    }
+
+    #[cfg(feature = "memory")]
+    pub(super) struct MemoryFactory;
+
+    #[cfg(feature = "memory")]
+    impl GameFactory for MemoryFactory {
+        fn info(&self) -> GameMetadata {
+            crate::games::memory::MemoryGame::info()
+        }
+
+        fn options(&self) -> &'static [&'static str] {
+            &[SEED_OPTION, "rows", "columns", "players"]
+        }
+
+        fn create(&self, config: &GameConfig) -> Result<Box<dyn DynGame>, RegistryError> {
+            use crate::games::memory::MemoryGame;
+
+            let mut builder = MemoryGame::builder();
+            match (config.parsed("rows")?, config.parsed("columns")?) {
+                (Some(rows), Some(columns)) => builder = builder.grid(rows, columns),
+                (None, None) => {}
+                _ => {
+                    return Err(RegistryError::Setup(
+                        "rows and columns must be set together".to_string(),
+                    ))
+                }
+            }
+            if let Some(players) = config.parsed("players")? {
+                builder = builder.players(players);
+            }
+            let game = builder
+                .build_with_rng(&mut config.rng()?)
+                .map_err(|invalid| RegistryError::Setup(invalid.to_string()))?;
+            Ok(Box::new(game))
+        }
+    }
+
+    #[cfg(feature = "solitaire")]
+    pub(super) struct GolfFactory;
+
+    #[cfg(feature = "solitaire")]
+    impl GameFactory for GolfFactory {
+        fn info(&self) -> GameMetadata {
+            crate::games::solitaire::golf::GolfGame::info()
+        }
+
+        fn options(&self) -> &'static [&'static str] {
+            &[SEED_OPTION]
+        }
+
+        fn create(&self, config: &GameConfig) -> Result<Box<dyn DynGame>, RegistryError> {
+            use crate::games::{solitaire::golf::GolfGame, SeededGame};
+
+            Ok(Box::new(GolfGame::new_with_rng(&mut config.rng()?)))
+        }
+    }
+
+    #[cfg(feature = "hearts")]
+    pub(super) struct HeartsFactory;
+
+    #[cfg(feature = "hearts")]
+    impl GameFactory for HeartsFactory {
+        fn info(&self) -> GameMetadata {
+            crate::games::hearts::HeartsGame::info()
+        }
+
+        fn options(&self) -> &'static [&'static str] {
+            &[SEED_OPTION]
+        }
+
+        fn create(&self, config: &GameConfig) -> Result<Box<dyn DynGame>, RegistryError> {
+            use crate::games::{hearts::HeartsGame, SeededGame};
+
+            Ok(Box::new(HeartsGame::new_with_rng(&mut config.rng()?)))
+        }
+    }
+
+    #[cfg(feature = "war")]
+    pub(super) struct WarFactory;
+
+    #[cfg(feature = "war")]
+    impl GameFactory for WarFactory {
+        fn info(&self) -> GameMetadata {
+            crate::games::war::WarGame::info()
+        }
+
+        fn options(&self) -> &'static [&'static str] {
+            &[SEED_OPTION]
+        }
+
+        fn create(&self, config: &GameConfig) -> Result<Box<dyn DynGame>, RegistryError> {
+            use crate::games::{war::WarGame, SeededGame};
+
+            Ok(Box::new(WarGame::new_with_rng(&mut config.rng()?)))
+        }
+    }
+}
+
+/// Errors related to building games by name
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RegistryError {
+    #[error("No game named {0} is registered")]
+    UnknownGame(String),
+    #[error("The game does not accept the option {0}")]
+    UnknownOption(String),
+    #[error("{value} is not a valid value for the option {key}")]
+    InvalidOption { key: String, value: String },
+    #[error("{0} is not a key=value option")]
+    InvalidConfig(String),
+    #[error("The game could not be set up: {0}")]
+    Setup(String),
+}
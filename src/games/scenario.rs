@@ -0,0 +1,215 @@
+//! Fully scripted games, for demos, tutorials, and deterministic UI tests
+//!
+//! A [`Scenario`] fixes both the deal, as the starting state of the game, and every decision
+//! made in it, so it plays out the same way every time without any seed. Each scripted step may
+//! carry a caption, shown when the step is played, to walk a reader through the game
+
+use std::cell::Cell;
+
+use thiserror::Error;
+
+use super::agent::{Agent, AgentGame};
+
+/// A scripted decision, with an optional caption explaining it
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Step<A> {
+    pub action: A,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub caption: Option<String>,
+}
+
+impl<A> Step<A> {
+    /// Creates a step with no caption
+    pub fn new(action: A) -> Step<A> {
+        Step {
+            action,
+            caption: None,
+        }
+    }
+
+    /// Creates a step with a caption
+    pub fn captioned(action: A, caption: impl Into<String>) -> Step<A> {
+        Step {
+            action,
+            caption: Some(caption.into()),
+        }
+    }
+}
+
+/// A game with a fixed deal and fixed decisions
+///
+/// Steps are handed out in order to whichever seat is asked for a decision next, so the script
+/// lists decisions in the order the game asks for them
+/// # Examples
+/// ```
+/// # use naipe::common::deck::Deck;
+/// # use naipe::games::scenario::{Scenario, ScenarioError, Step};
+/// # use naipe::games::solitaire::accordion::{AccordionGame, AccordionMove};
+/// let start = AccordionGame::deal(&Deck::from_notation("2S 3S 4S").unwrap());
+/// let squeeze = AccordionMove { from: 1, onto: 0 };
+/// let scenario = Scenario {
+///     title: "Squeezing spades".to_string(),
+///     start,
+///     steps: vec![
+///         Step::captioned(squeeze, "The three of spades matches the two's suit"),
+///         Step::new(squeeze),
+///     ],
+/// };
+/// let mut player = scenario.player();
+/// let first = player.step().unwrap();
+/// assert_eq!(first.captions, vec!["The three of spades matches the two's suit"]);
+/// assert!(!first.finished);
+/// assert!(player.step().unwrap().finished);
+/// assert!(scenario.play().unwrap().is_won());
+///
+/// let unscripted = Scenario {
+///     steps: vec![],
+///     ..scenario
+/// };
+/// assert_eq!(unscripted.play(), Err(ScenarioError::Exhausted { tick: 0 }));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scenario<G, A> {
+    /// Names the scenario for listing in a demo or tutorial
+    pub title: String,
+    /// The game as dealt, before any decisions
+    pub start: G,
+    /// Every decision made in the game, in the order they are asked for
+    pub steps: Vec<Step<A>>,
+}
+
+impl<G, A> Scenario<G, A>
+where
+    G: AgentGame<Action = A, TickOk = bool> + Clone,
+    A: Clone,
+{
+    /// Starts playing the scenario back from its deal, a tick at a time
+    pub fn player(&self) -> ScenarioPlayer<'_, G, A> {
+        ScenarioPlayer {
+            scenario: self,
+            game: self.start.clone(),
+            next_step: 0,
+            ticks: 0,
+            finished: false,
+        }
+    }
+
+    /// Plays the scenario to the end, checking the script is used up exactly as the game finishes
+    /// # Errors
+    /// As for [`ScenarioPlayer::step`], or [`ScenarioError::UnusedSteps`] if the game finishes
+    /// before every step is played
+    pub fn play(&self) -> Result<G, ScenarioError> {
+        let mut player = self.player();
+        while !player.step()?.finished {}
+        match self.steps.len() - player.next_step {
+            0 => Ok(player.game),
+            unused => Err(ScenarioError::UnusedSteps(unused)),
+        }
+    }
+}
+
+/// What happened in a single tick of a scenario
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScenarioTick<'a> {
+    /// The captions of the steps played in the tick
+    pub captions: Vec<&'a str>,
+    /// Whether the game finished in the tick
+    pub finished: bool,
+}
+
+/// Plays a [`Scenario`] back a tick at a time, for showing each position in turn
+#[derive(Clone, Debug)]
+pub struct ScenarioPlayer<'a, G, A> {
+    scenario: &'a Scenario<G, A>,
+    game: G,
+    next_step: usize,
+    ticks: usize,
+    finished: bool,
+}
+
+impl<'a, G, A> ScenarioPlayer<'a, G, A>
+where
+    G: AgentGame<Action = A, TickOk = bool>,
+    A: Clone,
+{
+    /// Gets the game as it stands
+    pub fn game(&self) -> &G {
+        &self.game
+    }
+
+    /// Gets the number of steps played so far
+    pub fn steps_played(&self) -> usize {
+        self.next_step
+    }
+
+    /// Gets the number of ticks played so far
+    pub fn ticks(&self) -> usize {
+        self.ticks
+    }
+
+    /// Ticks the game, answering every decision it asks for with the next scripted step
+    /// # Errors
+    /// [`ScenarioError::Exhausted`] if the game asks for more decisions than scripted, or the
+    /// scenario has no steps at all, [`ScenarioError::TickFailed`] if the tick fails, or
+    /// [`ScenarioError::Finished`] if the game has already finished
+    pub fn step(&mut self) -> Result<ScenarioTick<'a>, ScenarioError> {
+        if self.finished {
+            return Err(ScenarioError::Finished);
+        }
+        let tick = self.ticks;
+        let scenario: &'a Scenario<G, A> = self.scenario;
+        let steps = &scenario.steps;
+        // With no steps, there is nothing to answer the game's decisions with
+        let Some(last) = steps.last() else {
+            return Err(ScenarioError::Exhausted { tick });
+        };
+        let cursor = Cell::new(self.next_step);
+        let exhausted = Cell::new(false);
+        // A script run dry keeps repeating its last step, so the tick can finish before failing
+        let scripted = |_: &G::View| {
+            let index = cursor.get();
+            if index < steps.len() {
+                cursor.set(index + 1);
+            } else {
+                exhausted.set(true);
+            }
+            steps.get(index).unwrap_or(last).action.clone()
+        };
+        let mut agents = vec![scripted; self.game.seat_count()];
+        let mut seats: Vec<&mut dyn Agent<G::View, A>> = agents
+            .iter_mut()
+            .map(|agent| agent as &mut dyn Agent<G::View, A>)
+            .collect();
+        let result = self.game.tick_with_agents(&mut seats);
+        self.ticks += 1;
+        let played = self.next_step..cursor.get();
+        self.next_step = cursor.get();
+        if exhausted.get() {
+            return Err(ScenarioError::Exhausted { tick });
+        }
+        self.finished = result.map_err(|_| ScenarioError::TickFailed { tick })?;
+        Ok(ScenarioTick {
+            captions: steps[played]
+                .iter()
+                .filter_map(|step| step.caption.as_deref())
+                .collect(),
+            finished: self.finished,
+        })
+    }
+}
+
+/// Errors related to playing back a scenario
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ScenarioError {
+    #[error("The game asked for more decisions than scripted, at tick {tick}")]
+    Exhausted { tick: usize },
+    #[error("The game failed to tick at tick {tick}")]
+    TickFailed { tick: usize },
+    #[error("The game finished with {0} scripted steps left unplayed")]
+    UnusedSteps(usize),
+    #[error("The game has already finished")]
+    Finished,
+}
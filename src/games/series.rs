@@ -0,0 +1,231 @@
+//! Matches of several games between the same players
+//!
+//! A [`Series`] rotates the dealer each game, so that the first player to act alternates,
+//! and tracks wins until one player has won a majority of the games
+
+use std::num::NonZeroUsize;
+
+use thiserror::Error;
+
+/// A best-of-N series of games with a rotating dealer
+/// # Examples
+/// ```
+/// # use naipe::games::series::Series;
+/// use std::num::NonZeroUsize;
+/// let mut series = Series::new(NonZeroUsize::new(2).unwrap(), NonZeroUsize::new(3).unwrap());
+/// assert_eq!((series.dealer(), series.first_player()), (0, 1));
+/// series.record(Some(1)).unwrap();
+/// assert_eq!((series.dealer(), series.first_player()), (1, 0));
+/// series.record(Some(1)).unwrap();
+/// assert!(series.is_over());
+/// assert_eq!(series.winner(), Some(1));
+/// assert!(series.record(Some(0)).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SavedSeries"))]
+pub struct Series {
+    best_of: usize,
+    first_dealer: usize,
+    wins: Vec<usize>,
+    results: Vec<Option<usize>>,
+}
+
+impl Series {
+    /// Creates a series between the given number of seats, lasting at most `best_of` games
+    ///
+    /// Seat 0 deals the first game
+    pub fn new(seats: NonZeroUsize, best_of: NonZeroUsize) -> Series {
+        Series::with_first_dealer(seats, best_of, 0)
+    }
+
+    /// Creates a series where the given seat deals the first game, as for a rematch
+    ///
+    /// The first dealer wraps around if it is past the last seat
+    pub fn with_first_dealer(seats: NonZeroUsize, best_of: NonZeroUsize, dealer: usize) -> Series {
+        Series {
+            best_of: best_of.get(),
+            first_dealer: dealer % seats.get(),
+            wins: vec![0; seats.get()],
+            results: vec![],
+        }
+    }
+
+    /// Spots a seat wins before play, as a handicap
+    ///
+    /// Spotted wins count towards a majority, but are not games played
+    /// # Errors
+    /// [`SeriesError::UnknownSeat`] if the seat is not in the series
+    /// # Examples
+    /// ```
+    /// # use naipe::games::series::Series;
+    /// use std::num::NonZeroUsize;
+    /// let mut series = Series::new(NonZeroUsize::new(2).unwrap(), NonZeroUsize::new(5).unwrap());
+    /// series.spot(1, 2).unwrap();
+    /// series.record(Some(1)).unwrap();
+    /// assert_eq!(series.winner(), Some(1));
+    /// assert_eq!(series.games_played(), 1);
+    /// ```
+    pub fn spot(&mut self, seat: usize, wins: usize) -> Result<(), SeriesError> {
+        *self
+            .wins
+            .get_mut(seat)
+            .ok_or(SeriesError::UnknownSeat(seat))? += wins;
+        Ok(())
+    }
+
+    /// Gets the number of seats in the series
+    pub fn seats(&self) -> usize {
+        self.wins.len()
+    }
+
+    /// Gets the seat dealing the next game
+    pub fn dealer(&self) -> usize {
+        (self.first_dealer + self.results.len()) % self.seats()
+    }
+
+    /// Gets the seat acting first in the next game, to the dealer's left
+    pub fn first_player(&self) -> usize {
+        (self.dealer() + 1) % self.seats()
+    }
+
+    /// Gets every seat in playing order for the next game, starting with the first player
+    pub fn play_order(&self) -> Vec<usize> {
+        let first = self.first_player();
+        (0..self.seats())
+            .map(|offset| (first + offset) % self.seats())
+            .collect()
+    }
+
+    /// Records the winner of a game, or `None` for a drawn game
+    /// # Errors
+    /// [`SeriesError::UnknownSeat`] if the winner is not in the series, or
+    /// [`SeriesError::SeriesOver`] if the series has already been decided
+    pub fn record(&mut self, winner: Option<usize>) -> Result<(), SeriesError> {
+        if self.is_over() {
+            return Err(SeriesError::SeriesOver);
+        }
+        if let Some(seat) = winner {
+            *self
+                .wins
+                .get_mut(seat)
+                .ok_or(SeriesError::UnknownSeat(seat))? += 1;
+        }
+        self.results.push(winner);
+        Ok(())
+    }
+
+    /// Plays games until the series is over, returning the series winner
+    ///
+    /// The closure is given the dealer and the playing order for each game, and returns
+    /// the winning seat
+    /// # Errors
+    /// [`SeriesError::UnknownSeat`] if a game's winner is not in the series
+    pub fn play<F: FnMut(usize, &[usize]) -> Option<usize>>(
+        &mut self,
+        mut play_game: F,
+    ) -> Result<Option<usize>, SeriesError> {
+        while !self.is_over() {
+            let winner = play_game(self.dealer(), &self.play_order());
+            self.record(winner)?;
+        }
+        Ok(self.winner())
+    }
+
+    /// Gets the number of games won by each seat
+    pub fn wins(&self) -> &[usize] {
+        &self.wins
+    }
+
+    /// Gets the winner of each game played so far, in order
+    pub fn results(&self) -> &[Option<usize>] {
+        &self.results
+    }
+
+    /// Gets the number of games played so far
+    pub fn games_played(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Finds if the series has been decided, either by a majority of wins or by
+    /// playing every game
+    pub fn is_over(&self) -> bool {
+        self.majority_winner().is_some() || self.results.len() >= self.best_of
+    }
+
+    /// Gets the winner of the series once it is over
+    ///
+    /// If every game was played without a majority, the seat with the most wins takes the series,
+    /// and a tie for most wins leaves the series drawn
+    /// # Examples
+    /// ```
+    /// # use naipe::games::series::Series;
+    /// use std::num::NonZeroUsize;
+    /// let mut series = Series::new(NonZeroUsize::new(2).unwrap(), NonZeroUsize::new(3).unwrap());
+    /// let winner = series.play(|_, _| None).unwrap();
+    /// assert_eq!(winner, None);
+    /// assert_eq!(series.games_played(), 3);
+    /// ```
+    pub fn winner(&self) -> Option<usize> {
+        if let Some(seat) = self.majority_winner() {
+            return Some(seat);
+        }
+        if self.results.len() < self.best_of {
+            return None;
+        }
+        let most = *self.wins.iter().max()?;
+        let mut leaders = (0..self.seats()).filter(|seat| self.wins[*seat] == most);
+        match (leaders.next(), leaders.next()) {
+            (Some(seat), None) if most > 0 => Some(seat),
+            _ => None,
+        }
+    }
+
+    fn majority_winner(&self) -> Option<usize> {
+        (0..self.seats()).find(|seat| self.wins[*seat] > self.best_of / 2)
+    }
+}
+
+/// A series as saved, checked before it is loaded
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SavedSeries {
+    best_of: usize,
+    first_dealer: usize,
+    wins: Vec<usize>,
+    results: Vec<Option<usize>>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SavedSeries> for Series {
+    type Error = SeriesError;
+
+    /// Loads a saved series, refusing one with no seats or with a winner who is not seated
+    fn try_from(saved: SavedSeries) -> Result<Series, SeriesError> {
+        let seats = saved.wins.len();
+        if seats == 0 {
+            return Err(SeriesError::NoSeats);
+        }
+        if let Some(seat) = saved.results.iter().flatten().find(|seat| **seat >= seats) {
+            return Err(SeriesError::UnknownSeat(*seat));
+        }
+        Ok(Series {
+            best_of: saved.best_of,
+            first_dealer: saved.first_dealer % seats,
+            wins: saved.wins,
+            results: saved.results,
+        })
+    }
+}
+
+/// Errors related to recording a series
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SeriesError {
+    #[error("No player sits in seat {0}")]
+    UnknownSeat(usize),
+    #[error("The series is already decided")]
+    SeriesOver,
+    #[error("A series needs at least one seat")]
+    NoSeats,
+}
@@ -0,0 +1,276 @@
+//! Bulk Monte Carlo runs of seeded games
+//!
+//! A [`Simulator`] plays many games to completion, each from its own seed, and aggregates
+//! who won and how long the games lasted. With the `rayon` feature, games can be spread
+//! across threads, giving exactly the same report as a run on a single thread.
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use super::{Game, GameOutcome, SeededGame};
+use crate::stats::RunningStats;
+
+/// Runs many seeded games to completion, cutting off any that run too long
+///
+/// Run `n` is created from the seed `base_seed + n`, so any game of interest can be recreated
+/// with [`SeededGame::from_seed`]
+/// # Examples
+/// ```
+/// # use naipe::games::simulation::Simulator;
+/// # use naipe::games::war::WarGame;
+/// let report = Simulator::new(50).seed(7).max_ticks(100_000).run::<WarGame>();
+/// assert_eq!(report.games(), 50);
+/// assert_eq!(
+///     report.wins().iter().sum::<u64>() + report.draws() + report.cut_off().len() as u64,
+///     50
+/// );
+/// assert!(report.length_stats().mean().unwrap() > 0.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Simulator {
+    games: u64,
+    max_ticks: usize,
+    base_seed: u64,
+}
+
+impl Simulator {
+    /// Creates a simulator playing the given number of games, from a random base seed
+    pub fn new(games: u64) -> Simulator {
+        Simulator {
+            games,
+            max_ticks: DEFAULT_MAX_TICKS,
+            base_seed: rand::random(),
+        }
+    }
+
+    /// Sets the base seed, so the whole simulation can be repeated exactly
+    #[must_use]
+    pub fn seed(mut self, base_seed: u64) -> Simulator {
+        self.base_seed = base_seed;
+        self
+    }
+
+    /// Sets the number of ticks after which a game is cut off as non-terminating
+    #[must_use]
+    pub fn max_ticks(mut self, max_ticks: usize) -> Simulator {
+        self.max_ticks = max_ticks;
+        self
+    }
+
+    /// Gets the seed for the given run
+    pub fn seed_for(&self, run: u64) -> u64 {
+        self.base_seed.wrapping_add(run)
+    }
+
+    /// Plays every game on the current thread
+    pub fn run<G>(&self) -> SimulationReport
+    where
+        G: SeededGame + Game<TickOk = bool> + GameOutcome,
+    {
+        let mut report = SimulationReport::default();
+        for run in 0..self.games {
+            report.record(self.seed_for(run), self.play::<G>(self.seed_for(run)));
+        }
+        report
+    }
+
+    /// Plays every game across threads, giving exactly the same report as [`Simulator::run`]
+    /// # Examples
+    /// ```
+    /// # use naipe::games::simulation::Simulator;
+    /// # use naipe::games::war::WarGame;
+    /// for seed in 0..4 {
+    ///     let simulator = Simulator::new(100).seed(seed);
+    ///     let report = simulator.run_parallel::<WarGame>();
+    ///     assert_eq!(report, simulator.run::<WarGame>());
+    ///     assert_eq!(report.length_stats(), simulator.run::<WarGame>().length_stats());
+    /// }
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn run_parallel<G>(&self) -> SimulationReport
+    where
+        G: SeededGame + Game<TickOk = bool> + GameOutcome,
+    {
+        (0..self.games)
+            .into_par_iter()
+            .map(|run| {
+                let mut report = SimulationReport::default();
+                report.record(self.seed_for(run), self.play::<G>(self.seed_for(run)));
+                report
+            })
+            .reduce(SimulationReport::default, |mut report, other| {
+                report.merge(&other);
+                report
+            })
+    }
+
+    fn play<G>(&self, seed: u64) -> RunResult
+    where
+        G: SeededGame + Game<TickOk = bool> + GameOutcome,
+    {
+        let mut game = G::from_seed(seed);
+        for tick in 1..=self.max_ticks {
+            match game.tick() {
+                Ok(true) => {
+                    return RunResult::Finished {
+                        winner: game.winner(),
+                        ticks: tick,
+                    }
+                }
+                Ok(false) => {}
+                Err(_) => return RunResult::Failed,
+            }
+        }
+        RunResult::CutOff
+    }
+}
+
+/// Games are cut off after this many ticks, unless configured otherwise
+const DEFAULT_MAX_TICKS: usize = 100_000;
+
+/// How a single simulated game ended
+#[derive(Clone, Copy)]
+enum RunResult {
+    Finished { winner: Option<usize>, ticks: usize },
+    CutOff,
+    Failed,
+}
+
+/// Aggregated results of a simulation
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulationReport {
+    games: u64,
+    wins: Vec<u64>,
+    draws: u64,
+    cut_off: Vec<u64>,
+    failed: Vec<u64>,
+    lengths: BTreeMap<usize, u64>,
+}
+
+impl SimulationReport {
+    fn record(&mut self, seed: u64, result: RunResult) {
+        self.games += 1;
+        match result {
+            RunResult::Finished { winner, ticks } => {
+                match winner {
+                    Some(seat) => {
+                        if self.wins.len() <= seat {
+                            self.wins.resize(seat + 1, 0);
+                        }
+                        self.wins[seat] += 1;
+                    }
+                    None => self.draws += 1,
+                }
+                *self.lengths.entry(ticks).or_default() += 1;
+            }
+            RunResult::CutOff => self.cut_off.push(seed),
+            RunResult::Failed => self.failed.push(seed),
+        }
+    }
+
+    /// Combines the results of another report into this one
+    pub fn merge(&mut self, other: &SimulationReport) {
+        self.games += other.games;
+        if self.wins.len() < other.wins.len() {
+            self.wins.resize(other.wins.len(), 0);
+        }
+        for (wins, other_wins) in self.wins.iter_mut().zip(&other.wins) {
+            *wins += other_wins;
+        }
+        self.draws += other.draws;
+        self.cut_off.extend(&other.cut_off);
+        self.failed.extend(&other.failed);
+        for (ticks, count) in &other.lengths {
+            *self.lengths.entry(*ticks).or_default() += count;
+        }
+    }
+
+    /// Gets the number of games played
+    pub fn games(&self) -> u64 {
+        self.games
+    }
+
+    /// Gets the number of games won by each seat
+    pub fn wins(&self) -> &[u64] {
+        &self.wins
+    }
+
+    /// Gets the fraction of all games played that the seat won
+    #[allow(clippy::cast_precision_loss)]
+    pub fn win_rate(&self, seat: usize) -> Option<f64> {
+        (self.games > 0)
+            .then(|| self.wins.get(seat).copied().unwrap_or_default() as f64 / self.games as f64)
+    }
+
+    /// Gets the exact fraction of all games played that the seat won
+    #[cfg(feature = "exact-math")]
+    pub fn win_ratio(&self, seat: usize) -> Option<crate::common::ratio::Ratio> {
+        crate::common::ratio::Ratio::new(
+            self.wins.get(seat).copied().unwrap_or_default(),
+            self.games,
+        )
+    }
+
+    /// Gets the number of finished games without a winner
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
+    /// Gets the seeds of the games cut off for running too long
+    pub fn cut_off(&self) -> &[u64] {
+        &self.cut_off
+    }
+
+    /// Gets the seeds of the games which failed to tick
+    pub fn failed(&self) -> &[u64] {
+        &self.failed
+    }
+
+    /// Gets the mean, variance, and range of the length in ticks of finished games
+    ///
+    /// Computed from the histogram of lengths, shortest first, so the result does not depend on
+    /// the order the games finished in
+    pub fn length_stats(&self) -> RunningStats {
+        let mut stats = RunningStats::new();
+        for (ticks, count) in &self.lengths {
+            #[allow(clippy::cast_precision_loss)]
+            stats.add_repeated(*ticks as f64, *count);
+        }
+        stats
+    }
+
+    /// Gets how many finished games lasted each number of ticks
+    pub fn length_histogram(&self) -> &BTreeMap<usize, u64> {
+        &self.lengths
+    }
+
+    /// Gets the length in ticks below which the given fraction of finished games ended
+    /// # Examples
+    /// ```
+    /// # use naipe::games::simulation::Simulator;
+    /// # use naipe::games::war::WarGame;
+    /// let report = Simulator::new(20).seed(3).run::<WarGame>();
+    /// let median = report.length_quantile(0.5).unwrap();
+    /// assert!(median <= report.length_quantile(0.9).unwrap());
+    /// ```
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn length_quantile(&self, quantile: f64) -> Option<usize> {
+        let finished: u64 = self.lengths.values().sum();
+        if finished == 0 {
+            return None;
+        }
+        let target = (quantile.clamp(0.0, 1.0) * finished as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        self.lengths.iter().find_map(|(ticks, count)| {
+            seen += count;
+            (seen >= target).then_some(*ticks)
+        })
+    }
+}
@@ -0,0 +1,307 @@
+//! Accordion, squeezing a row of cards into a single pile
+//!
+//! Every card is dealt face up in a row, each its own pile. A pile may be moved onto the pile
+//! directly to its left, or onto the third pile to its left, if their top cards share a suit or
+//! rank. The game is won once a single pile is left.
+
+use std::collections::HashSet;
+
+use rand::Rng;
+
+use super::{shuffled_deck, SolitaireError, SolitaireEvent, SolitaireVariant, Solution};
+use crate::common::{card::Card, deck::Deck};
+use crate::games::{
+    agent::{Agent, AgentGame},
+    audit::AuditedGame,
+    dynamic::ErasedGame,
+    event::EventSource,
+    info::{GameInfo, GameMetadata, GameTag},
+    GameOutcome, SeededGame,
+};
+
+/// How many piles to the left a pile may move, besides the pile directly next to it
+const ACCORDION_REACH: usize = 3;
+
+/// A move in Accordion, moving one pile onto another to its left
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccordionMove {
+    /// The pile moved, counting from zero on the left
+    pub from: usize,
+    /// The pile moved onto, one or three places to the left
+    pub onto: usize,
+}
+
+/// A game of Accordion
+///
+/// Accordion is played alone with every card face up, so the single agent sees the whole game
+/// # Examples
+/// ```
+/// # use naipe::common::deck::Deck;
+/// # use naipe::games::solitaire::accordion::{AccordionGame, AccordionMove};
+/// let mut game = AccordionGame::deal(&Deck::from_notation("2S 9H 3D 4S").unwrap());
+/// assert_eq!(game.legal_moves(), vec![AccordionMove { from: 3, onto: 0 }]);
+/// game.play(AccordionMove { from: 3, onto: 0 }).unwrap();
+/// assert_eq!(game.tops(), vec!["4S".parse().unwrap(), "9H".parse().unwrap(), "3D".parse().unwrap()]);
+/// assert!(game.is_over());
+/// assert!(!game.is_won());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccordionGame {
+    piles: Vec<Vec<Card>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<SolitaireEvent>,
+}
+
+impl AccordionGame {
+    /// Lays out every card of the deck in a row, the top card on the left
+    ///
+    /// Any number of cards may be played with, for quick games and small solver tests
+    pub fn deal(deck: &Deck) -> AccordionGame {
+        let mut deck = deck.clone();
+        AccordionGame {
+            piles: std::iter::from_fn(|| deck.draw())
+                .map(|card| vec![card])
+                .collect(),
+            events: vec![],
+        }
+    }
+
+    /// Gets each pile from left to right, with its top card last
+    pub fn piles(&self) -> &[Vec<Card>] {
+        &self.piles
+    }
+
+    /// Gets the top card of each pile, from left to right
+    pub fn tops(&self) -> Vec<Card> {
+        self.piles
+            .iter()
+            .filter_map(|pile| pile.last().copied())
+            .collect()
+    }
+
+    /// Gets every move allowed now, from the leftmost pile moved, and the nearer target first
+    pub fn legal_moves(&self) -> Vec<AccordionMove> {
+        let tops = self.tops();
+        let matches = |card: Card, other: Card| {
+            card.get_suit() == other.get_suit() || card.get_rank() == other.get_rank()
+        };
+        (1..tops.len())
+            .flat_map(|from| {
+                [1, ACCORDION_REACH]
+                    .into_iter()
+                    .filter_map(move |reach| from.checked_sub(reach))
+                    .map(move |onto| AccordionMove { from, onto })
+            })
+            .filter(|next| matches(tops[next.from], tops[next.onto]))
+            .collect()
+    }
+
+    /// Makes a move
+    /// # Errors
+    /// [`SolitaireError::IllegalMove`] if the move is not allowed now
+    pub fn play(&mut self, next: AccordionMove) -> Result<(), SolitaireError> {
+        if !self.legal_moves().contains(&next) {
+            return Err(SolitaireError::IllegalMove);
+        }
+        let moved = self.piles.remove(next.from);
+        self.piles[next.onto].extend(moved);
+        Ok(())
+    }
+
+    /// Finds if a single pile is left
+    pub fn is_won(&self) -> bool {
+        self.piles.len() <= 1
+    }
+
+    /// Finds if the game is won, or no move is left
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.legal_moves().is_empty()
+    }
+
+    /// Searches for a way to win from this position, looking at no more than the given number of
+    /// positions
+    ///
+    /// Gives `None` if the game cannot be won, or if no win was found within the limit. Few deals
+    /// of a whole deck can be won, and searching one takes many positions, so short rows suit
+    /// quick tests
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::Deck;
+    /// # use naipe::games::solitaire::accordion::AccordionGame;
+    /// let game = AccordionGame::deal(&Deck::from_notation("AS 2H 2S 3H 3S").unwrap());
+    /// let solution = game.solve(1_000).unwrap();
+    /// let mut game = game.clone();
+    /// for next in solution.moves {
+    ///     game.play(next).unwrap();
+    /// }
+    /// assert!(game.is_won());
+    /// ```
+    pub fn solve(&self, max_positions: u64) -> Option<Solution<AccordionMove>> {
+        let mut seen = HashSet::new();
+        let mut moves = vec![];
+        let mut positions = 0;
+        // Searching copies the game at every position, so leaves the deal's record behind
+        let start = AccordionGame {
+            events: vec![],
+            ..self.clone()
+        };
+        start
+            .search(&mut seen, &mut moves, &mut positions, max_positions)
+            .then_some(Solution { moves, positions })
+    }
+
+    /// Searches depth first for a win, skipping positions already seen
+    fn search(
+        &self,
+        seen: &mut HashSet<Vec<Card>>,
+        moves: &mut Vec<AccordionMove>,
+        positions: &mut u64,
+        max_positions: u64,
+    ) -> bool {
+        if self.is_won() {
+            return true;
+        }
+        if *positions >= max_positions || !seen.insert(self.tops()) {
+            return false;
+        }
+        *positions += 1;
+        for next in self.legal_moves() {
+            let mut game = self.clone();
+            if game.play(next).is_err() {
+                continue;
+            }
+            moves.push(next);
+            if game.search(seen, moves, positions, max_positions) {
+                return true;
+            }
+            moves.pop();
+        }
+        false
+    }
+}
+
+impl SeededGame for AccordionGame {
+    /// Deals a game of Accordion from a deck shuffled by a generator seeded from the given one
+    fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> AccordionGame {
+        let (deck, _) = shuffled_deck(SolitaireVariant::Accordion, rng, false);
+        AccordionGame::deal(&deck)
+    }
+}
+
+impl AuditedGame for AccordionGame {
+    /// Deals a game of Accordion, recording the shuffle as an event given until the first tick
+    fn audited_with_rng<R: Rng + ?Sized>(rng: &mut R) -> AccordionGame {
+        let (deck, events) = shuffled_deck(SolitaireVariant::Accordion, rng, true);
+        let mut game = AccordionGame::deal(&deck);
+        game.events = events;
+        game
+    }
+}
+
+impl AgentGame for AccordionGame {
+    type View = AccordionGame;
+    type Action = AccordionMove;
+    /// Whether the game is over
+    type TickOk = bool;
+    type TickError = SolitaireError;
+
+    fn seat_count(&self) -> usize {
+        1
+    }
+
+    fn view(&self, _seat: usize) -> AccordionGame {
+        self.clone()
+    }
+
+    /// Asks the agent for a move, and makes it
+    fn tick_with_agents(
+        &mut self,
+        agents: &mut [&mut dyn Agent<AccordionGame, AccordionMove>],
+    ) -> Result<bool, SolitaireError> {
+        let [agent] = agents else {
+            return Err(SolitaireError::WrongAgentCount(agents.len()));
+        };
+        // Only the deal emits events, which the first tick leaves behind
+        self.events.clear();
+        if !self.is_over() {
+            let next = agent.choose_action(self);
+            self.play(next)?;
+        }
+        Ok(self.is_over())
+    }
+}
+
+impl EventSource for AccordionGame {
+    type Event = SolitaireEvent;
+
+    /// Gets the events from before the first tick, which are only the record of the shuffle in
+    /// audit mode
+    fn events(&self) -> &[SolitaireEvent] {
+        &self.events
+    }
+}
+
+impl ErasedGame for AccordionGame {
+    /// Writes the view as `piles=[<top card>,...] legal=[<from>><onto>,...]`, with piles counted
+    /// from zero on the left
+    fn encode_view(view: &AccordionGame) -> String {
+        let tops: Vec<String> = view.tops().iter().map(ToString::to_string).collect();
+        let legal: Vec<String> = view
+            .legal_moves()
+            .iter()
+            .map(|next| format!("{}>{}", next.from, next.onto))
+            .collect();
+        format!("piles=[{}] legal=[{}]", tops.join(","), legal.join(","))
+    }
+
+    /// Reads a move as `<from>><onto>`, such as `3>0`
+    fn decode_action(action: &str) -> Option<AccordionMove> {
+        let (from, onto) = action.trim().split_once('>')?;
+        Some(AccordionMove {
+            from: from.trim().parse().ok()?,
+            onto: onto.trim().parse().ok()?,
+        })
+    }
+
+    /// Makes the first legal move, moving the leftmost pile that can move
+    fn fallback_action(view: &AccordionGame) -> AccordionMove {
+        view.legal_moves()
+            .first()
+            .copied()
+            .unwrap_or(AccordionMove { from: 1, onto: 0 })
+    }
+
+    fn outcome(&self) -> Option<String> {
+        if !self.is_over() {
+            return None;
+        }
+        Some(if self.is_won() {
+            "Won".to_string()
+        } else {
+            format!("Lost with {} piles left", self.piles.len())
+        })
+    }
+}
+
+impl GameOutcome for AccordionGame {
+    /// Gets the only seat once a single pile is left
+    fn winner(&self) -> Option<usize> {
+        self.is_won().then_some(0)
+    }
+}
+
+impl GameInfo for AccordionGame {
+    fn info() -> GameMetadata {
+        GameMetadata {
+            name: "Accordion",
+            summary: "Squeeze a row of cards into one pile by matching suits or ranks",
+            min_players: 1,
+            max_players: 1,
+            decks: 1,
+            minutes: 10,
+            tags: &[GameTag::Solitaire],
+        }
+    }
+}
@@ -0,0 +1,402 @@
+//! Deterministic daily deals, so every player can take on the same challenge each day
+//!
+//! Each date and variant maps to a fixed seed, and the seed to a shuffled deck. Each deal is
+//! solved, by the variant's solver or a check of the caller's own, and skipped unless a win is
+//! found; the next seed in sequence is then tried, so the daily deal stays the same for everyone.
+//! The solvers for the larger variants give up on some deals that can be won, so these are
+//! skipped too. With a [`DifficultyRater`], each deal is rated, and a deal of each difficulty
+//! can be offered.
+
+use std::fmt::{self, Display, Formatter};
+
+use rand::SeedableRng;
+use thiserror::Error;
+
+use crate::clock::{Clock, SystemClock};
+use crate::common::deck::Deck;
+use crate::games::GameRng;
+
+use super::{
+    difficulty::{Difficulty, DifficultyRater, DifficultyRating},
+    SolitaireVariant,
+};
+
+/// The offset basis of the 64-bit FNV-1a hash used to derive daily seeds
+const SEED_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// The prime of the 64-bit FNV-1a hash used to derive daily seeds
+const SEED_PRIME: u64 = 0x0100_0000_01b3;
+
+/// How many deals are tried for a date before giving up, unless configured otherwise
+const DEFAULT_MAX_ATTEMPTS: u32 = 1_000;
+/// The most positions the variant's solver looks at in each deal, unless configured otherwise
+const DEFAULT_SOLVER_POSITIONS: u64 = 100_000;
+
+/// A check of whether a shuffled deck can be won
+type WinnableCheck = Box<dyn Fn(&Deck) -> bool>;
+
+/// A calendar date in the proleptic Gregorian calendar
+/// # Examples
+/// ```
+/// # use naipe::games::solitaire::daily::DealDate;
+/// let date = DealDate::new(2024, 2, 29).unwrap();
+/// assert_eq!(date.to_string(), "2024-02-29");
+/// assert!(DealDate::new(2023, 2, 29).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DealDate {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl DealDate {
+    /// Creates a date from its year, month (counting from one), and day of the month
+    /// # Errors
+    /// [`DailyDealError::InvalidDate`] if the month or day does not exist
+    pub fn new(year: i32, month: u8, day: u8) -> Result<DealDate, DailyDealError> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(DailyDealError::InvalidDate { year, month, day });
+        }
+        Ok(DealDate { year, month, day })
+    }
+
+    /// Gets the current date in UTC, so players in every time zone share a deal
+    pub fn today() -> DealDate {
+        DealDate::today_on(&SystemClock)
+    }
+
+    /// Gets the date in UTC as read from the given clock
+    /// # Examples
+    /// ```
+    /// # use naipe::clock::MockClock;
+    /// # use naipe::games::solitaire::daily::DealDate;
+    /// use std::time::Duration;
+    /// let clock = MockClock::new(Duration::from_secs(19_782 * 86_400 + 86_399));
+    /// assert_eq!(DealDate::today_on(&clock), DealDate::new(2024, 2, 29).unwrap());
+    /// ```
+    pub fn today_on(clock: &impl Clock) -> DealDate {
+        let seconds = clock.now().as_secs();
+        DealDate::from_days_since_epoch(i64::try_from(seconds / 86_400).unwrap_or(i64::MAX))
+    }
+
+    /// Gets the date the given number of days after 1970-01-01
+    /// # Examples
+    /// ```
+    /// # use naipe::games::solitaire::daily::DealDate;
+    /// assert_eq!(DealDate::from_days_since_epoch(0), DealDate::new(1970, 1, 1).unwrap());
+    /// assert_eq!(DealDate::from_days_since_epoch(19_782), DealDate::new(2024, 2, 29).unwrap());
+    /// assert_eq!(DealDate::from_days_since_epoch(-1), DealDate::new(1969, 12, 31).unwrap());
+    /// ```
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn from_days_since_epoch(days: i64) -> DealDate {
+        // Counts from 0000-03-01, so the leap day falls at the end of each year
+        let days = days.saturating_add(719_468);
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        // The month and day are always in range, and the year is clamped to fit
+        DealDate {
+            year: year.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+
+    /// Gets the year
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Gets the month, counting from one
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Gets the day of the month, counting from one
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+impl Display for DealDate {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Finds if the year has a leap day
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Gets the number of days in the month of the year
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Gets the seed of the first deal tried for the variant on the date
+///
+/// The seed is a hash of the variant's name and the date, so it is the same on every platform
+/// and in every release
+/// # Examples
+/// ```
+/// # use naipe::games::solitaire::SolitaireVariant;
+/// # use naipe::games::solitaire::daily::{daily_seed, DealDate};
+/// let today = DealDate::new(2024, 6, 1).unwrap();
+/// let tomorrow = DealDate::new(2024, 6, 2).unwrap();
+/// assert_eq!(
+///     daily_seed(SolitaireVariant::Klondike, today),
+///     daily_seed(SolitaireVariant::Klondike, today)
+/// );
+/// assert_ne!(
+///     daily_seed(SolitaireVariant::Klondike, today),
+///     daily_seed(SolitaireVariant::Klondike, tomorrow)
+/// );
+/// assert_ne!(
+///     daily_seed(SolitaireVariant::Klondike, today),
+///     daily_seed(SolitaireVariant::FreeCell, today)
+/// );
+/// ```
+pub fn daily_seed(variant: SolitaireVariant, date: DealDate) -> u64 {
+    format!("{variant} {date}")
+        .bytes()
+        .fold(SEED_OFFSET, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(SEED_PRIME)
+        })
+}
+
+/// The deal chosen for a variant on a date
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DailyDeal {
+    pub variant: SolitaireVariant,
+    pub date: DealDate,
+    /// The seed the deck was shuffled from
+    pub seed: u64,
+    /// How many deals were tried before this one was accepted, including itself
+    pub attempts: u32,
+    /// The shuffled deck, with the first card to be dealt on top
+    pub deck: Deck,
+    /// The deal's difficulty, if the generator has a rater
+    pub rating: Option<DifficultyRating>,
+    /// Whether the deal passed a winnability check, by the variant's solver or a registered
+    /// check, rather than being offered unchecked
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub checked: bool,
+}
+
+/// Generates the same deal for every player of a variant on a given date
+/// # Examples
+/// ```
+/// # use naipe::games::solitaire::SolitaireVariant;
+/// # use naipe::games::solitaire::accordion::AccordionGame;
+/// # use naipe::games::solitaire::daily::{DailyDeals, DealDate};
+/// let date = DealDate::new(2024, 6, 1).unwrap();
+/// // Accordion deals are solved, so the daily deal can be won
+/// let deals = DailyDeals::new(SolitaireVariant::Accordion).solver_positions(10_000);
+/// let deal = deals.deal(date).unwrap();
+/// assert!(deal.checked);
+/// assert!(AccordionGame::deal(&deal.deck).solve(10_000).is_some());
+/// assert_eq!(deals.deal(date).unwrap(), deal);
+///
+/// // As are Klondike deals
+/// let deal = DailyDeals::new(SolitaireVariant::Klondike)
+///     .solver_positions(20_000)
+///     .deal(date)
+///     .unwrap();
+/// assert!(deal.checked);
+/// assert_eq!(deal.deck.len(), 52);
+/// ```
+pub struct DailyDeals {
+    variant: SolitaireVariant,
+    max_attempts: u32,
+    solver_positions: u64,
+    winnable: Option<WinnableCheck>,
+    rater: Option<DifficultyRater>,
+}
+
+impl DailyDeals {
+    /// Creates a generator for the variant, accepting only deals its solver can win
+    pub fn new(variant: SolitaireVariant) -> DailyDeals {
+        DailyDeals {
+            variant,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            solver_positions: DEFAULT_SOLVER_POSITIONS,
+            winnable: None,
+            rater: None,
+        }
+    }
+
+    /// Sets the most positions the variant's solver looks at in each deal, skipping the deal if
+    /// no win is found within them
+    ///
+    /// Changing the limit can change which deal is chosen for a date, so every player's generator
+    /// must use the same one
+    #[must_use]
+    pub fn solver_positions(mut self, solver_positions: u64) -> DailyDeals {
+        self.solver_positions = solver_positions;
+        self
+    }
+
+    /// Only accepts deals the check finds winnable, such as by running a solver on them, in place
+    /// of the variant's own solver
+    ///
+    /// Changing the check changes which deal is chosen for a date, so every player's generator
+    /// must use the same one
+    /// # Examples
+    /// ```
+    /// # use naipe::games::solitaire::SolitaireVariant;
+    /// # use naipe::games::solitaire::daily::{DailyDeals, DealDate};
+    /// let date = DealDate::new(2024, 6, 1).unwrap();
+    /// // A stand-in for a solver, rejecting about two in three deals
+    /// let deals = DailyDeals::new(SolitaireVariant::Klondike)
+    ///     .winnable(|deck| deck.fingerprint().0 % 3 == 0);
+    /// let deal = deals.deal(date).unwrap();
+    /// assert!(deal.checked);
+    /// assert_eq!(deal.deck.fingerprint().0 % 3, 0);
+    /// ```
+    #[must_use]
+    pub fn winnable<F: Fn(&Deck) -> bool + 'static>(mut self, winnable: F) -> DailyDeals {
+        self.winnable = Some(Box::new(winnable));
+        self
+    }
+
+    /// Rates the difficulty of each deal
+    ///
+    /// A rater calibrated on a sample of the variant's deals, with
+    /// [`DifficultyRater::calibrate`], finds a deal of each difficulty about as quickly as the
+    /// others
+    #[must_use]
+    pub fn rater(mut self, rater: DifficultyRater) -> DailyDeals {
+        self.rater = Some(rater);
+        self
+    }
+
+    /// Sets how many deals are tried for a date before giving up
+    #[must_use]
+    pub fn max_attempts(mut self, max_attempts: u32) -> DailyDeals {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Gets the variant deals are generated for
+    pub fn variant(&self) -> SolitaireVariant {
+        self.variant
+    }
+
+    /// Gets the deal for the date, the first in its seed sequence found winnable
+    /// # Errors
+    /// [`DailyDealError::NoWinnableDeal`] if none of the deals tried were found winnable
+    pub fn deal(&self, date: DealDate) -> Result<DailyDeal, DailyDealError> {
+        self.find(date, |_| true)
+            .ok_or(DailyDealError::NoWinnableDeal(self.max_attempts))
+    }
+
+    /// Gets the deal of the given difficulty for the date, the first in its seed sequence
+    /// found winnable and rated at that difficulty
+    ///
+    /// Without a rater, every deal is rated [`Difficulty::Medium`]
+    /// # Errors
+    /// [`DailyDealError::NoDealOfDifficulty`] if none of the deals tried were winnable and of
+    /// the difficulty
+    /// # Examples
+    /// ```
+    /// # use naipe::games::solitaire::SolitaireVariant;
+    /// # use naipe::games::solitaire::daily::{DailyDeals, DealDate};
+    /// # use naipe::games::solitaire::difficulty::{Difficulty, DifficultyRater};
+    /// // A stand-in for a solver, whose effort varies from deal to deal
+    /// let rater = DifficultyRater::new().solver(|deck| Some(deck.fingerprint().0 % 1_000), 250);
+    /// let deals = DailyDeals::new(SolitaireVariant::Golf).rater(rater);
+    /// let date = DealDate::new(2024, 6, 1).unwrap();
+    /// let easy = deals.deal_of(date, Difficulty::Easy).unwrap();
+    /// let hard = deals.deal_of(date, Difficulty::Hard).unwrap();
+    /// assert_eq!(easy.rating.unwrap().difficulty, Difficulty::Easy);
+    /// assert_eq!(hard.rating.unwrap().difficulty, Difficulty::Hard);
+    /// assert_ne!(easy.seed, hard.seed);
+    /// ```
+    pub fn deal_of(
+        &self,
+        date: DealDate,
+        difficulty: Difficulty,
+    ) -> Result<DailyDeal, DailyDealError> {
+        let unrated = DifficultyRater::new();
+        let rater = self.rater.as_ref().unwrap_or(&unrated);
+        self.find(date, |deal| {
+            deal.rating
+                .as_ref()
+                .map_or_else(|| rater.rate(&deal.deck, deal.seed), Clone::clone)
+                .difficulty
+                == difficulty
+        })
+        .ok_or(DailyDealError::NoDealOfDifficulty {
+            difficulty,
+            attempts: self.max_attempts,
+        })
+    }
+
+    /// Finds the first winnable deal for the date also accepted by the given check
+    fn find<F: Fn(&DailyDeal) -> bool>(&self, date: DealDate, accept: F) -> Option<DailyDeal> {
+        let first_seed = daily_seed(self.variant, date);
+        (0..self.max_attempts).find_map(|attempt| {
+            let seed = first_seed.wrapping_add(u64::from(attempt));
+            let mut deck = self.variant.deck();
+            deck.shuffle(&mut GameRng::seed_from_u64(seed));
+            let winnable = match &self.winnable {
+                Some(winnable) => Some(winnable(&deck)),
+                None => self.variant.solve(&deck, self.solver_positions),
+            };
+            if winnable == Some(false) {
+                return None;
+            }
+            let rating = self.rater.as_ref().map(|rater| rater.rate(&deck, seed));
+            let deal = DailyDeal {
+                variant: self.variant,
+                date,
+                seed,
+                attempts: attempt + 1,
+                deck,
+                rating,
+                checked: winnable.is_some(),
+            };
+            accept(&deal).then_some(deal)
+        })
+    }
+
+    /// Gets the deal for the current date in UTC
+    /// # Errors
+    /// As for [`DailyDeals::deal`]
+    pub fn today(&self) -> Result<DailyDeal, DailyDealError> {
+        self.deal(DealDate::today())
+    }
+}
+
+/// Errors related to generating daily deals
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DailyDealError {
+    #[error("{year:04}-{month:02}-{day:02} is not a valid date")]
+    InvalidDate { year: i32, month: u8, day: u8 },
+    #[error("None of the {0} deals tried were winnable")]
+    NoWinnableDeal(u32),
+    #[error("None of the {attempts} deals tried were winnable and {difficulty}")]
+    NoDealOfDifficulty {
+        difficulty: Difficulty,
+        attempts: u32,
+    },
+}
@@ -0,0 +1,310 @@
+//! Difficulty ratings for solitaire deals
+//!
+//! A deal is rated by how hard a solver works to win it, and how often bots playing with
+//! different policies win it. Both measures are scaled to a score between zero (easiest) and
+//! one (hardest), which is then banded into a [`Difficulty`]. The bands can be calibrated from a
+//! sample of deals, so that each difficulty is offered about as often as the others.
+//!
+//! Scores are always computed and banded as exact fractions, so a deal falls in the same band on
+//! every platform, whichever features are enabled. The `exact-math` feature exposes the exact
+//! scores as `Ratio`s.
+
+use std::fmt::{self, Display, Formatter};
+
+use rand::SeedableRng;
+
+use crate::common::deck::Deck;
+use crate::common::ratio::Ratio;
+use crate::games::GameRng;
+
+/// How many games each bot plays of a deal, unless configured otherwise
+const DEFAULT_TRIALS: u32 = 10;
+
+/// Solves a deal, returning the effort taken (such as positions searched), or `None` if it
+/// could not be solved
+type Solver = Box<dyn Fn(&Deck) -> Option<u64>>;
+/// Plays a deal with a bot, returning whether it won
+type BotPolicy = (String, Box<dyn Fn(&Deck, &mut GameRng) -> bool>);
+
+/// How hard a deal is to win
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Medium => write!(f, "Medium"),
+            Difficulty::Hard => write!(f, "Hard"),
+        }
+    }
+}
+
+/// The measured difficulty of a deal
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DifficultyRating {
+    /// The combined score, from zero (easiest) to one (hardest)
+    pub score: f64,
+    pub difficulty: Difficulty,
+    /// The fraction of games won by the bots, across every policy
+    pub win_rate: Option<f64>,
+    /// The effort the solver took, or `None` if there is no solver or it failed
+    pub solver_effort: Option<u64>,
+    /// The combined score, computed exactly
+    #[cfg(feature = "exact-math")]
+    pub exact_score: Ratio,
+}
+
+/// Rates deals by solver effort and bot win rate
+///
+/// Each configured measure is scaled to a score between zero and one, and the deal's score is
+/// their average: the bots' loss rate, and the solver's effort relative to its typical effort
+/// (scoring one half at the typical effort, and one if the solver fails). With nothing
+/// configured, every deal scores one half.
+///
+/// Deals scoring below a third are easy and those from two thirds are hard, unless the
+/// thresholds are set or calibrated
+/// # Examples
+/// ```
+/// # use naipe::games::solitaire::difficulty::{Difficulty, DifficultyRater};
+/// # use naipe::common::deck::Deck;
+/// use rand::Rng;
+/// let rater = DifficultyRater::new()
+///     .policy("never wins", |_, _| false)
+///     .policy("coin flip", |_, rng| rng.gen_bool(0.5))
+///     .trials(100);
+/// let rating = rater.rate(&Deck::default(), 7);
+/// assert!(rating.win_rate.unwrap() < 0.5);
+/// assert_eq!(rating.difficulty, Difficulty::Hard);
+/// assert_eq!(rater.rate(&Deck::default(), 7), rating);
+/// ```
+pub struct DifficultyRater {
+    solver: Option<(Solver, u64)>,
+    policies: Vec<BotPolicy>,
+    trials: u32,
+    /// The score below which deals are easy, and the score from which they are hard
+    thresholds: (Ratio, Ratio),
+}
+
+impl DifficultyRater {
+    /// Creates a rater with no solver or bots
+    pub fn new() -> DifficultyRater {
+        DifficultyRater {
+            solver: None,
+            policies: vec![],
+            trials: DEFAULT_TRIALS,
+            thresholds: (
+                Ratio::new(1, 3).unwrap_or(Ratio::ZERO),
+                Ratio::new(2, 3).unwrap_or(Ratio::ONE),
+            ),
+        }
+    }
+
+    /// Rates deals by the effort the solver takes, given the effort of a typical deal
+    #[must_use]
+    pub fn solver<F: Fn(&Deck) -> Option<u64> + 'static>(
+        mut self,
+        solver: F,
+        typical_effort: u64,
+    ) -> DifficultyRater {
+        self.solver = Some((Box::new(solver), typical_effort));
+        self
+    }
+
+    /// Rates deals by how often a bot playing with the named policy wins them
+    #[must_use]
+    pub fn policy<F: Fn(&Deck, &mut GameRng) -> bool + 'static>(
+        mut self,
+        name: &str,
+        policy: F,
+    ) -> DifficultyRater {
+        self.policies.push((name.to_string(), Box::new(policy)));
+        self
+    }
+
+    /// Sets how many games each bot plays of every deal
+    #[must_use]
+    pub fn trials(mut self, trials: u32) -> DifficultyRater {
+        self.trials = trials;
+        self
+    }
+
+    /// Sets the score below which deals are easy, and the score from which they are hard
+    ///
+    /// The thresholds are approximated as exact fractions, to the nearest 2<sup>-32</sup>, and
+    /// deals are banded by those
+    #[must_use]
+    pub fn thresholds(mut self, easy_below: f64, hard_from: f64) -> DifficultyRater {
+        self.thresholds = (
+            Ratio::approximate(easy_below),
+            Ratio::approximate(hard_from),
+        );
+        self
+    }
+
+    /// Sets the exact score below which deals are easy, and the score from which they are hard
+    #[cfg(feature = "exact-math")]
+    #[must_use]
+    pub fn exact_thresholds(mut self, easy_below: Ratio, hard_from: Ratio) -> DifficultyRater {
+        self.thresholds = (easy_below, hard_from);
+        self
+    }
+
+    /// Sets the thresholds from how the rater scores a sample of deals, so that about a third of
+    /// them are rated at each difficulty
+    ///
+    /// Each deal is scored as by [`DifficultyRater::rate`], with the bots seeded from the given
+    /// seed plus the deal's place in the sample. Deals scoring the same are always rated the
+    /// same, so a sample with many equal scores is split less evenly. An empty sample leaves the
+    /// thresholds as they were.
+    /// # Examples
+    /// ```
+    /// # use naipe::games::solitaire::difficulty::{Difficulty, DifficultyRater};
+    /// # use naipe::games::solitaire::SolitaireVariant;
+    /// # use naipe::games::GameRng;
+    /// use rand::SeedableRng;
+    /// let sample: Vec<_> = (0..30)
+    ///     .map(|seed| {
+    ///         let mut deck = SolitaireVariant::Golf.deck();
+    ///         deck.shuffle(&mut GameRng::seed_from_u64(seed));
+    ///         deck
+    ///     })
+    ///     .collect();
+    /// // A stand-in for a solver, whose effort varies from deal to deal
+    /// let rater = DifficultyRater::new()
+    ///     .solver(|deck| Some(deck.fingerprint().0 % 1_000), 50)
+    ///     .calibrate(sample.clone(), 1);
+    /// let easy = sample
+    ///     .iter()
+    ///     .filter(|deck| rater.rate(deck, 0).difficulty == Difficulty::Easy)
+    ///     .count();
+    /// assert_eq!(easy, 10);
+    /// ```
+    #[must_use]
+    pub fn calibrate<I: IntoIterator<Item = Deck>>(
+        mut self,
+        sample: I,
+        seed: u64,
+    ) -> DifficultyRater {
+        let mut scores: Vec<Ratio> = sample
+            .into_iter()
+            .zip(seed..)
+            .map(|(deck, seed)| self.measure(&deck, seed).score)
+            .collect();
+        scores.sort_unstable();
+        let count = scores.len();
+        if count > 0 {
+            self.thresholds = (scores[count / 3], scores[count * 2 / 3]);
+        }
+        self
+    }
+
+    /// Gets the names of the bot policies, in the order added
+    pub fn policies(&self) -> impl Iterator<Item = &str> {
+        self.policies.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Gets the difficulty band of a score, approximated as an exact fraction as for
+    /// [`DifficultyRater::thresholds`]
+    pub fn difficulty(&self, score: f64) -> Difficulty {
+        self.band(Ratio::approximate(score))
+    }
+
+    /// Gets the difficulty band of an exact score
+    /// # Examples
+    /// ```
+    /// # use naipe::common::ratio::Ratio;
+    /// # use naipe::games::solitaire::difficulty::{Difficulty, DifficultyRater};
+    /// let rater = DifficultyRater::new().thresholds(0.25, 0.75);
+    /// assert_eq!(rater.exact_difficulty(Ratio::new(1, 4).unwrap()), Difficulty::Medium);
+    /// assert_eq!(rater.exact_difficulty(Ratio::new(3, 4).unwrap()), Difficulty::Hard);
+    /// ```
+    #[cfg(feature = "exact-math")]
+    pub fn exact_difficulty(&self, score: Ratio) -> Difficulty {
+        self.band(score)
+    }
+
+    fn band(&self, score: Ratio) -> Difficulty {
+        let (easy_below, hard_from) = self.thresholds;
+        if score < easy_below {
+            Difficulty::Easy
+        } else if score < hard_from {
+            Difficulty::Medium
+        } else {
+            Difficulty::Hard
+        }
+    }
+
+    /// Rates a deal, seeding the bots from the given seed so the rating is repeatable
+    pub fn rate(&self, deck: &Deck, seed: u64) -> DifficultyRating {
+        let measures = self.measure(deck, seed);
+        #[allow(clippy::cast_precision_loss)]
+        let win_rate = measures
+            .wins
+            .map(|wins| wins as f64 / measures.games as f64);
+        DifficultyRating {
+            score: measures.score.to_f64(),
+            difficulty: self.band(measures.score),
+            win_rate,
+            solver_effort: measures.solver_effort,
+            #[cfg(feature = "exact-math")]
+            exact_score: measures.score,
+        }
+    }
+
+    /// Runs the solver and bots on a deal, combining their measures into an exact score
+    fn measure(&self, deck: &Deck, seed: u64) -> Measures {
+        let mut scores = vec![];
+
+        let solver_effort = self.solver.as_ref().and_then(|(solver, typical_effort)| {
+            let effort = solver(deck);
+            scores.push(effort.map_or(Ratio::ONE, |effort| {
+                let total = effort.saturating_add(*typical_effort);
+                Ratio::new(effort, total).unwrap_or(Ratio::HALF)
+            }));
+            effort
+        });
+
+        let mut rng = GameRng::seed_from_u64(seed);
+        let games = self.policies.len() as u64 * u64::from(self.trials);
+        let wins = (games > 0).then(|| {
+            self.policies
+                .iter()
+                .map(|(_, policy)| {
+                    (0..self.trials).filter(|_| policy(deck, &mut rng)).count() as u64
+                })
+                .sum::<u64>()
+        });
+        if let Some(wins) = wins {
+            scores.extend(Ratio::new(games - wins, games));
+        }
+
+        Measures {
+            score: Ratio::mean(&scores).unwrap_or(Ratio::HALF),
+            solver_effort,
+            wins,
+            games,
+        }
+    }
+}
+
+/// What the solver and bots found of a deal
+struct Measures {
+    score: Ratio,
+    solver_effort: Option<u64>,
+    wins: Option<u64>,
+    games: u64,
+}
+
+impl Default for DifficultyRater {
+    fn default() -> DifficultyRater {
+        DifficultyRater::new()
+    }
+}
@@ -0,0 +1,356 @@
+//! Freecell, with a solver for its deals
+//!
+//! Every card is dealt face up across eight columns. Cards build down in the columns in
+//! alternating colors, and any card may fill an empty column. Four free cells each hold a single
+//! card, to park cards out of the way. Each suit builds up from its ace on a foundation, and
+//! cards are never taken back off. The game is won once every card is on the foundations.
+//!
+//! Only one card moves at a time, but a run of cards may move together if there are enough free
+//! cells and empty columns to move it one card at a time.
+
+use super::{
+    builds_down_alternating, builds_on_foundation, is_safe_on_foundations, search_depth_first,
+    suit_index, SolitaireError, SolitaireVariant, Solution,
+};
+use crate::common::{card::Card, deck::Deck};
+
+/// The number of columns dealt
+const FREECELL_COLUMNS: usize = 8;
+/// The number of free cells
+const FREE_CELLS: usize = 4;
+
+/// A move in Freecell
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum FreeCellMove {
+    /// Plays the exposed card of the column, counting from zero, onto its foundation
+    ColumnToFoundation(usize),
+    /// Plays the card in the free cell, counting from zero, onto its foundation
+    CellToFoundation(usize),
+    /// Moves the exposed card of the column into an empty free cell
+    ColumnToCell(usize),
+    /// Moves the card in the free cell onto the column
+    CellToColumn { cell: usize, to: usize },
+    /// Moves the given number of cards, from the exposed card back, onto another column
+    ColumnToColumn {
+        from: usize,
+        count: usize,
+        to: usize,
+    },
+}
+
+/// A game of Freecell
+/// # Examples
+/// ```
+/// # use naipe::common::deck::Deck;
+/// # use naipe::games::solitaire::freecell::{FreeCellGame, FreeCellMove};
+/// let mut game = FreeCellGame::deal(&Deck::default()).unwrap();
+/// assert_eq!(game.columns()[0].len(), 7);
+/// assert_eq!(game.columns()[7].len(), 6);
+/// game.play(FreeCellMove::ColumnToCell(0)).unwrap();
+/// assert_eq!(game.cells().iter().flatten().count(), 1);
+/// assert!(FreeCellGame::deal(&Deck::new_empty()).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FreeCellGame {
+    columns: Vec<Vec<Card>>,
+    cells: [Option<Card>; FREE_CELLS],
+    /// The number of cards on each suit's foundation
+    foundations: [usize; 4],
+}
+
+/// The parts of a Freecell position that decide what can happen next, with the columns and
+/// cells in order so that positions differing only by which is which are seen once
+type FreeCellPosition = (Vec<Vec<Card>>, Vec<Card>, [usize; 4]);
+
+impl FreeCellGame {
+    /// Lays out a game from a deck, dealing across the columns from the top
+    /// # Errors
+    /// [`SolitaireError::WrongDeckSize`] unless the deck is a standard 52 cards
+    pub fn deal(deck: &Deck) -> Result<FreeCellGame, SolitaireError> {
+        let expected = SolitaireVariant::FreeCell.deck().len();
+        if deck.len() != expected {
+            return Err(SolitaireError::WrongDeckSize {
+                expected,
+                actual: deck.len(),
+            });
+        }
+        let mut deck = deck.clone();
+        let mut columns = vec![vec![]; FREECELL_COLUMNS];
+        for (index, card) in std::iter::from_fn(|| deck.draw()).enumerate() {
+            columns[index % FREECELL_COLUMNS].push(card);
+        }
+        Ok(FreeCellGame {
+            columns,
+            cells: [None; FREE_CELLS],
+            foundations: [0; 4],
+        })
+    }
+
+    /// Gets each column, with its exposed card last
+    pub fn columns(&self) -> &[Vec<Card>] {
+        &self.columns
+    }
+
+    /// Gets the card in each free cell
+    pub fn cells(&self) -> &[Option<Card>] {
+        &self.cells
+    }
+
+    /// Gets the number of cards on each foundation, in the order spades, clubs, hearts, diamonds
+    pub fn foundations(&self) -> [usize; 4] {
+        self.foundations
+    }
+
+    /// Gets the most cards that may move together onto the column, one at a time through the
+    /// free cells and the other empty columns
+    fn max_run(&self, to: usize) -> usize {
+        let cells = self.cells.iter().filter(|cell| cell.is_none()).count();
+        let columns = self
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(index, column)| *index != to && column.is_empty())
+            .count();
+        (cells + 1) << columns
+    }
+
+    /// Finds if the card may be played onto the column
+    fn fits(&self, card: Card, column: usize) -> bool {
+        self.columns[column]
+            .last()
+            .is_none_or(|onto| builds_down_alternating(card, *onto))
+    }
+
+    /// Finds if the card may be played onto its foundation
+    fn fits_foundation(&self, card: Card) -> bool {
+        builds_on_foundation(card, self.foundations[suit_index(card.get_suit())])
+    }
+
+    /// Gets the number of cards at the end of the column built down in alternating colors
+    fn run_len(column: &[Card]) -> usize {
+        let built = column
+            .windows(2)
+            .rev()
+            .take_while(|pair| builds_down_alternating(pair[1], pair[0]))
+            .count();
+        if column.is_empty() {
+            0
+        } else {
+            built + 1
+        }
+    }
+
+    /// Gets every move allowed now, playing to the foundations first
+    pub fn legal_moves(&self) -> Vec<FreeCellMove> {
+        let mut moves = vec![];
+        for (from, column) in self.columns.iter().enumerate() {
+            if column
+                .last()
+                .is_some_and(|card| self.fits_foundation(*card))
+            {
+                moves.push(FreeCellMove::ColumnToFoundation(from));
+            }
+        }
+        for (cell, card) in self.cells.iter().enumerate() {
+            if card.is_some_and(|card| self.fits_foundation(card)) {
+                moves.push(FreeCellMove::CellToFoundation(cell));
+            }
+        }
+        for (from, column) in self.columns.iter().enumerate() {
+            let run = FreeCellGame::run_len(column);
+            for to in (0..FREECELL_COLUMNS).filter(|to| *to != from) {
+                let most = run.min(self.max_run(to));
+                moves.extend(
+                    (1..=most)
+                        .filter(|count| self.fits(column[column.len() - count], to))
+                        .map(|count| FreeCellMove::ColumnToColumn { from, count, to }),
+                );
+            }
+        }
+        for (cell, card) in self.cells.iter().enumerate() {
+            if let Some(card) = card {
+                moves.extend(
+                    (0..FREECELL_COLUMNS)
+                        .filter(|to| self.fits(*card, *to))
+                        .map(|to| FreeCellMove::CellToColumn { cell, to }),
+                );
+            }
+        }
+        if self.cells.contains(&None) {
+            moves.extend(
+                (0..FREECELL_COLUMNS)
+                    .filter(|from| !self.columns[*from].is_empty())
+                    .map(FreeCellMove::ColumnToCell),
+            );
+        }
+        moves
+    }
+
+    /// Makes a move
+    /// # Errors
+    /// [`SolitaireError::IllegalMove`] if the move is not allowed now
+    pub fn play(&mut self, next: FreeCellMove) -> Result<(), SolitaireError> {
+        if !self.allows(next) {
+            return Err(SolitaireError::IllegalMove);
+        }
+        match next {
+            FreeCellMove::ColumnToFoundation(from) => {
+                if let Some(card) = self.columns[from].pop() {
+                    self.foundations[suit_index(card.get_suit())] += 1;
+                }
+            }
+            FreeCellMove::CellToFoundation(cell) => {
+                if let Some(card) = self.cells[cell].take() {
+                    self.foundations[suit_index(card.get_suit())] += 1;
+                }
+            }
+            FreeCellMove::ColumnToCell(from) => {
+                let card = self.columns[from].pop();
+                if let Some(cell) = self.cells.iter_mut().find(|cell| cell.is_none()) {
+                    *cell = card;
+                }
+            }
+            FreeCellMove::CellToColumn { cell, to } => {
+                self.columns[to].extend(self.cells[cell].take());
+            }
+            FreeCellMove::ColumnToColumn { from, count, to } => {
+                let at = self.columns[from].len() - count;
+                let cards = self.columns[from].split_off(at);
+                self.columns[to].extend(cards);
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds if the move is allowed now
+    fn allows(&self, next: FreeCellMove) -> bool {
+        let exposed = |column: usize| self.columns.get(column).and_then(|column| column.last());
+        match next {
+            FreeCellMove::ColumnToFoundation(from) => {
+                exposed(from).is_some_and(|card| self.fits_foundation(*card))
+            }
+            FreeCellMove::CellToFoundation(cell) => self
+                .cells
+                .get(cell)
+                .copied()
+                .flatten()
+                .is_some_and(|card| self.fits_foundation(card)),
+            FreeCellMove::ColumnToCell(from) => {
+                exposed(from).is_some() && self.cells.contains(&None)
+            }
+            FreeCellMove::CellToColumn { cell, to } => {
+                to < FREECELL_COLUMNS
+                    && self
+                        .cells
+                        .get(cell)
+                        .copied()
+                        .flatten()
+                        .is_some_and(|card| self.fits(card, to))
+            }
+            FreeCellMove::ColumnToColumn { from, count, to } => {
+                from != to
+                    && to < FREECELL_COLUMNS
+                    && self.columns.get(from).is_some_and(|column| {
+                        (1..=FreeCellGame::run_len(column).min(self.max_run(to))).contains(&count)
+                            && self.fits(column[column.len() - count], to)
+                    })
+            }
+        }
+    }
+
+    /// Finds if every card is on the foundations
+    pub fn is_won(&self) -> bool {
+        self.foundations.iter().sum::<usize>() == SolitaireVariant::FreeCell.deck().len()
+    }
+
+    /// Finds if the game is won, or no move is left
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.legal_moves().is_empty()
+    }
+
+    /// Searches for a way to win from this position, looking at no more than the given number of
+    /// positions
+    ///
+    /// Gives `None` if no win was found within the limit. The search skips moves that cannot
+    /// help, such as moving a whole column into an empty one, so it may miss the rare deal won
+    /// only by a line it skips
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::Deck;
+    /// # use naipe::games::solitaire::freecell::FreeCellGame;
+    /// use naipe::games::GameRng;
+    /// use rand::SeedableRng;
+    /// let mut deck = Deck::default();
+    /// deck.shuffle(&mut GameRng::seed_from_u64(1));
+    /// let game = FreeCellGame::deal(&deck).unwrap();
+    /// let solution = game.solve(100_000).unwrap();
+    /// let mut game = game.clone();
+    /// for next in solution.moves {
+    ///     game.play(next).unwrap();
+    /// }
+    /// assert!(game.is_won());
+    /// ```
+    pub fn solve(&self, max_positions: u64) -> Option<Solution<FreeCellMove>> {
+        search_depth_first(
+            self,
+            max_positions,
+            FreeCellGame::is_won,
+            FreeCellGame::position,
+            FreeCellGame::useful_moves,
+            |game, next| game.play(next).is_ok(),
+        )
+    }
+
+    /// Gets the moves worth searching, best first: only a card safe to play to its foundation if
+    /// there is one, and otherwise every move that could help
+    fn useful_moves(&self) -> Vec<FreeCellMove> {
+        let moves = self.legal_moves();
+        let safe = moves.iter().copied().find(|next| match *next {
+            FreeCellMove::ColumnToFoundation(from) => self.columns[from]
+                .last()
+                .is_some_and(|card| is_safe_on_foundations(*card, &self.foundations)),
+            FreeCellMove::CellToFoundation(cell) => {
+                self.cells[cell].is_some_and(|card| is_safe_on_foundations(card, &self.foundations))
+            }
+            _ => false,
+        });
+        if let Some(safe) = safe {
+            return vec![safe];
+        }
+        let mut useful: Vec<FreeCellMove> = moves
+            .into_iter()
+            .filter(|next| match *next {
+                // Moving a whole column into an empty one changes nothing
+                FreeCellMove::ColumnToColumn { from, count, to } => {
+                    !(self.columns[to].is_empty() && count == self.columns[from].len())
+                }
+                // Parking the last card of a column only empties it, as moving it would
+                FreeCellMove::ColumnToCell(from) => self.columns[from].len() > 1,
+                _ => true,
+            })
+            .collect();
+        // Build onto cards before filling empty columns, and park cards in free cells last
+        useful.sort_by_key(|next| match *next {
+            FreeCellMove::ColumnToFoundation(_) | FreeCellMove::CellToFoundation(_) => (0, 0),
+            FreeCellMove::CellToColumn { to, .. } if !self.columns[to].is_empty() => (1, 0),
+            FreeCellMove::ColumnToColumn { count, to, .. } if !self.columns[to].is_empty() => {
+                (2, usize::MAX - count)
+            }
+            FreeCellMove::ColumnToColumn { count, .. } => (3, count),
+            FreeCellMove::CellToColumn { .. } => (4, 0),
+            FreeCellMove::ColumnToCell(from) => (5, self.columns[from].len()),
+        });
+        useful
+    }
+
+    fn position(&self) -> FreeCellPosition {
+        let mut columns = self.columns.clone();
+        columns.sort_unstable();
+        let mut cells: Vec<Card> = self.cells.iter().flatten().copied().collect();
+        cells.sort_unstable();
+        (columns, cells, self.foundations)
+    }
+}
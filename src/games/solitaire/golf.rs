@@ -0,0 +1,437 @@
+//! Golf solitaire, clearing seven columns onto a single run of cards
+//!
+//! Thirty-five cards are dealt face up into seven columns of five, and one card from the stock
+//! starts the foundation. The bottom card of any column may be played onto the foundation if it
+//! is one rank above or below the foundation's top card, with aces low and nothing played on a
+//! king. When no card can be played, the next stock card is turned onto the foundation. The
+//! game is won once every column is cleared.
+
+use std::collections::HashSet;
+
+use rand::Rng;
+
+use super::{shuffled_deck, SolitaireError, SolitaireEvent, SolitaireVariant, Solution};
+use crate::common::{
+    card::{Card, Rank},
+    deck::Deck,
+    order::RankOrder,
+};
+use crate::games::{
+    agent::{Agent, AgentGame},
+    audit::AuditedGame,
+    dynamic::ErasedGame,
+    event::EventSource,
+    info::{GameInfo, GameMetadata, GameTag},
+    Claim, ClaimError, GameOutcome, SeededGame,
+};
+
+/// The number of columns dealt
+const GOLF_COLUMNS: usize = 7;
+/// The number of cards dealt to each column
+const GOLF_COLUMN_DEPTH: usize = 5;
+/// The most positions searched to check a claim, beyond which it is unproven
+const CLAIM_POSITIONS: u64 = 1_000_000;
+
+/// A move in Golf
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum GolfMove {
+    /// Plays the exposed card of the column, counting from zero, onto the foundation
+    Play(usize),
+    /// Turns the next stock card onto the foundation
+    Draw,
+}
+
+/// A game of Golf solitaire
+///
+/// Golf is played alone with every card face up, so the single agent sees the whole game
+/// # Examples
+/// ```
+/// # use naipe::games::solitaire::golf::{GolfGame, GolfMove};
+/// # use naipe::games::{GameOutcome, SeededGame};
+/// let mut game = GolfGame::from_seed(12);
+/// assert_eq!(game.columns().len(), 7);
+/// assert_eq!(game.stock_len(), 16);
+///
+/// // Play any card that fits, and draw otherwise
+/// while !game.is_over() {
+///     let next = game.legal_moves()[0];
+///     game.play(next).unwrap();
+/// }
+/// assert_eq!(game.winner().is_some(), game.is_won());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GolfGame {
+    columns: Vec<Vec<Card>>,
+    stock: Vec<Card>,
+    foundation: Vec<Card>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<SolitaireEvent>,
+}
+
+/// The parts of a Golf position that decide what can happen next
+type GolfPosition = (Vec<usize>, usize, Option<Card>);
+
+impl GolfGame {
+    /// Lays out a game from a deck, dealing from the top
+    /// # Errors
+    /// [`SolitaireError::WrongDeckSize`] unless the deck is a standard 52 cards
+    pub fn deal(deck: &Deck) -> Result<GolfGame, SolitaireError> {
+        let expected = SolitaireVariant::Golf.deck().len();
+        if deck.len() != expected {
+            return Err(SolitaireError::WrongDeckSize {
+                expected,
+                actual: deck.len(),
+            });
+        }
+        let mut deck = deck.clone();
+        let mut columns = vec![vec![]; GOLF_COLUMNS];
+        for _ in 0..GOLF_COLUMN_DEPTH {
+            for column in &mut columns {
+                column.extend(deck.draw());
+            }
+        }
+        let mut stock: Vec<Card> = std::iter::from_fn(|| deck.draw()).collect();
+        stock.reverse();
+        let foundation = stock.pop().into_iter().collect();
+        Ok(GolfGame {
+            columns,
+            stock,
+            foundation,
+            events: vec![],
+        })
+    }
+
+    /// Gets each column, with its exposed card last
+    pub fn columns(&self) -> &[Vec<Card>] {
+        &self.columns
+    }
+
+    /// Gets the card on top of the foundation
+    pub fn foundation_top(&self) -> Option<Card> {
+        self.foundation.last().copied()
+    }
+
+    /// Gets the number of cards left in the stock
+    pub fn stock_len(&self) -> usize {
+        self.stock.len()
+    }
+
+    /// Finds if the card may be played onto the foundation
+    pub fn fits(&self, card: Card) -> bool {
+        let Some(top) = self.foundation_top() else {
+            return true;
+        };
+        let order = RankOrder::ace_low();
+        top.get_rank() != Rank::King
+            && (order.is_directly_after(card.get_rank(), top.get_rank())
+                || order.is_directly_after(top.get_rank(), card.get_rank()))
+    }
+
+    /// Gets every move allowed now, playing from columns left to right before drawing
+    pub fn legal_moves(&self) -> Vec<GolfMove> {
+        let mut moves: Vec<GolfMove> = self
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| column.last().is_some_and(|card| self.fits(*card)))
+            .map(|(column, _)| GolfMove::Play(column))
+            .collect();
+        if !self.stock.is_empty() {
+            moves.push(GolfMove::Draw);
+        }
+        moves
+    }
+
+    /// Makes a move
+    /// # Errors
+    /// [`SolitaireError::IllegalMove`] if the move is not allowed now
+    pub fn play(&mut self, next: GolfMove) -> Result<(), SolitaireError> {
+        let card = match next {
+            GolfMove::Play(column) => {
+                let card = self
+                    .columns
+                    .get(column)
+                    .and_then(|column| column.last().copied())
+                    .filter(|card| self.fits(*card))
+                    .ok_or(SolitaireError::IllegalMove)?;
+                self.columns[column].pop();
+                card
+            }
+            GolfMove::Draw => self.stock.pop().ok_or(SolitaireError::IllegalMove)?,
+        };
+        self.foundation.push(card);
+        Ok(())
+    }
+
+    /// Finds if every column has been cleared
+    pub fn is_won(&self) -> bool {
+        self.columns.iter().all(Vec::is_empty)
+    }
+
+    /// Finds if the game is won, or no move is left
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.legal_moves().is_empty()
+    }
+
+    /// Searches for a way to win from this position, looking at no more than the given number of
+    /// positions
+    ///
+    /// Gives `None` if the game cannot be won, or if no win was found within the limit
+    /// # Examples
+    /// ```
+    /// # use naipe::games::solitaire::golf::GolfGame;
+    /// # use naipe::games::SeededGame;
+    /// let game = GolfGame::from_seed(3);
+    /// if let Some(solution) = game.solve(100_000) {
+    ///     let mut game = game.clone();
+    ///     for next in solution.moves {
+    ///         game.play(next).unwrap();
+    ///     }
+    ///     assert!(game.is_won());
+    /// }
+    /// ```
+    pub fn solve(&self, max_positions: u64) -> Option<Solution<GolfMove>> {
+        let mut seen = HashSet::new();
+        let mut moves = vec![];
+        let mut positions = 0;
+        // Searching copies the game at every position, so leaves the deal's record behind
+        let start = GolfGame {
+            events: vec![],
+            ..self.clone()
+        };
+        start
+            .search(&mut seen, &mut moves, &mut positions, max_positions)
+            .then_some(Solution { moves, positions })
+    }
+
+    /// Searches depth first for a win, skipping positions already seen
+    fn search(
+        &self,
+        seen: &mut HashSet<GolfPosition>,
+        moves: &mut Vec<GolfMove>,
+        positions: &mut u64,
+        max_positions: u64,
+    ) -> bool {
+        if self.is_won() {
+            return true;
+        }
+        if *positions >= max_positions || !seen.insert(self.position()) {
+            return false;
+        }
+        *positions += 1;
+        for next in self.legal_moves() {
+            let mut game = self.clone();
+            if game.play(next).is_err() {
+                continue;
+            }
+            moves.push(next);
+            if game.search(seen, moves, positions, max_positions) {
+                return true;
+            }
+            moves.pop();
+        }
+        false
+    }
+
+    fn position(&self) -> GolfPosition {
+        (
+            self.columns.iter().map(Vec::len).collect(),
+            self.stock.len(),
+            self.foundation_top(),
+        )
+    }
+}
+
+impl SeededGame for GolfGame {
+    /// Deals a game of Golf from a deck shuffled by a generator seeded from the given one
+    fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> GolfGame {
+        let (deck, _) = shuffled_deck(SolitaireVariant::Golf, rng, false);
+        GolfGame::deal(&deck).expect("Golf deals from its own deck")
+    }
+}
+
+impl AuditedGame for GolfGame {
+    /// Deals a game of Golf, recording the shuffle as an event given until the first tick
+    fn audited_with_rng<R: Rng + ?Sized>(rng: &mut R) -> GolfGame {
+        let (deck, events) = shuffled_deck(SolitaireVariant::Golf, rng, true);
+        let mut game = GolfGame::deal(&deck).expect("Golf deals from its own deck");
+        game.events = events;
+        game
+    }
+}
+
+impl AgentGame for GolfGame {
+    type View = GolfGame;
+    type Action = GolfMove;
+    /// Whether the game is over
+    type TickOk = bool;
+    type TickError = SolitaireError;
+
+    fn seat_count(&self) -> usize {
+        1
+    }
+
+    fn view(&self, _seat: usize) -> GolfGame {
+        self.clone()
+    }
+
+    /// Asks the agent for a move, and makes it
+    fn tick_with_agents(
+        &mut self,
+        agents: &mut [&mut dyn Agent<GolfGame, GolfMove>],
+    ) -> Result<bool, SolitaireError> {
+        let [agent] = agents else {
+            return Err(SolitaireError::WrongAgentCount(agents.len()));
+        };
+        // Only the deal emits events, which the first tick leaves behind
+        self.events.clear();
+        if !self.is_over() {
+            let next = agent.choose_action(self);
+            self.play(next)?;
+        }
+        Ok(self.is_over())
+    }
+}
+
+impl EventSource for GolfGame {
+    type Event = SolitaireEvent;
+
+    /// Gets the events from before the first tick, which are only the record of the shuffle in
+    /// audit mode
+    /// # Examples
+    /// ```
+    /// # use naipe::games::audit::AuditedGame;
+    /// # use naipe::games::event::EventSource;
+    /// # use naipe::games::solitaire::golf::GolfGame;
+    /// # use naipe::games::solitaire::SolitaireEvent;
+    /// # use naipe::games::SeededGame;
+    /// let game = GolfGame::audited_from_seed(2);
+    /// let SolitaireEvent::Randomness(record) = &game.events()[0] else {
+    ///     panic!("the shuffle is recorded");
+    /// };
+    /// assert!(record.verify());
+    /// assert_eq!(game.columns(), GolfGame::from_seed(2).columns());
+    /// ```
+    fn events(&self) -> &[SolitaireEvent] {
+        &self.events
+    }
+}
+
+impl ErasedGame for GolfGame {
+    /// Writes the view as `columns=[<cards>,...] foundation=<card> stock=<count> legal=[<moves>]`,
+    /// with each column's cards separated by `/`, its exposed card last, and moves written as
+    /// the column to play from or `draw`
+    fn encode_view(view: &GolfGame) -> String {
+        let columns: Vec<String> = view
+            .columns
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>()
+                    .join("/")
+            })
+            .collect();
+        let legal: Vec<String> = view
+            .legal_moves()
+            .into_iter()
+            .map(|next| match next {
+                GolfMove::Play(column) => column.to_string(),
+                GolfMove::Draw => "draw".to_string(),
+            })
+            .collect();
+        format!(
+            "columns=[{}] foundation={} stock={} legal=[{}]",
+            columns.join(","),
+            view.foundation_top()
+                .map_or_else(|| "none".to_string(), |card| card.to_string()),
+            view.stock_len(),
+            legal.join(","),
+        )
+    }
+
+    /// Reads the column to play from, counting from zero, or `draw`
+    fn decode_action(action: &str) -> Option<GolfMove> {
+        let action = action.trim();
+        if action.eq_ignore_ascii_case("draw") {
+            return Some(GolfMove::Draw);
+        }
+        action.parse().ok().map(GolfMove::Play)
+    }
+
+    /// Makes the first legal move, playing from a column before drawing
+    fn fallback_action(view: &GolfGame) -> GolfMove {
+        view.legal_moves()
+            .first()
+            .copied()
+            .unwrap_or(GolfMove::Draw)
+    }
+
+    fn outcome(&self) -> Option<String> {
+        if !self.is_over() {
+            return None;
+        }
+        let left: usize = self.columns.iter().map(Vec::len).sum();
+        Some(if left == 0 {
+            "Won".to_string()
+        } else {
+            format!("Lost with {left} cards left in the columns")
+        })
+    }
+}
+
+impl Claim for GolfGame {
+    /// Claims the game is won from here, which holds if the solver finds a win, and plays the
+    /// win out
+    /// # Examples
+    /// ```
+    /// # use naipe::games::solitaire::golf::GolfGame;
+    /// # use naipe::games::{Claim, SeededGame};
+    /// let mut game = GolfGame::from_seed(3);
+    /// if game.solve(1_000_000).is_some() {
+    ///     game.claim(0).unwrap();
+    ///     assert!(game.is_won());
+    /// } else {
+    ///     assert!(game.claim(0).is_err());
+    /// }
+    /// assert!(game.claim(1).is_err());
+    /// ```
+    fn claim(&mut self, seat: usize) -> Result<(), ClaimError> {
+        if seat != 0 {
+            return Err(ClaimError::UnknownSeat(seat));
+        }
+        if self.is_over() {
+            return Err(ClaimError::GameOver);
+        }
+        let solution = self.solve(CLAIM_POSITIONS).ok_or(ClaimError::Unproven)?;
+        for next in solution.moves {
+            self.play(next).map_err(|_| ClaimError::Unproven)?;
+        }
+        Ok(())
+    }
+}
+
+impl GameOutcome for GolfGame {
+    /// Gets the only seat once every column is cleared
+    fn winner(&self) -> Option<usize> {
+        self.is_won().then_some(0)
+    }
+}
+
+impl GameInfo for GolfGame {
+    fn info() -> GameMetadata {
+        GameMetadata {
+            name: "Golf",
+            summary: "Clear seven columns onto a run of cards going up or down",
+            min_players: 1,
+            max_players: 1,
+            decks: 1,
+            minutes: 5,
+            tags: &[GameTag::Solitaire],
+        }
+    }
+}
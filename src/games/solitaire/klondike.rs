@@ -0,0 +1,335 @@
+//! Klondike, the classic patience game, with a solver for its deals
+//!
+//! Twenty-eight cards are dealt into seven columns, from one card to seven, with only the last
+//! card of each face up. The rest form the stock, turned one card at a time onto the waste, and
+//! the waste is turned back over as the stock whenever the stock runs out. Face up cards build
+//! down in the columns in alternating colors, a run of them moving together, and only a king may
+//! fill an empty column. Each suit builds up from its ace on a foundation, and cards are never
+//! taken back off. The game is won once every card is on the foundations.
+//!
+//! The solver sees the face down cards, so it finds whether a deal can be won at all, rather
+//! than how a player should play it.
+
+use super::{
+    builds_down_alternating, builds_on_foundation, is_safe_on_foundations, search_depth_first,
+    suit_index, SolitaireError, SolitaireVariant, Solution, TableauColumn,
+};
+use crate::common::{
+    card::{Card, Rank},
+    deck::Deck,
+};
+
+/// The number of columns dealt
+const KLONDIKE_COLUMNS: usize = 7;
+
+/// A move in Klondike
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum KlondikeMove {
+    /// Turns the next stock card onto the waste, or the waste back over once the stock is empty
+    Draw,
+    /// Plays the top card of the waste onto its foundation
+    WasteToFoundation,
+    /// Plays the top card of the waste onto the column, counting from zero
+    WasteToColumn(usize),
+    /// Plays the exposed card of the column onto its foundation
+    ColumnToFoundation(usize),
+    /// Moves the given number of face up cards, from the exposed card back, onto another column
+    ColumnToColumn {
+        from: usize,
+        count: usize,
+        to: usize,
+    },
+}
+
+/// A game of Klondike, drawing one card at a time from the stock
+/// # Examples
+/// ```
+/// # use naipe::common::deck::Deck;
+/// # use naipe::games::solitaire::klondike::{KlondikeGame, KlondikeMove};
+/// let game = KlondikeGame::deal(&Deck::default()).unwrap();
+/// assert_eq!(game.columns().len(), 7);
+/// assert_eq!(game.columns()[6].hidden_len(), 6);
+/// assert_eq!(game.stock_len(), 24);
+/// assert!(game.legal_moves().contains(&KlondikeMove::Draw));
+/// assert!(KlondikeGame::deal(&Deck::new_empty()).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KlondikeGame {
+    columns: Vec<TableauColumn>,
+    stock: Vec<Card>,
+    waste: Vec<Card>,
+    /// The number of cards on each suit's foundation
+    foundations: [usize; 4],
+}
+
+/// The parts of a Klondike position that decide what can happen next, with the columns in order
+/// so that positions differing only by which column is which are seen once
+type KlondikePosition = (Vec<(usize, Vec<Card>)>, usize, [usize; 4]);
+
+impl KlondikeGame {
+    /// Lays out a game from a deck, dealing across the columns from the top, one fewer column
+    /// each row
+    /// # Errors
+    /// [`SolitaireError::WrongDeckSize`] unless the deck is a standard 52 cards
+    pub fn deal(deck: &Deck) -> Result<KlondikeGame, SolitaireError> {
+        let expected = SolitaireVariant::Klondike.deck().len();
+        if deck.len() != expected {
+            return Err(SolitaireError::WrongDeckSize {
+                expected,
+                actual: deck.len(),
+            });
+        }
+        let mut deck = deck.clone();
+        let mut columns = vec![vec![]; KLONDIKE_COLUMNS];
+        for row in 0..KLONDIKE_COLUMNS {
+            for column in &mut columns[row..] {
+                column.extend(deck.draw());
+            }
+        }
+        let mut stock: Vec<Card> = std::iter::from_fn(|| deck.draw()).collect();
+        stock.reverse();
+        Ok(KlondikeGame {
+            columns: columns.into_iter().map(TableauColumn::dealt).collect(),
+            stock,
+            waste: vec![],
+            foundations: [0; 4],
+        })
+    }
+
+    /// Gets each column
+    pub fn columns(&self) -> &[TableauColumn] {
+        &self.columns
+    }
+
+    /// Gets the number of cards left in the stock
+    pub fn stock_len(&self) -> usize {
+        self.stock.len()
+    }
+
+    /// Gets the top card of the waste
+    pub fn waste_top(&self) -> Option<Card> {
+        self.waste.last().copied()
+    }
+
+    /// Gets the number of cards on each foundation, in the order spades, clubs, hearts, diamonds
+    pub fn foundations(&self) -> [usize; 4] {
+        self.foundations
+    }
+
+    /// Finds if the card may be played onto the column
+    fn fits(&self, card: Card, column: usize) -> bool {
+        match self.columns[column].shown().last() {
+            Some(onto) => builds_down_alternating(card, *onto),
+            None => card.get_rank() == Rank::King,
+        }
+    }
+
+    /// Finds if the card may be played onto its foundation
+    fn fits_foundation(&self, card: Card) -> bool {
+        builds_on_foundation(card, self.foundations[suit_index(card.get_suit())])
+    }
+
+    /// Gets every move allowed now, playing to the foundations first and drawing last
+    pub fn legal_moves(&self) -> Vec<KlondikeMove> {
+        let mut moves = vec![];
+        if self
+            .waste_top()
+            .is_some_and(|card| self.fits_foundation(card))
+        {
+            moves.push(KlondikeMove::WasteToFoundation);
+        }
+        for (from, column) in self.columns.iter().enumerate() {
+            if column
+                .shown()
+                .last()
+                .is_some_and(|card| self.fits_foundation(*card))
+            {
+                moves.push(KlondikeMove::ColumnToFoundation(from));
+            }
+        }
+        for (from, column) in self.columns.iter().enumerate() {
+            let shown = column.shown();
+            for (start, card) in shown.iter().enumerate() {
+                let count = shown.len() - start;
+                moves.extend(
+                    (0..KLONDIKE_COLUMNS)
+                        .filter(|to| *to != from && self.fits(*card, *to))
+                        .map(|to| KlondikeMove::ColumnToColumn { from, count, to }),
+                );
+            }
+        }
+        if let Some(card) = self.waste_top() {
+            moves.extend(
+                (0..KLONDIKE_COLUMNS)
+                    .filter(|to| self.fits(card, *to))
+                    .map(KlondikeMove::WasteToColumn),
+            );
+        }
+        if !self.stock.is_empty() || !self.waste.is_empty() {
+            moves.push(KlondikeMove::Draw);
+        }
+        moves
+    }
+
+    /// Makes a move
+    /// # Errors
+    /// [`SolitaireError::IllegalMove`] if the move is not allowed now
+    pub fn play(&mut self, next: KlondikeMove) -> Result<(), SolitaireError> {
+        match next {
+            KlondikeMove::Draw => {
+                if let Some(card) = self.stock.pop() {
+                    self.waste.push(card);
+                } else if self.waste.is_empty() {
+                    return Err(SolitaireError::IllegalMove);
+                } else {
+                    self.stock = std::mem::take(&mut self.waste);
+                    self.stock.reverse();
+                }
+            }
+            KlondikeMove::WasteToFoundation => {
+                let card = self
+                    .waste_top()
+                    .filter(|card| self.fits_foundation(*card))
+                    .ok_or(SolitaireError::IllegalMove)?;
+                self.waste.pop();
+                self.foundations[suit_index(card.get_suit())] += 1;
+            }
+            KlondikeMove::WasteToColumn(to) => {
+                let card = self
+                    .waste_top()
+                    .filter(|card| to < KLONDIKE_COLUMNS && self.fits(*card, to))
+                    .ok_or(SolitaireError::IllegalMove)?;
+                self.waste.pop();
+                self.columns[to].shown.push(card);
+            }
+            KlondikeMove::ColumnToFoundation(from) => {
+                let card = self
+                    .columns
+                    .get(from)
+                    .and_then(|column| column.shown().last().copied())
+                    .filter(|card| self.fits_foundation(*card))
+                    .ok_or(SolitaireError::IllegalMove)?;
+                self.columns[from].take(1);
+                self.foundations[suit_index(card.get_suit())] += 1;
+            }
+            KlondikeMove::ColumnToColumn { from, count, to } => {
+                let card = self
+                    .columns
+                    .get(from)
+                    .filter(|_| from != to && to < KLONDIKE_COLUMNS && count > 0)
+                    .and_then(|column| column.shown().iter().rev().nth(count - 1).copied())
+                    .filter(|card| self.fits(*card, to))
+                    .ok_or(SolitaireError::IllegalMove)?;
+                let cards = self.columns[from].take(count);
+                debug_assert_eq!(cards.first(), Some(&card));
+                self.columns[to].shown.extend(cards);
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds if every card is on the foundations
+    pub fn is_won(&self) -> bool {
+        self.foundations.iter().sum::<usize>() == SolitaireVariant::Klondike.deck().len()
+    }
+
+    /// Finds if the game is won, or no move is left
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.legal_moves().is_empty()
+    }
+
+    /// Searches for a way to win from this position, looking at no more than the given number of
+    /// positions
+    ///
+    /// Gives `None` if no win was found within the limit. The search skips moves that cannot
+    /// help, such as moving a king between empty columns, so it may miss the rare deal won only
+    /// by a line it skips
+    /// # Examples
+    /// ```
+    /// # use naipe::common::deck::Deck;
+    /// # use naipe::games::solitaire::klondike::KlondikeGame;
+    /// use naipe::games::GameRng;
+    /// use rand::SeedableRng;
+    /// let mut deck = Deck::default();
+    /// deck.shuffle(&mut GameRng::seed_from_u64(1));
+    /// let game = KlondikeGame::deal(&deck).unwrap();
+    /// if let Some(solution) = game.solve(100_000) {
+    ///     let mut game = game.clone();
+    ///     for next in solution.moves {
+    ///         game.play(next).unwrap();
+    ///     }
+    ///     assert!(game.is_won());
+    /// }
+    /// ```
+    pub fn solve(&self, max_positions: u64) -> Option<Solution<KlondikeMove>> {
+        search_depth_first(
+            self,
+            max_positions,
+            KlondikeGame::is_won,
+            KlondikeGame::position,
+            KlondikeGame::useful_moves,
+            |game, next| game.play(next).is_ok(),
+        )
+    }
+
+    /// Gets the moves worth searching, best first: only a card safe to play to its foundation if
+    /// there is one, and otherwise every move that could help
+    fn useful_moves(&self) -> Vec<KlondikeMove> {
+        let moves = self.legal_moves();
+        let safe = moves.iter().copied().find(|next| {
+            match next {
+                KlondikeMove::WasteToFoundation => self.waste_top(),
+                KlondikeMove::ColumnToFoundation(from) => {
+                    self.columns[*from].shown().last().copied()
+                }
+                _ => None,
+            }
+            .is_some_and(|card| is_safe_on_foundations(card, &self.foundations))
+        });
+        if let Some(safe) = safe {
+            return vec![safe];
+        }
+        let mut useful: Vec<KlondikeMove> = moves
+            .into_iter()
+            .filter(|next| match *next {
+                KlondikeMove::ColumnToColumn { from, count, to } => {
+                    let column = &self.columns[from];
+                    let whole = count == column.shown().len();
+                    if whole {
+                        // Moving a whole run helps if it turns a card over, or frees a column
+                        // for a king
+                        column.hidden_len() > 0 || !self.columns[to].is_empty()
+                    } else {
+                        // Moving part of a run only helps if it uncovers a card for a foundation
+                        let uncovered = column.shown()[column.shown().len() - count - 1];
+                        self.fits_foundation(uncovered)
+                    }
+                }
+                _ => true,
+            })
+            .collect();
+        // Turn cards over before anything else, and prefer the column hiding the most
+        useful.sort_by_key(|next| match *next {
+            KlondikeMove::WasteToFoundation | KlondikeMove::ColumnToFoundation(_) => (0, 0),
+            KlondikeMove::ColumnToColumn { from, count, .. }
+                if count == self.columns[from].shown().len() =>
+            {
+                (1, usize::MAX - self.columns[from].hidden_len())
+            }
+            KlondikeMove::WasteToColumn(_) => (2, 0),
+            KlondikeMove::ColumnToColumn { .. } => (3, 0),
+            _ => (4, 0),
+        });
+        useful
+    }
+
+    fn position(&self) -> KlondikePosition {
+        let mut columns: Vec<(usize, Vec<Card>)> =
+            self.columns.iter().map(TableauColumn::key).collect();
+        columns.sort_unstable();
+        (columns, self.waste.len(), self.foundations)
+    }
+}
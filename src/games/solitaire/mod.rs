@@ -0,0 +1,415 @@
+//! Shared support for single-player patience games
+//!
+//! naipe plays [`klondike::KlondikeGame`], [`freecell::FreeCellGame`], [`spider::SpiderGame`],
+//! and the quick patience games [`golf::GolfGame`] and [`accordion::AccordionGame`], each with a
+//! solver, and this module deals the cards each variant starts from.
+//! The [`daily::DailyDeals`] generator gives every player the same deal on the same day, only
+//! offering deals the variant's solver can win, and can pick deals of a chosen
+//! [`difficulty::Difficulty`].
+
+pub mod accordion;
+pub mod daily;
+pub mod difficulty;
+pub mod freecell;
+pub mod golf;
+pub mod klondike;
+pub mod spider;
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+    fmt::{self, Display, Formatter},
+    hash::Hash,
+    num::NonZeroU16,
+};
+
+use rand::{Rng, SeedableRng};
+use thiserror::Error;
+
+use crate::common::{
+    card::{Card, Rank, Suit},
+    deck::{Deck, DeckBuilder},
+};
+use crate::games::{audit::RandomnessRecord, GameRng};
+
+/// A solitaire game, which decides how many cards are dealt
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum SolitaireVariant {
+    /// Played with a single deck, drawing from a stock
+    Klondike,
+    /// Played with a single deck, with four free cells to park cards in
+    FreeCell,
+    /// Played with two decks of all four suits
+    Spider,
+    /// Played with a single deck, clearing seven columns onto a run up or down
+    Golf,
+    /// Played with a single deck, squeezing a row of cards into a single pile
+    Accordion,
+}
+
+impl SolitaireVariant {
+    /// Gets the composition of the cards the variant is played with
+    /// # Examples
+    /// ```
+    /// # use naipe::games::solitaire::SolitaireVariant;
+    /// assert_eq!(SolitaireVariant::FreeCell.deck_builder().build().len(), 52);
+    /// assert_eq!(SolitaireVariant::Spider.deck_builder().build().len(), 104);
+    /// ```
+    pub fn deck_builder(self) -> DeckBuilder {
+        match self {
+            SolitaireVariant::Klondike
+            | SolitaireVariant::FreeCell
+            | SolitaireVariant::Golf
+            | SolitaireVariant::Accordion => DeckBuilder::new(),
+            SolitaireVariant::Spider => DeckBuilder::new().sets(NonZeroU16::MIN.saturating_add(1)),
+        }
+    }
+
+    /// Creates an unshuffled deck of the cards the variant is played with
+    pub fn deck(self) -> Deck {
+        self.deck_builder().build()
+    }
+
+    /// Finds if naipe has a solver for the variant, which is so for every variant
+    pub fn has_solver(self) -> bool {
+        matches!(
+            self,
+            SolitaireVariant::Klondike
+                | SolitaireVariant::FreeCell
+                | SolitaireVariant::Spider
+                | SolitaireVariant::Golf
+                | SolitaireVariant::Accordion
+        )
+    }
+
+    /// Solves the deal from the deck with the variant's solver, looking at no more than the given
+    /// number of positions
+    ///
+    /// Gives `None` if the variant has no solver, and otherwise whether a win was found, which is
+    /// `false` for a deck of the wrong size
+    /// # Examples
+    /// ```
+    /// # use naipe::games::solitaire::SolitaireVariant;
+    /// let deck = SolitaireVariant::Golf.deck();
+    /// assert!(SolitaireVariant::Golf.solve(&deck, 1_000).is_some());
+    /// assert_eq!(SolitaireVariant::Spider.solve(&deck, 1_000), Some(false));
+    /// ```
+    pub fn solve(self, deck: &Deck, max_positions: u64) -> Option<bool> {
+        match self {
+            SolitaireVariant::Klondike => Some(
+                klondike::KlondikeGame::deal(deck)
+                    .is_ok_and(|game| game.solve(max_positions).is_some()),
+            ),
+            SolitaireVariant::FreeCell => Some(
+                freecell::FreeCellGame::deal(deck)
+                    .is_ok_and(|game| game.solve(max_positions).is_some()),
+            ),
+            SolitaireVariant::Spider => Some(
+                spider::SpiderGame::deal(deck)
+                    .is_ok_and(|game| game.solve(max_positions).is_some()),
+            ),
+            SolitaireVariant::Golf => Some(
+                golf::GolfGame::deal(deck).is_ok_and(|game| game.solve(max_positions).is_some()),
+            ),
+            SolitaireVariant::Accordion => Some(
+                accordion::AccordionGame::deal(deck)
+                    .solve(max_positions)
+                    .is_some(),
+            ),
+        }
+    }
+}
+
+impl Display for SolitaireVariant {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SolitaireVariant::Klondike => write!(f, "Klondike"),
+            SolitaireVariant::FreeCell => write!(f, "FreeCell"),
+            SolitaireVariant::Spider => write!(f, "Spider"),
+            SolitaireVariant::Golf => write!(f, "Golf"),
+            SolitaireVariant::Accordion => write!(f, "Accordion"),
+        }
+    }
+}
+
+/// Something that happened in a patience game, reported through
+/// [`EventSource::events`](crate::games::event::EventSource::events)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SolitaireEvent {
+    /// In audit mode, the cards were shuffled before the deal
+    Randomness(RandomnessRecord),
+}
+
+/// Shuffles the variant's cards with a generator seeded from the given one, recording the
+/// shuffle in audit mode
+fn shuffled_deck<R: Rng + ?Sized>(
+    variant: SolitaireVariant,
+    rng: &mut R,
+    audit: bool,
+) -> (Deck, Vec<SolitaireEvent>) {
+    let mut deck = variant.deck();
+    let mut rng = GameRng::from_seed(rng.gen());
+    if audit {
+        let record = RandomnessRecord::shuffle("deal", &mut deck, &mut rng);
+        (deck, vec![SolitaireEvent::Randomness(record)])
+    } else {
+        deck.shuffle(&mut rng);
+        (deck, vec![])
+    }
+}
+
+/// A tableau column, with cards face down beneath a run of face up cards
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableauColumn {
+    hidden: Vec<Card>,
+    shown: Vec<Card>,
+}
+
+impl TableauColumn {
+    /// Lays out a column from cards dealt in order, turning the last face up
+    fn dealt(mut hidden: Vec<Card>) -> TableauColumn {
+        let shown = hidden.pop().into_iter().collect();
+        TableauColumn { hidden, shown }
+    }
+
+    /// Gets the number of cards face down
+    pub fn hidden_len(&self) -> usize {
+        self.hidden.len()
+    }
+
+    /// Gets the face up cards, with the exposed card last
+    pub fn shown(&self) -> &[Card] {
+        &self.shown
+    }
+
+    /// Finds if the column has no cards at all
+    pub fn is_empty(&self) -> bool {
+        self.hidden.is_empty() && self.shown.is_empty()
+    }
+
+    /// Takes the given number of face up cards off the column, turning the next card face up
+    /// once none are left
+    fn take(&mut self, count: usize) -> Vec<Card> {
+        let cards = self.shown.split_off(self.shown.len() - count);
+        if self.shown.is_empty() {
+            self.shown.extend(self.hidden.pop());
+        }
+        cards
+    }
+
+    /// Gets every card of the column in order, and how many are face down, to tell positions
+    /// apart
+    fn key(&self) -> (usize, Vec<Card>) {
+        let cards = self.hidden.iter().chain(&self.shown).copied().collect();
+        (self.hidden.len(), cards)
+    }
+}
+
+/// Gets the value of a rank counting aces low, from one for an ace to thirteen for a king
+fn pips(rank: Rank) -> usize {
+    match rank {
+        Rank::Ace => 1,
+        Rank::Two => 2,
+        Rank::Three => 3,
+        Rank::Four => 4,
+        Rank::Five => 5,
+        Rank::Six => 6,
+        Rank::Seven => 7,
+        Rank::Eight => 8,
+        Rank::Nine => 9,
+        Rank::Ten => 10,
+        Rank::Jack => 11,
+        Rank::Queen => 12,
+        Rank::King => 13,
+        Rank::Joker => 0,
+    }
+}
+
+/// Gets the index of a suit's foundation
+fn suit_index(suit: Suit) -> usize {
+    match suit {
+        Suit::Spade => 0,
+        Suit::Club => 1,
+        Suit::Heart => 2,
+        Suit::Diamond => 3,
+    }
+}
+
+/// Finds if the card is the next to build onto a foundation holding the given number of cards
+fn builds_on_foundation(card: Card, foundation: usize) -> bool {
+    pips(card.get_rank()) == foundation + 1
+}
+
+/// Finds if the card may go onto its foundation without being needed to build on in the
+/// tableau, since every card of the other color it could hold is already on a foundation
+fn is_safe_on_foundations(card: Card, foundations: &[usize; 4]) -> bool {
+    let pips = pips(card.get_rank());
+    builds_on_foundation(card, foundations[suit_index(card.get_suit())])
+        && (pips <= 2
+            || Suit::all_suits()
+                .into_iter()
+                .filter(|suit| suit.is_red() != card.get_suit().is_red())
+                .all(|suit| foundations[suit_index(suit)] + 1 >= pips))
+}
+
+/// Finds if the card may be built onto the other in the tableau, one rank lower and of the
+/// other color
+fn builds_down_alternating(card: Card, onto: Card) -> bool {
+    onto.get_rank().is_directly_after(card.get_rank())
+        && card.get_suit().is_red() != onto.get_suit().is_red()
+}
+
+/// A winning line of play found by a solver
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Solution<M> {
+    /// Every move to make, in order
+    pub moves: Vec<M>,
+    /// How many positions the solver looked at, as a measure of how hard the deal is
+    pub positions: u64,
+}
+
+/// A position being searched, with the moves from it still to try
+struct Frame<G, M> {
+    game: G,
+    moves: std::vec::IntoIter<M>,
+}
+
+/// Searches depth first for a win, trying the moves given for each position in order and
+/// skipping positions already seen, looking at no more than the given number of positions
+///
+/// The search keeps its own stack, rather than recursing, as winning lines can run to hundreds of
+/// moves
+fn search_depth_first<G: Clone, M: Copy, K: Eq + Hash>(
+    start: &G,
+    max_positions: u64,
+    is_won: impl Fn(&G) -> bool,
+    key: impl Fn(&G) -> K,
+    moves: impl Fn(&G) -> Vec<M>,
+    play: impl Fn(&mut G, M) -> bool,
+) -> Option<Solution<M>> {
+    let mut seen = HashSet::new();
+    let mut path = vec![];
+    if is_won(start) {
+        return Some(Solution {
+            moves: path,
+            positions: 0,
+        });
+    }
+    if max_positions == 0 {
+        return None;
+    }
+    seen.insert(key(start));
+    let mut positions = 1;
+    let mut stack = vec![Frame {
+        game: start.clone(),
+        moves: moves(start).into_iter(),
+    }];
+    while let Some(frame) = stack.last_mut() {
+        let Some(next) = frame.moves.next() else {
+            stack.pop();
+            path.pop();
+            continue;
+        };
+        let mut game = frame.game.clone();
+        if !play(&mut game, next) {
+            continue;
+        }
+        if is_won(&game) {
+            path.push(next);
+            return Some(Solution {
+                moves: path,
+                positions,
+            });
+        }
+        if positions >= max_positions {
+            return None;
+        }
+        if seen.insert(key(&game)) {
+            positions += 1;
+            path.push(next);
+            stack.push(Frame {
+                moves: moves(&game).into_iter(),
+                game,
+            });
+        }
+    }
+    None
+}
+
+/// Searches for a win best first, always going on from the position seen with the lowest score,
+/// skipping positions already seen, looking at no more than the given number of positions
+fn search_best_first<G: Clone, M: Copy, K: Eq + Hash>(
+    start: &G,
+    max_positions: u64,
+    is_won: impl Fn(&G) -> bool,
+    key: impl Fn(&G) -> K,
+    score: impl Fn(&G) -> usize,
+    moves: impl Fn(&G) -> Vec<M>,
+    play: impl Fn(&mut G, M) -> bool,
+) -> Option<Solution<M>> {
+    if is_won(start) {
+        return Some(Solution {
+            moves: vec![],
+            positions: 0,
+        });
+    }
+    let mut seen = HashSet::new();
+    seen.insert(key(start));
+    // How each position queued was reached, from the position before it
+    let mut steps: Vec<(usize, Option<M>)> = vec![(0, None)];
+    // The positions queued, each taken out once searched
+    let mut games = vec![Some(start.clone())];
+    let mut queue = BinaryHeap::from([(Reverse(score(start)), 0)]);
+    let mut positions = 0;
+    while let Some((_, index)) = queue.pop() {
+        if positions >= max_positions {
+            return None;
+        }
+        positions += 1;
+        let Some(game) = games[index].take() else {
+            continue;
+        };
+        for next in moves(&game) {
+            let mut after = game.clone();
+            if !play(&mut after, next) {
+                continue;
+            }
+            if is_won(&after) {
+                let mut path = vec![next];
+                let mut step = index;
+                while let (parent, Some(before)) = steps[step] {
+                    path.push(before);
+                    step = parent;
+                }
+                path.reverse();
+                return Some(Solution {
+                    moves: path,
+                    positions,
+                });
+            }
+            if seen.insert(key(&after)) {
+                steps.push((index, Some(next)));
+                queue.push((Reverse(score(&after)), games.len()));
+                games.push(Some(after));
+            }
+        }
+    }
+    None
+}
+
+/// Errors related to playing patience games
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SolitaireError {
+    #[error("Expected a deck of {expected} cards, but got {actual}")]
+    WrongDeckSize { expected: usize, actual: usize },
+    #[error("That move is not allowed now")]
+    IllegalMove,
+    #[error("Expected 1 agent, but got {0}")]
+    WrongAgentCount(usize),
+}
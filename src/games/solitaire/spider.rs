@@ -0,0 +1,349 @@
+//! Spider, played with two decks of all four suits, with a solver for its deals
+//!
+//! Fifty-four cards are dealt into ten columns, six to each of the first four and five to the
+//! rest, with only the last card of each face up. Any card may be built onto a card one rank
+//! higher, whatever its suit, but only a run of one suit moves together, and any run may fill an
+//! empty column. The other fifty cards form the stock, dealt a card to each column at a time
+//! once no column is empty. A run of one suit from king down to ace leaves the tableau as soon
+//! as it is built, and the game is won once all eight have left.
+//!
+//! The solver sees the face down cards, and only tries moves likely to help, so it finds a win
+//! for some winnable deals and not others.
+
+use super::{search_best_first, SolitaireError, SolitaireVariant, Solution, TableauColumn};
+use crate::common::{
+    card::{Card, Rank},
+    deck::Deck,
+};
+
+/// The number of columns dealt
+const SPIDER_COLUMNS: usize = 10;
+/// The number of columns dealt an extra card at the start
+const LONG_COLUMNS: usize = 4;
+/// The number of cards dealt to the shorter columns at the start
+const SHORT_COLUMN_DEPTH: usize = 5;
+/// The number of cards in a run of one suit from king down to ace
+const SUIT_RUN: usize = 13;
+
+// The weights the solver scores positions by, found by trial on sample deals
+/// The cost of a card built onto one of another suit
+const OFF_SUIT_COST: usize = 1;
+/// The cost of a card sitting on one it doesn't build onto
+const BREAK_COST: usize = 2;
+/// The cost of each face down card
+const HIDDEN_COST: usize = 20;
+/// The cost of every two cards left in the stock
+const STOCK_COST: usize = 3;
+/// The cost of each run of one suit still to be built and taken off
+const RUN_COST: usize = 100;
+/// The value of each empty column
+const EMPTY_COLUMN_VALUE: usize = 10;
+
+/// A move in Spider
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum SpiderMove {
+    /// Deals a card from the stock face up onto every column
+    Deal,
+    /// Moves the given number of cards, from the exposed card back, from one column onto
+    /// another, counting columns from zero
+    Move {
+        from: usize,
+        count: usize,
+        to: usize,
+    },
+}
+
+/// A game of Spider
+/// # Examples
+/// ```
+/// # use naipe::games::solitaire::spider::{SpiderGame, SpiderMove};
+/// # use naipe::games::solitaire::SolitaireVariant;
+/// let mut game = SpiderGame::deal(&SolitaireVariant::Spider.deck()).unwrap();
+/// assert_eq!(game.columns()[0].hidden_len(), 5);
+/// assert_eq!(game.columns()[9].hidden_len(), 4);
+/// assert_eq!(game.stock_len(), 50);
+/// game.play(SpiderMove::Deal).unwrap();
+/// assert_eq!(game.stock_len(), 40);
+/// assert!(SpiderGame::deal(&SolitaireVariant::Golf.deck()).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpiderGame {
+    columns: Vec<TableauColumn>,
+    stock: Vec<Card>,
+    /// The number of runs of one suit built from king down to ace
+    completed: usize,
+}
+
+/// The parts of a Spider position that decide what can happen next, with the columns in order
+/// so that positions differing only by which column is which are seen once
+type SpiderPosition = (Vec<(usize, Vec<Card>)>, usize);
+
+impl SpiderGame {
+    /// Lays out a game from a deck, dealing across the columns from the top
+    /// # Errors
+    /// [`SolitaireError::WrongDeckSize`] unless the deck is two standard decks of 104 cards
+    pub fn deal(deck: &Deck) -> Result<SpiderGame, SolitaireError> {
+        let expected = SolitaireVariant::Spider.deck().len();
+        if deck.len() != expected {
+            return Err(SolitaireError::WrongDeckSize {
+                expected,
+                actual: deck.len(),
+            });
+        }
+        let mut deck = deck.clone();
+        let mut columns = vec![vec![]; SPIDER_COLUMNS];
+        for row in 0..=SHORT_COLUMN_DEPTH {
+            let dealt = if row < SHORT_COLUMN_DEPTH {
+                SPIDER_COLUMNS
+            } else {
+                LONG_COLUMNS
+            };
+            for column in &mut columns[..dealt] {
+                column.extend(deck.draw());
+            }
+        }
+        let mut stock: Vec<Card> = std::iter::from_fn(|| deck.draw()).collect();
+        stock.reverse();
+        Ok(SpiderGame {
+            columns: columns.into_iter().map(TableauColumn::dealt).collect(),
+            stock,
+            completed: 0,
+        })
+    }
+
+    /// Gets each column
+    pub fn columns(&self) -> &[TableauColumn] {
+        &self.columns
+    }
+
+    /// Gets the number of cards left in the stock
+    pub fn stock_len(&self) -> usize {
+        self.stock.len()
+    }
+
+    /// Gets the number of runs of one suit built from king down to ace, and taken off
+    pub fn completed(&self) -> usize {
+        self.completed
+    }
+
+    /// Gets the number of cards at the end of the column built down in one suit
+    fn run_len(column: &TableauColumn) -> usize {
+        let shown = column.shown();
+        let built = shown
+            .windows(2)
+            .rev()
+            .take_while(|pair| builds_down_in_suit(pair[1], pair[0]))
+            .count();
+        if shown.is_empty() {
+            0
+        } else {
+            built + 1
+        }
+    }
+
+    /// Finds if the card may be played onto the column
+    fn fits(&self, card: Card, column: usize) -> bool {
+        self.columns[column]
+            .shown()
+            .last()
+            .is_none_or(|onto| onto.get_rank().is_directly_after(card.get_rank()))
+    }
+
+    /// Gets every move allowed now, dealing last
+    pub fn legal_moves(&self) -> Vec<SpiderMove> {
+        let mut moves = vec![];
+        for (from, column) in self.columns.iter().enumerate() {
+            let shown = column.shown();
+            for count in 1..=SpiderGame::run_len(column) {
+                let card = shown[shown.len() - count];
+                moves.extend(
+                    (0..SPIDER_COLUMNS)
+                        .filter(|to| *to != from && self.fits(card, *to))
+                        .map(|to| SpiderMove::Move { from, count, to }),
+                );
+            }
+        }
+        if !self.stock.is_empty() && !self.columns.iter().any(TableauColumn::is_empty) {
+            moves.push(SpiderMove::Deal);
+        }
+        moves
+    }
+
+    /// Makes a move, then takes off any run of one suit from king down to ace it completes
+    /// # Errors
+    /// [`SolitaireError::IllegalMove`] if the move is not allowed now
+    pub fn play(&mut self, next: SpiderMove) -> Result<(), SolitaireError> {
+        match next {
+            SpiderMove::Deal => {
+                if self.stock.is_empty() || self.columns.iter().any(TableauColumn::is_empty) {
+                    return Err(SolitaireError::IllegalMove);
+                }
+                for column in 0..SPIDER_COLUMNS {
+                    self.columns[column].shown.extend(self.stock.pop());
+                    self.complete_run(column);
+                }
+            }
+            SpiderMove::Move { from, count, to } => {
+                let legal = from != to
+                    && to < SPIDER_COLUMNS
+                    && self.columns.get(from).is_some_and(|column| {
+                        (1..=SpiderGame::run_len(column)).contains(&count)
+                            && self.fits(column.shown()[column.shown().len() - count], to)
+                    });
+                if !legal {
+                    return Err(SolitaireError::IllegalMove);
+                }
+                let cards = self.columns[from].take(count);
+                self.columns[to].shown.extend(cards);
+                self.complete_run(to);
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes off a run of one suit from king down to ace at the end of the column, if there is
+    /// one
+    fn complete_run(&mut self, column: usize) {
+        if SpiderGame::run_len(&self.columns[column]) >= SUIT_RUN
+            && self.columns[column]
+                .shown()
+                .last()
+                .is_some_and(|card| card.get_rank() == Rank::Ace)
+        {
+            self.columns[column].take(SUIT_RUN);
+            self.completed += 1;
+        }
+    }
+
+    /// Finds if every card has been built into runs and taken off
+    pub fn is_won(&self) -> bool {
+        self.completed * SUIT_RUN == SolitaireVariant::Spider.deck().len()
+    }
+
+    /// Finds if the game is won, or no move is left
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.legal_moves().is_empty()
+    }
+
+    /// Searches for a way to win from this position, looking at no more than the given number of
+    /// positions
+    ///
+    /// Gives `None` if no win was found within the limit. The search only tries moves likely to
+    /// help, such as those turning a card over or building in suit, so it misses some deals that
+    /// can be won
+    /// # Examples
+    /// ```
+    /// # use naipe::games::solitaire::spider::SpiderGame;
+    /// # use naipe::games::solitaire::SolitaireVariant;
+    /// use naipe::games::GameRng;
+    /// use rand::SeedableRng;
+    /// let mut deck = SolitaireVariant::Spider.deck();
+    /// deck.shuffle(&mut GameRng::seed_from_u64(1));
+    /// let game = SpiderGame::deal(&deck).unwrap();
+    /// if let Some(solution) = game.solve(10_000) {
+    ///     let mut game = game.clone();
+    ///     for next in solution.moves {
+    ///         game.play(next).unwrap();
+    ///     }
+    ///     assert!(game.is_won());
+    /// }
+    /// ```
+    pub fn solve(&self, max_positions: u64) -> Option<Solution<SpiderMove>> {
+        search_best_first(
+            self,
+            max_positions,
+            SpiderGame::is_won,
+            SpiderGame::position,
+            SpiderGame::disorder,
+            SpiderGame::useful_moves,
+            |game, next| game.play(next).is_ok(),
+        )
+    }
+
+    /// Gets the moves worth searching, best first
+    ///
+    /// Only whole runs of one suit are moved, and a run already built onto a card one rank
+    /// higher only moves onto a card of its own suit. Moves building in suit come first, then
+    /// those turning over the column hiding the most cards, then the rest, and dealing last.
+    fn useful_moves(&self) -> Vec<SpiderMove> {
+        let mut useful: Vec<(usize, SpiderMove)> = self
+            .legal_moves()
+            .into_iter()
+            .filter_map(|next| {
+                let SpiderMove::Move { from, count, to } = next else {
+                    return Some((usize::MAX, next));
+                };
+                let column = &self.columns[from];
+                let shown = column.shown();
+                let card = shown[shown.len() - count];
+                let beneath = shown.len().checked_sub(count + 1).map(|at| shown[at]);
+                let onto = self.columns[to].shown().last();
+                let in_suit = onto.is_some_and(|onto| onto.get_suit() == card.get_suit());
+                let built = beneath
+                    .is_some_and(|beneath| beneath.get_rank().is_directly_after(card.get_rank()));
+                let helps = match beneath {
+                    // Moving a whole column into an empty one changes nothing
+                    None => column.hidden_len() > 0 || onto.is_some(),
+                    Some(_) if count < SpiderGame::run_len(column) => false,
+                    Some(_) => !built || in_suit || onto.is_none(),
+                };
+                let mut after = self.clone();
+                let rank = if after.play(next).is_ok() {
+                    after.disorder()
+                } else {
+                    usize::MAX
+                };
+                helps.then_some((rank, next))
+            })
+            .collect();
+        useful.sort_by_key(|(rank, _)| *rank);
+        useful.into_iter().map(|(_, next)| next).collect()
+    }
+
+    /// Scores how far the position is from being won, lower being closer
+    fn disorder(&self) -> usize {
+        let breaks: usize = self
+            .columns
+            .iter()
+            .map(|column| {
+                column
+                    .shown()
+                    .windows(2)
+                    .map(|pair| {
+                        if builds_down_in_suit(pair[1], pair[0]) {
+                            0
+                        } else if pair[0].get_rank().is_directly_after(pair[1].get_rank()) {
+                            OFF_SUIT_COST
+                        } else {
+                            BREAK_COST
+                        }
+                    })
+                    .sum::<usize>()
+                    + HIDDEN_COST * column.hidden_len()
+            })
+            .sum();
+        let empty = self
+            .columns
+            .iter()
+            .filter(|column| column.is_empty())
+            .count();
+        let left = SolitaireVariant::Spider.deck().len() / SUIT_RUN - self.completed;
+        (breaks + STOCK_COST * self.stock.len() / 2 + RUN_COST * left)
+            .saturating_sub(EMPTY_COLUMN_VALUE * empty)
+    }
+
+    fn position(&self) -> SpiderPosition {
+        let mut columns: Vec<(usize, Vec<Card>)> =
+            self.columns.iter().map(TableauColumn::key).collect();
+        columns.sort_unstable();
+        (columns, self.stock.len())
+    }
+}
+
+/// Finds if the card may move together with the other, one rank lower in the same suit
+fn builds_down_in_suit(card: Card, onto: Card) -> bool {
+    onto.get_rank().is_directly_after(card.get_rank()) && card.get_suit() == onto.get_suit()
+}
@@ -0,0 +1,438 @@
+//! Tournaments between many entrants, such as leagues of computer strategies
+//!
+//! A [`Tournament`] schedules matches between pairs of entrants and keeps their standings,
+//! either as a round robin or, for larger fields, as a Swiss system paired round by round.
+//! Its whole state is plain data, so with the `serde` feature a long tournament can be saved
+//! after every match and resumed from [`Tournament::next_match`] after a restart.
+
+use std::{cmp::Reverse, collections::HashSet, num::NonZeroUsize};
+
+use thiserror::Error;
+
+/// Standing points for winning a match
+pub const WIN_POINTS: u32 = 2;
+/// Standing points for drawing a match
+pub const DRAW_POINTS: u32 = 1;
+
+/// How entrants are paired
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum TournamentFormat {
+    /// Every entrant plays every other entrant once
+    RoundRobin,
+    /// Entrants play a fixed number of rounds, each against an entrant on similar points they
+    /// have not yet played
+    Swiss { rounds: NonZeroUsize },
+}
+
+/// A match between two entrants, counting entrants from zero in the order they entered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pairing {
+    /// The round the match belongs to, counting from zero
+    pub round: usize,
+    pub entrants: [usize; 2],
+}
+
+/// A played match
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchRecord {
+    pub pairing: Pairing,
+    /// The winning entrant, or `None` for a draw
+    pub winner: Option<usize>,
+}
+
+/// A round an entrant sat out of a Swiss tournament, scoring as a win
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bye {
+    pub round: usize,
+    pub entrant: usize,
+}
+
+/// An entrant's record so far
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Standing {
+    pub entrant: usize,
+    /// [`WIN_POINTS`] for each win or bye and [`DRAW_POINTS`] for each draw
+    pub points: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub byes: u32,
+    /// The Buchholz score, the sum of the points of every opponent played, breaking ties
+    pub buchholz: u32,
+}
+
+/// A tournament in progress
+/// # Examples
+/// ```
+/// # use naipe::games::tournament::Tournament;
+/// let names = ["always hit", "always stand", "basic strategy"];
+/// let mut tournament = Tournament::round_robin(names.iter().map(ToString::to_string)).unwrap();
+/// while let Some(pairing) = tournament.next_match() {
+///     // The later entrant always wins
+///     let winner = pairing.entrants.into_iter().max();
+///     tournament.record(winner).unwrap();
+/// }
+/// assert!(tournament.is_over());
+/// assert_eq!(tournament.results().len(), 3);
+///
+/// let leader = tournament.standings()[0];
+/// assert_eq!(tournament.entrant(leader.entrant), Some("basic strategy"));
+/// assert_eq!(leader.wins, 2);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SavedTournament"))]
+pub struct Tournament {
+    format: TournamentFormat,
+    entrants: Vec<String>,
+    schedule: Vec<Pairing>,
+    results: Vec<MatchRecord>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    byes: Vec<Bye>,
+}
+
+impl Tournament {
+    /// Creates a round robin tournament, in which every entrant plays every other once
+    ///
+    /// With an odd number of entrants, one entrant sits out each round
+    /// # Errors
+    /// [`TournamentError::TooFewEntrants`] if there are fewer than two entrants
+    pub fn round_robin<I: IntoIterator<Item = String>>(
+        entrants: I,
+    ) -> Result<Tournament, TournamentError> {
+        let entrants: Vec<String> = entrants.into_iter().collect();
+        if entrants.len() < 2 {
+            return Err(TournamentError::TooFewEntrants(entrants.len()));
+        }
+        let schedule = round_robin_schedule(entrants.len());
+        Ok(Tournament {
+            format: TournamentFormat::RoundRobin,
+            entrants,
+            schedule,
+            results: vec![],
+            byes: vec![],
+        })
+    }
+
+    /// Creates a Swiss system tournament, lasting the given number of rounds
+    ///
+    /// Each round is paired once the previous round is over, matching entrants on the same or
+    /// similar points and avoiding rematches where possible. With an odd number of entrants, the
+    /// lowest ranked entrant yet to sit out gets a bye.
+    /// # Examples
+    /// ```
+    /// # use std::num::NonZeroUsize;
+    /// # use naipe::games::tournament::Tournament;
+    /// let entrants = (1..=8).map(|entrant| format!("bot {entrant}"));
+    /// let rounds = NonZeroUsize::new(3).unwrap();
+    /// let mut tournament = Tournament::swiss(entrants, rounds).unwrap();
+    /// assert_eq!(tournament.schedule().len(), 4);
+    /// while let Some(pairing) = tournament.next_match() {
+    ///     // The earlier entrant always wins
+    ///     let winner = pairing.entrants.into_iter().min();
+    ///     tournament.record(winner).unwrap();
+    /// }
+    /// assert_eq!(tournament.results().len(), 12);
+    ///
+    /// // Only one entrant wins every round, and no pair meets twice
+    /// let standings = tournament.standings();
+    /// assert_eq!((standings[0].entrant, standings[0].wins), (0, 3));
+    /// assert_eq!(standings[1].wins, 2);
+    /// let mut pairs: Vec<[usize; 2]> = tournament.schedule().iter().map(|pairing| {
+    ///     let mut entrants = pairing.entrants;
+    ///     entrants.sort();
+    ///     entrants
+    /// }).collect();
+    /// pairs.sort();
+    /// pairs.dedup();
+    /// assert_eq!(pairs.len(), 12);
+    /// ```
+    /// # Errors
+    /// [`TournamentError::TooFewEntrants`] if there are fewer than two entrants
+    pub fn swiss<I: IntoIterator<Item = String>>(
+        entrants: I,
+        rounds: NonZeroUsize,
+    ) -> Result<Tournament, TournamentError> {
+        let entrants: Vec<String> = entrants.into_iter().collect();
+        if entrants.len() < 2 {
+            return Err(TournamentError::TooFewEntrants(entrants.len()));
+        }
+        let mut tournament = Tournament {
+            format: TournamentFormat::Swiss { rounds },
+            entrants,
+            schedule: vec![],
+            results: vec![],
+            byes: vec![],
+        };
+        tournament.pair_swiss_round(0);
+        Ok(tournament)
+    }
+
+    /// Gets how entrants are paired
+    pub fn format(&self) -> TournamentFormat {
+        self.format
+    }
+
+    /// Gets the name of an entrant
+    pub fn entrant(&self, entrant: usize) -> Option<&str> {
+        self.entrants.get(entrant).map(String::as_str)
+    }
+
+    /// Gets the names of every entrant, in the order they entered
+    pub fn entrants(&self) -> &[String] {
+        &self.entrants
+    }
+
+    /// Gets every match scheduled so far, played or not
+    ///
+    /// A Swiss tournament only schedules each round once the previous round is over
+    pub fn schedule(&self) -> &[Pairing] {
+        &self.schedule
+    }
+
+    /// Gets every match played so far, in order
+    pub fn results(&self) -> &[MatchRecord] {
+        &self.results
+    }
+
+    /// Gets every bye given so far
+    pub fn byes(&self) -> &[Bye] {
+        &self.byes
+    }
+
+    /// Gets the next match to play, or `None` once the tournament is over
+    pub fn next_match(&self) -> Option<Pairing> {
+        self.schedule.get(self.results.len()).copied()
+    }
+
+    /// Gets the round being played, counting from zero
+    pub fn current_round(&self) -> Option<usize> {
+        self.next_match().map(|pairing| pairing.round)
+    }
+
+    /// Finds if every match has been played
+    pub fn is_over(&self) -> bool {
+        self.next_match().is_none()
+    }
+
+    /// Records the result of the next match, with the winning entrant or `None` for a draw
+    /// # Errors
+    /// [`TournamentError::TournamentOver`] if every match has been played, or
+    /// [`TournamentError::NotInMatch`] if the winner is not one of the match's entrants
+    pub fn record(&mut self, winner: Option<usize>) -> Result<(), TournamentError> {
+        let pairing = self.next_match().ok_or(TournamentError::TournamentOver)?;
+        if let Some(entrant) = winner {
+            if !pairing.entrants.contains(&entrant) {
+                return Err(TournamentError::NotInMatch(entrant));
+            }
+        }
+        self.results.push(MatchRecord { pairing, winner });
+        if let TournamentFormat::Swiss { rounds } = self.format {
+            let next_round = pairing.round + 1;
+            if self.is_over() && next_round < rounds.get() {
+                self.pair_swiss_round(next_round);
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets every entrant's standing, best first
+    ///
+    /// Entrants on equal points are ranked by their Buchholz score, then in entry order
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = (0..self.entrants.len())
+            .map(|entrant| Standing {
+                entrant,
+                points: 0,
+                wins: 0,
+                draws: 0,
+                losses: 0,
+                byes: 0,
+                buchholz: 0,
+            })
+            .collect();
+        for bye in &self.byes {
+            let standing = &mut standings[bye.entrant];
+            standing.byes += 1;
+            standing.points += WIN_POINTS;
+        }
+        for record in &self.results {
+            for entrant in record.pairing.entrants {
+                let standing = &mut standings[entrant];
+                match record.winner {
+                    None => {
+                        standing.draws += 1;
+                        standing.points += DRAW_POINTS;
+                    }
+                    Some(winner) if winner == entrant => {
+                        standing.wins += 1;
+                        standing.points += WIN_POINTS;
+                    }
+                    Some(_) => standing.losses += 1,
+                }
+            }
+        }
+        for record in &self.results {
+            let [first, second] = record.pairing.entrants;
+            standings[first].buchholz += standings[second].points;
+            standings[second].buchholz += standings[first].points;
+        }
+        standings.sort_by_key(|standing| (Reverse(standing.points), Reverse(standing.buchholz)));
+        standings
+    }
+
+    /// Schedules a round of a Swiss tournament from the current standings
+    fn pair_swiss_round(&mut self, round: usize) {
+        let mut ranked: Vec<usize> = self
+            .standings()
+            .iter()
+            .map(|standing| standing.entrant)
+            .collect();
+        if ranked.len() % 2 == 1 {
+            let had_bye: HashSet<usize> = self.byes.iter().map(|bye| bye.entrant).collect();
+            let sitting_out = ranked
+                .iter()
+                .rposition(|entrant| !had_bye.contains(entrant))
+                .unwrap_or(ranked.len() - 1);
+            let entrant = ranked.remove(sitting_out);
+            self.byes.push(Bye { round, entrant });
+        }
+        let played: HashSet<[usize; 2]> = self
+            .results
+            .iter()
+            .flat_map(|record| {
+                let [first, second] = record.pairing.entrants;
+                [[first, second], [second, first]]
+            })
+            .collect();
+        // Rematches are only allowed once every entrant has played every other
+        let pairs = pair_without_rematches(&ranked, &played).unwrap_or_else(|| {
+            ranked
+                .chunks_exact(2)
+                .map(|pair| [pair[0], pair[1]])
+                .collect()
+        });
+        self.schedule.extend(
+            pairs
+                .into_iter()
+                .map(|entrants| Pairing { round, entrants }),
+        );
+    }
+}
+
+/// A tournament as saved, checked before it is loaded
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SavedTournament {
+    format: TournamentFormat,
+    entrants: Vec<String>,
+    schedule: Vec<Pairing>,
+    results: Vec<MatchRecord>,
+    #[serde(default)]
+    byes: Vec<Bye>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SavedTournament> for Tournament {
+    type Error = TournamentError;
+
+    /// Loads a saved tournament, refusing one naming an entrant who did not enter, or with a
+    /// result that does not match the match scheduled
+    fn try_from(saved: SavedTournament) -> Result<Tournament, TournamentError> {
+        let entrants = saved.entrants.len();
+        if entrants < 2 {
+            return Err(TournamentError::TooFewEntrants(entrants));
+        }
+        let unknown = saved
+            .schedule
+            .iter()
+            .flat_map(|pairing| pairing.entrants)
+            .chain(saved.byes.iter().map(|bye| bye.entrant))
+            .find(|entrant| *entrant >= entrants);
+        if let Some(entrant) = unknown {
+            return Err(TournamentError::UnknownEntrant(entrant));
+        }
+        for (index, record) in saved.results.iter().enumerate() {
+            if saved.schedule.get(index) != Some(&record.pairing) {
+                return Err(TournamentError::NotScheduled(index));
+            }
+            if let Some(winner) = record.winner {
+                if !record.pairing.entrants.contains(&winner) {
+                    return Err(TournamentError::NotInMatch(winner));
+                }
+            }
+        }
+        Ok(Tournament {
+            format: saved.format,
+            entrants: saved.entrants,
+            schedule: saved.schedule,
+            results: saved.results,
+            byes: saved.byes,
+        })
+    }
+}
+
+/// Pairs ranked entrants, each with the highest ranked entrant below them they have not played,
+/// backtracking when the lower ranks cannot all be paired
+fn pair_without_rematches(
+    ranked: &[usize],
+    played: &HashSet<[usize; 2]>,
+) -> Option<Vec<[usize; 2]>> {
+    let Some((&first, rest)) = ranked.split_first() else {
+        return Some(vec![]);
+    };
+    rest.iter()
+        .enumerate()
+        .filter(|(_, &second)| !played.contains(&[first, second]))
+        .find_map(|(index, &second)| {
+            let mut remaining = rest.to_vec();
+            remaining.remove(index);
+            let mut pairs = pair_without_rematches(&remaining, played)?;
+            pairs.insert(0, [first, second]);
+            Some(pairs)
+        })
+}
+
+/// Schedules every pairing of the entrants with the circle method, so each plays once a round
+fn round_robin_schedule(entrants: usize) -> Vec<Pairing> {
+    // An odd field gets an empty slot, and whoever is paired with it sits out
+    let slots = entrants + entrants % 2;
+    let mut circle: Vec<usize> = (0..slots).collect();
+    let mut schedule = vec![];
+    for round in 0..slots - 1 {
+        for i in 0..slots / 2 {
+            let (first, second) = (circle[i], circle[slots - 1 - i]);
+            if first < entrants && second < entrants {
+                schedule.push(Pairing {
+                    round,
+                    entrants: [first, second],
+                });
+            }
+        }
+        // The first slot stays put while the rest rotate
+        circle[1..].rotate_right(1);
+    }
+    schedule
+}
+
+/// Errors related to running a tournament
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TournamentError {
+    #[error("A tournament needs at least two entrants, not {0}")]
+    TooFewEntrants(usize),
+    #[error("Entrant {0} is not playing in this match")]
+    NotInMatch(usize),
+    #[error("Every match has already been played")]
+    TournamentOver,
+    #[error("No entrant {0} entered the tournament")]
+    UnknownEntrant(usize),
+    #[error("The result of match {0} is not for the match scheduled")]
+    NotScheduled(usize),
+}
@@ -0,0 +1,559 @@
+//! A reusable engine for trick-taking games
+//!
+//! A [`Trick`] tracks the cards played to it, checks each play against the [`TrickRules`],
+//! and finds the winner once every seat has played. Concrete games such as Hearts, Spades,
+//! or Whist layer their own leading restrictions and scoring on top, with the rules'
+//! [`Objective`] saying whether tricks and points are sought or avoided.
+
+use std::cmp::Ordering;
+
+use thiserror::Error;
+
+use crate::common::{
+    card::{Card, Suit},
+    hand::Hand,
+    inference::VoidTracker,
+    order::RankOrder,
+};
+use crate::games::penalty::{Violation, ViolationKind};
+
+/// Which suit, if any, beats every other suit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum TrumpRule {
+    /// Only cards of the suit led can win the trick
+    NoTrump,
+    /// Any card of the suit beats every card of other suits
+    Suit(Suit),
+}
+
+impl TrumpRule {
+    /// Gets the trump suit, if there is one
+    pub fn suit(&self) -> Option<Suit> {
+        match self {
+            TrumpRule::NoTrump => None,
+            TrumpRule::Suit(suit) => Some(*suit),
+        }
+    }
+}
+
+/// Which cards a player may play when not leading
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum FollowRule {
+    /// Players must follow the suit led if able, and may play anything otherwise
+    MustFollow,
+    /// Players must follow the suit led if able, and must trump if they cannot
+    MustFollowOrTrump,
+    /// Players may play any card
+    Free,
+}
+
+/// Whether players want to take tricks and points, or to avoid them
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Objective {
+    /// The most tricks or points is best, as in Whist or Spades
+    #[default]
+    Take,
+    /// The fewest tricks or points is best, as in Hearts, null games in Skat, or misère bids
+    Avoid,
+}
+
+impl Objective {
+    /// Finds if the objective is to avoid tricks or points
+    pub fn is_misere(&self) -> bool {
+        *self == Objective::Avoid
+    }
+
+    /// Compares two totals of tricks or points, with the better total for its holder greater
+    /// # Examples
+    /// ```
+    /// # use naipe::games::tricks::Objective;
+    /// use std::cmp::Ordering;
+    /// assert_eq!(Objective::Take.compare(3, 5), Ordering::Less);
+    /// assert_eq!(Objective::Avoid.compare(3, 5), Ordering::Greater);
+    /// ```
+    pub fn compare<T: Ord + Copy>(&self, total: T, other: T) -> Ordering {
+        match self {
+            Objective::Take => total.cmp(&other),
+            Objective::Avoid => other.cmp(&total),
+        }
+    }
+
+    /// Gets every seat with the best total, in seat order
+    /// # Examples
+    /// ```
+    /// # use naipe::games::tricks::Objective;
+    /// assert_eq!(Objective::Take.leaders(&[4, 9, 9, 2]), vec![1, 2]);
+    /// assert_eq!(Objective::Avoid.leaders(&[4, 9, 9, 2]), vec![3]);
+    /// assert!(Objective::Avoid.leaders::<u32>(&[]).is_empty());
+    /// ```
+    pub fn leaders<T: Ord + Copy>(&self, totals: &[T]) -> Vec<usize> {
+        let Some(best) = totals
+            .iter()
+            .copied()
+            .max_by(|total, other| self.compare(*total, *other))
+        else {
+            return vec![];
+        };
+        (0..totals.len())
+            .filter(|seat| totals[*seat] == best)
+            .collect()
+    }
+
+    /// Gets the seat with the best total, unless it is tied
+    pub fn winner<T: Ord + Copy>(&self, totals: &[T]) -> Option<usize> {
+        match self.leaders(totals).as_slice() {
+            [seat] => Some(*seat),
+            _ => None,
+        }
+    }
+}
+
+/// The rules shared by every trick of a game
+/// # Examples
+/// ```
+/// # use naipe::common::card::Suit;
+/// # use naipe::games::tricks::{FollowRule, TrickRules, TrumpRule};
+/// let spades = TrickRules {
+///     trump: TrumpRule::Suit(Suit::Spade),
+///     ..TrickRules::default()
+/// };
+/// assert_eq!(spades.follow, FollowRule::MustFollow);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrickRules {
+    pub trump: TrumpRule,
+    pub follow: FollowRule,
+    /// How ranks compare within a suit
+    pub ranks: RankOrder,
+    /// Whether tricks are wanted or not
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub objective: Objective,
+}
+
+impl Default for TrickRules {
+    /// Creates rules with no trump, following suit when able, aces high, and tricks wanted
+    fn default() -> TrickRules {
+        TrickRules {
+            trump: TrumpRule::NoTrump,
+            follow: FollowRule::MustFollow,
+            ranks: RankOrder::ace_high(),
+            objective: Objective::Take,
+        }
+    }
+}
+
+/// A single trick, with one card played by each seat in turn
+/// # Examples
+/// ```
+/// # use naipe::common::hand::Hand;
+/// # use naipe::games::tricks::{Trick, TrickRules};
+/// let rules = TrickRules::default();
+/// let mut hands = [
+///     Hand::from_notation("KH 2C").unwrap(),
+///     Hand::from_notation("AH 3S").unwrap(),
+///     Hand::from_notation("4D 5D").unwrap(),
+/// ];
+/// let mut trick = Trick::new(0, 3);
+/// trick.play(0, "KH".parse().unwrap(), &mut hands[0], &rules).unwrap();
+///
+/// // Seat 1 holds a heart, so must follow suit
+/// assert_eq!(trick.legal_plays(&hands[1], &rules), vec!["AH".parse().unwrap()]);
+/// assert!(trick.play(1, "3S".parse().unwrap(), &mut hands[1], &rules).is_err());
+/// trick.play(1, "AH".parse().unwrap(), &mut hands[1], &rules).unwrap();
+/// trick.play(2, "5D".parse().unwrap(), &mut hands[2], &rules).unwrap();
+///
+/// assert!(trick.is_complete());
+/// assert_eq!(trick.winner(&rules), Some(1));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trick {
+    leader: usize,
+    seats: usize,
+    plays: Vec<(usize, Card)>,
+}
+
+impl Trick {
+    /// Creates an empty trick, led by the given seat, between the given number of seats
+    pub fn new(leader: usize, seats: usize) -> Trick {
+        Trick {
+            leader,
+            seats,
+            plays: vec![],
+        }
+    }
+
+    /// Gets the seat leading this trick
+    pub fn leader(&self) -> usize {
+        self.leader
+    }
+
+    /// Gets the suit led, once a card has been played
+    pub fn led_suit(&self) -> Option<Suit> {
+        self.plays.first().map(|(_, card)| card.get_suit())
+    }
+
+    /// Gets each seat and the card they played, in the order played
+    pub fn plays(&self) -> &[(usize, Card)] {
+        &self.plays
+    }
+
+    /// Gets the cards played to this trick, in the order played
+    pub fn cards(&self) -> Vec<Card> {
+        self.plays.iter().map(|(_, card)| *card).collect()
+    }
+
+    /// Finds if every seat has played to this trick
+    pub fn is_complete(&self) -> bool {
+        self.plays.len() >= self.seats
+    }
+
+    /// Gets the seat due to play next, unless the trick is complete
+    pub fn next_seat(&self) -> Option<usize> {
+        (!self.is_complete()).then(|| (self.leader + self.plays.len()) % self.seats)
+    }
+
+    /// Gets every card in the hand that may be played to this trick under the rules
+    pub fn legal_plays(&self, hand: &Hand, rules: &TrickRules) -> Vec<Card> {
+        let Some(led) = self.led_suit() else {
+            return hand.iter().copied().collect();
+        };
+        let of_suit = |suit: Suit| -> Vec<Card> {
+            hand.iter()
+                .copied()
+                .filter(|card| card.get_suit() == suit)
+                .collect()
+        };
+        let following = of_suit(led);
+        match rules.follow {
+            FollowRule::Free => hand.iter().copied().collect(),
+            _ if !following.is_empty() => following,
+            FollowRule::MustFollowOrTrump => {
+                let trumps = rules.trump.suit().map(of_suit).unwrap_or_default();
+                if trumps.is_empty() {
+                    hand.iter().copied().collect()
+                } else {
+                    trumps
+                }
+            }
+            FollowRule::MustFollow => hand.iter().copied().collect(),
+        }
+    }
+
+    /// Checks that the seat may play the card from their hand to this trick
+    /// # Errors
+    /// A [`Violation`] of kind [`ViolationKind::OutOfTurn`] if it is not the seat's turn,
+    /// [`ViolationKind::IllegalCard`] if the seat does not hold the card, or
+    /// [`ViolationKind::Renege`] if the card breaks the follow rule
+    pub fn check_play(
+        &self,
+        seat: usize,
+        card: Card,
+        hand: &Hand,
+        rules: &TrickRules,
+    ) -> Result<(), Violation> {
+        if self.next_seat() != Some(seat) {
+            return Err(Violation::with_card(seat, ViolationKind::OutOfTurn, card));
+        }
+        if !hand.contains(card) {
+            return Err(Violation::with_card(seat, ViolationKind::IllegalCard, card));
+        }
+        if !self.legal_plays(hand, rules).contains(&card) {
+            return Err(Violation::with_card(seat, ViolationKind::Renege, card));
+        }
+        Ok(())
+    }
+
+    /// Plays a card from the seat's hand to this trick
+    /// # Errors
+    /// [`TrickError::Complete`] if every seat has already played, or
+    /// [`TrickError::Illegal`] if the play is not allowed, as for [`Trick::check_play`],
+    /// in which case the hand is left unchanged
+    pub fn play(
+        &mut self,
+        seat: usize,
+        card: Card,
+        hand: &mut Hand,
+        rules: &TrickRules,
+    ) -> Result<(), TrickError> {
+        if self.is_complete() {
+            return Err(TrickError::Complete);
+        }
+        self.check_play(seat, card, hand, rules)?;
+        hand.remove(card);
+        self.plays.push((seat, card));
+        Ok(())
+    }
+
+    /// Plays a card as for [`Trick::play`], recording in the tracker when the seat shows out of
+    /// the suit led
+    /// # Errors
+    /// As for [`Trick::play`], in which case nothing is recorded
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Suit;
+    /// # use naipe::common::hand::Hand;
+    /// # use naipe::common::inference::VoidTracker;
+    /// # use naipe::games::tricks::{Trick, TrickRules};
+    /// let rules = TrickRules::default();
+    /// let mut voids = VoidTracker::new(2);
+    /// let mut hands = [Hand::from_notation("KH").unwrap(), Hand::from_notation("2C").unwrap()];
+    /// let mut trick = Trick::new(0, 2);
+    /// trick.play_tracked(0, "KH".parse().unwrap(), &mut hands[0], &rules, &mut voids).unwrap();
+    /// trick.play_tracked(1, "2C".parse().unwrap(), &mut hands[1], &rules, &mut voids).unwrap();
+    /// assert_eq!(voids.void_seats(Suit::Heart), vec![1]);
+    /// assert!(voids.voids(0).is_empty());
+    /// ```
+    pub fn play_tracked(
+        &mut self,
+        seat: usize,
+        card: Card,
+        hand: &mut Hand,
+        rules: &TrickRules,
+        voids: &mut VoidTracker,
+    ) -> Result<(), TrickError> {
+        let led = self.led_suit();
+        self.play(seat, card, hand, rules)?;
+        if let Some(led) = led {
+            voids.record_play(seat, led, card);
+        }
+        Ok(())
+    }
+
+    /// Gets the seat currently winning the trick, once a card has been played
+    pub fn winning_seat(&self, rules: &TrickRules) -> Option<usize> {
+        let led = self.led_suit()?;
+        let trump = rules.trump.suit();
+        let strength = |card: &Card| {
+            let suit_class = if Some(card.get_suit()) == trump {
+                2
+            } else {
+                u8::from(card.get_suit() == led)
+            };
+            (suit_class, card.get_rank())
+        };
+        self.plays
+            .iter()
+            .copied()
+            .reduce(|best, play| {
+                let (best_class, best_rank) = strength(&best.1);
+                let (class, rank) = strength(&play.1);
+                let ordering = class
+                    .cmp(&best_class)
+                    .then_with(|| rules.ranks.compare(rank, best_rank));
+                if ordering == Ordering::Greater {
+                    play
+                } else {
+                    best
+                }
+            })
+            .map(|(seat, _)| seat)
+    }
+
+    /// Finds if the seat would be winning the trick after playing the card, such as to find
+    /// plays that duck under a misère [`Objective`]
+    /// # Examples
+    /// ```
+    /// # use naipe::common::hand::Hand;
+    /// # use naipe::games::tricks::{Objective, Trick, TrickRules};
+    /// let rules = TrickRules { objective: Objective::Avoid, ..TrickRules::default() };
+    /// let mut hand = Hand::from_notation("9H").unwrap();
+    /// let mut trick = Trick::new(0, 2);
+    /// trick.play(0, "9H".parse().unwrap(), &mut hand, &rules).unwrap();
+    ///
+    /// let hand = Hand::from_notation("KH 2H").unwrap();
+    /// let ducks: Vec<_> = trick
+    ///     .legal_plays(&hand, &rules)
+    ///     .into_iter()
+    ///     .filter(|card| !trick.would_win(1, *card, &rules))
+    ///     .collect();
+    /// assert_eq!(ducks, vec!["2H".parse().unwrap()]);
+    /// ```
+    pub fn would_win(&self, seat: usize, card: Card, rules: &TrickRules) -> bool {
+        let mut trick = self.clone();
+        trick.plays.push((seat, card));
+        trick.winning_seat(rules) == Some(seat)
+    }
+
+    /// Gets the seat winning the trick, once every seat has played
+    pub fn winner(&self, rules: &TrickRules) -> Option<usize> {
+        if self.is_complete() {
+            self.winning_seat(rules)
+        } else {
+            None
+        }
+    }
+}
+
+/// A claim about how many of the remaining tricks a seat will take
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum TrickClaim {
+    /// The seat takes at least this many of the remaining tricks
+    AtLeast(usize),
+    /// The seat takes no more than this many of the remaining tricks
+    AtMost(usize),
+}
+
+/// Trick play that can be played out card by card, to check a [`TrickClaim`]
+///
+/// Games with their own restrictions on leading or following give them through
+/// [`TrickPlayout::playable`], so claims are checked under the game's full rules
+pub trait TrickPlayout: Clone {
+    /// Gets the trick in progress
+    fn current_trick(&self) -> &Trick;
+
+    /// Gets the number of tricks left to play, including the trick in progress
+    fn tricks_left(&self) -> usize;
+
+    /// Gets every card the seat due to play may play now, which is empty once play is over
+    fn playable(&self) -> Vec<Card>;
+
+    /// Plays a card for the seat due to play, giving the seat that took the trick if the card
+    /// completed it
+    fn play_out(&mut self, card: Card) -> Option<usize>;
+}
+
+/// The rest of a deal played under only the [`TrickRules`], as a [`TrickPlayout`]
+/// # Examples
+/// ```
+/// # use naipe::common::hand::Hand;
+/// # use naipe::games::tricks::{check_claim, Trick, TrickClaim, TrickDeal, TrickRules};
+/// let hands = vec![
+///     Hand::from_notation("AS KS").unwrap(),
+///     Hand::from_notation("QS 2H").unwrap(),
+///     Hand::from_notation("JS 3H").unwrap(),
+/// ];
+/// let deal = TrickDeal::new(hands, Trick::new(0, 3), TrickRules::default());
+///
+/// // Leading from the top, seat 0 takes both tricks however the others play
+/// assert_eq!(check_claim(&deal, 0, TrickClaim::AtLeast(2), 1_000), Some(true));
+/// assert_eq!(check_claim(&deal, 1, TrickClaim::AtLeast(1), 1_000), Some(false));
+/// assert_eq!(check_claim(&deal, 1, TrickClaim::AtMost(0), 1_000), Some(true));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrickDeal {
+    hands: Vec<Hand>,
+    trick: Trick,
+    rules: TrickRules,
+}
+
+impl TrickDeal {
+    /// Creates the rest of a deal from each seat's hand and the trick in progress
+    pub fn new(hands: Vec<Hand>, trick: Trick, rules: TrickRules) -> TrickDeal {
+        TrickDeal {
+            hands,
+            trick,
+            rules,
+        }
+    }
+}
+
+impl TrickPlayout for TrickDeal {
+    fn current_trick(&self) -> &Trick {
+        &self.trick
+    }
+
+    fn tricks_left(&self) -> usize {
+        self.trick
+            .next_seat()
+            .and_then(|seat| self.hands.get(seat))
+            .map_or(0, Hand::len)
+    }
+
+    fn playable(&self) -> Vec<Card> {
+        self.trick
+            .next_seat()
+            .and_then(|seat| self.hands.get(seat))
+            .map(|hand| self.trick.legal_plays(hand, &self.rules))
+            .unwrap_or_default()
+    }
+
+    fn play_out(&mut self, card: Card) -> Option<usize> {
+        let seat = self.trick.next_seat()?;
+        self.trick
+            .play(seat, card, self.hands.get_mut(seat)?, &self.rules)
+            .ok()?;
+        let winner = self.trick.winner(&self.rules)?;
+        self.trick = Trick::new(winner, self.trick.seats);
+        Some(winner)
+    }
+}
+
+/// Checks a claim by playing out every way the rest of the deal could go, with the claiming seat
+/// choosing its plays and every other seat defending against the claim
+///
+/// Gives `Some(true)` if the claim holds however the other seats play, `Some(false)` if some
+/// defence defeats it, or `None` if more than the given number of positions would need checking
+pub fn check_claim<P: TrickPlayout>(
+    playout: &P,
+    seat: usize,
+    claim: TrickClaim,
+    max_positions: u64,
+) -> Option<bool> {
+    let mut positions = 0;
+    claim_holds(playout, seat, claim, 0, &mut positions, max_positions)
+}
+
+/// Searches the rest of play for [`check_claim`], given the tricks the seat has taken so far
+fn claim_holds<P: TrickPlayout>(
+    playout: &P,
+    seat: usize,
+    claim: TrickClaim,
+    taken: usize,
+    positions: &mut u64,
+    max_positions: u64,
+) -> Option<bool> {
+    let most = taken + playout.tricks_left();
+    match claim {
+        TrickClaim::AtLeast(tricks) if taken >= tricks => return Some(true),
+        TrickClaim::AtLeast(tricks) if most < tricks => return Some(false),
+        TrickClaim::AtMost(tricks) if taken > tricks => return Some(false),
+        TrickClaim::AtMost(tricks) if most <= tricks => return Some(true),
+        _ => {}
+    }
+    let plays = playout.playable();
+    if plays.is_empty() {
+        return Some(matches!(claim, TrickClaim::AtMost(_)));
+    }
+    if *positions >= max_positions {
+        return None;
+    }
+    *positions += 1;
+    let claiming = playout.current_trick().next_seat() == Some(seat);
+    let mut undecided = false;
+    for card in plays {
+        let mut next = playout.clone();
+        let taken = taken + usize::from(next.play_out(card) == Some(seat));
+        match claim_holds(&next, seat, claim, taken, positions, max_positions) {
+            Some(holds) if holds == claiming => return Some(holds),
+            None => undecided = true,
+            Some(_) => {}
+        }
+    }
+    (!undecided).then_some(!claiming)
+}
+
+/// Errors related to playing to a trick
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TrickError {
+    #[error("Every seat has already played to the trick")]
+    Complete,
+    #[error("Illegal play: {0}")]
+    Illegal(Violation),
+}
+
+impl From<Violation> for TrickError {
+    fn from(violation: Violation) -> TrickError {
+        TrickError::Illegal(violation)
+    }
+}
@@ -1,22 +1,166 @@
 //! An implementation of the card game War
 
-use std::cmp::Ordering;
+use std::{cmp::Ordering, iter, marker::PhantomData};
 
 use log::debug;
+use rand::{Rng, SeedableRng};
+use thiserror::Error;
 
-use crate::common::{card::Suitless, deck::Deck, hand::Hand};
-use crate::games::Game;
+use crate::common::{
+    card::{Card, Rank, Suitless},
+    deck::Deck,
+    hand::Hand,
+    inference::{Reveal, Revealing},
+    pile::CardPile,
+};
+use crate::games::{
+    agent::{Agent, AgentGame},
+    audit::{AuditedGame, RandomnessRecord},
+    dynamic::ErasedGame,
+    event::EventSource,
+    info::{GameInfo, GameMetadata, GameTag},
+    Concede, ConcedeError, Game, GameOutcome, GameRng, SeededGame,
+};
 
-/// Game state for the game of War
-#[derive(Clone, Debug)]
-pub struct WarGame {
-    player_1_hand: Hand,
-    player_2_hand: Hand,
+/// The most shuffles tried to find a lucky deal, before settling for the last one
+const LUCKY_SHUFFLE_LIMIT: usize = 100;
+
+/// The seed reshuffles are drawn from in games saved before the generator was saved with them
+#[cfg(feature = "serde")]
+const LEGACY_SEED: u64 = 0;
+
+/// Creates the generator for a game saved without one
+#[cfg(feature = "serde")]
+fn legacy_rng() -> GameRng {
+    GameRng::seed_from_u64(LEGACY_SEED)
+}
+
+/// A game of War, played with a [`Vec`] for each hand
+///
+/// Hands are only played from and added to at the top, which a [`Vec`] does fastest, as
+/// `cargo bench --bench piles` shows
+pub type WarGame = War<Vec<Card>>;
+
+/// Game state for the game of War, keeping each player's hand in a [`CardPile`] of type `P`
+/// # Examples
+/// ```
+/// # use naipe::common::card::Card;
+/// # use naipe::games::war::{War, WarGame};
+/// # use naipe::games::{Game, SeededGame};
+/// use std::collections::VecDeque;
+/// // The backing of the hands has no effect on play
+/// let mut game = WarGame::from_seed(8);
+/// let mut queued = War::<VecDeque<Card>>::from_seed(8);
+/// while !game.tick().unwrap() {
+///     assert_eq!(queued.tick(), Ok(false));
+/// }
+/// assert_eq!(queued.cards(0), game.cards(0));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::struct_field_names)]
+pub struct War<P> {
+    player_1_hand: P,
+    player_2_hand: P,
     player_1_capture: Deck,
     player_2_capture: Deck,
+    #[cfg_attr(feature = "serde", serde(default = "legacy_rng"))]
+    rng: GameRng,
+    /// Events are only pending until the next tick, so they are never saved
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<WarEvent>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    audit: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    lucky_deal: Option<LuckyDeal>,
+    /// Whether the game has ticked, after which the deal's events are no longer pending
+    #[cfg_attr(feature = "serde", serde(default))]
+    ticked: bool,
 }
 
-impl WarGame {
+/// Something that happened during a tick of War
+///
+/// Player 1 is player 0, and player 2 is player 1
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum WarEvent {
+    /// A player played a card from their hand, face up or face down
+    CardPlayed {
+        player: usize,
+        card: Card,
+        face_up: bool,
+    },
+    /// Both players played the same rank, starting a war
+    WarStarted { rank: Rank },
+    /// A player won the cards played
+    PileCaptured { player: usize, cards: Vec<Card> },
+    /// A player ran out of cards in hand, and shuffled their captured cards into a new hand
+    Reshuffle { player: usize, cards: usize },
+    /// A player has all the cards, winning the game
+    GameOver { winner: usize },
+    /// In audit mode, the cards were shuffled, for a deal or a reshuffle
+    Randomness(RandomnessRecord),
+    /// The deal was biased in a player's favour
+    LuckyDeal(LuckyDeal),
+}
+
+/// A deal reshuffled in a player's favour, kept with the game so the bias is never hidden
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LuckyDeal {
+    /// The player favoured
+    pub player: usize,
+    /// The number of aces the player was promised
+    pub aces: usize,
+    /// How many times the deck was shuffled to find the deal
+    pub shuffles: usize,
+    /// Whether the promise was kept, rather than giving up after too many shuffles
+    pub kept: bool,
+}
+
+impl<P: CardPile + Clone + Default> War<P> {
+    /// Creates a builder for a game with an asymmetric start
+    pub fn builder() -> WarGameBuilder<P> {
+        WarGameBuilder::default()
+    }
+
+    /// Sets whether every reshuffle is recorded as a [`WarEvent::Randomness`] event
+    ///
+    /// Auditing draws the same random numbers, so play is unchanged
+    /// # Examples
+    /// ```
+    /// # use naipe::games::war::{WarEvent, WarGame};
+    /// # use naipe::games::{event::EventSource, Game, SeededGame};
+    /// let mut game = WarGame::from_seed(5);
+    /// let mut audited = WarGame::from_seed(5);
+    /// audited.set_audit(true);
+    /// let mut records = vec![];
+    /// while !game.tick().unwrap() {
+    ///     audited.tick().unwrap();
+    ///     records.extend(audited.events().iter().filter_map(|event| match event {
+    ///         WarEvent::Randomness(record) => Some(record.clone()),
+    ///         _ => None,
+    ///     }));
+    /// }
+    /// assert_eq!(audited.cards(0), game.cards(0));
+    /// assert!(!records.is_empty());
+    /// assert!(records.iter().all(|record| record.verify()));
+    /// ```
+    pub fn set_audit(&mut self, audit: bool) {
+        self.audit = audit;
+    }
+
+    /// Finds if every reshuffle is recorded
+    pub fn is_audited(&self) -> bool {
+        self.audit
+    }
+
+    /// Gets how the deal was biased, if it was
+    pub fn lucky_deal(&self) -> Option<&LuckyDeal> {
+        self.lucky_deal.as_ref()
+    }
+
     /// A convenience function to specify if the game is won by player 1
     pub fn player_1_won(&self) -> bool {
         self.player_2_hand.is_empty() && self.player_2_capture.is_empty()
@@ -26,6 +170,36 @@ impl WarGame {
         self.player_1_hand.is_empty() && self.player_1_capture.is_empty()
     }
 
+    /// Gets every card held by the player, with player 1 as player 0 and player 2 as player 1
+    ///
+    /// Captured cards are placed beneath the hand, so a game can be saved and resumed with
+    /// [`WarGameBuilder::hands`]
+    /// # Examples
+    /// ```
+    /// # use naipe::games::war::WarGame;
+    /// let game = WarGame::default();
+    /// let resumed = WarGame::builder()
+    ///     .hands(game.cards(0).unwrap(), game.cards(1).unwrap())
+    ///     .build_from_seed(7)
+    ///     .unwrap();
+    /// assert_eq!(resumed.cards(0), game.cards(0));
+    /// assert!(game.cards(2).is_none());
+    /// ```
+    pub fn cards(&self, player: usize) -> Option<Hand> {
+        let (hand, capture) = match player {
+            0 => (&self.player_1_hand, &self.player_1_capture),
+            1 => (&self.player_2_hand, &self.player_2_capture),
+            _ => return None,
+        };
+        Some(
+            capture
+                .clone()
+                .into_iter()
+                .chain(take_cards(&mut hand.clone()))
+                .collect(),
+        )
+    }
+
     fn player_1_card_count(&self) -> usize {
         self.player_1_hand.len() + self.player_1_capture.len()
     }
@@ -33,156 +207,668 @@ impl WarGame {
     fn player_2_card_count(&self) -> usize {
         self.player_2_hand.len() + self.player_2_capture.len()
     }
+
+    fn emit(&mut self, event: WarEvent) {
+        debug!("{event:?}");
+        self.events.push(event);
+    }
+
+    fn hand(&mut self, player: usize) -> &mut P {
+        if player == 0 {
+            &mut self.player_1_hand
+        } else {
+            &mut self.player_2_hand
+        }
+    }
+
+    /// Shuffles a player's captured cards into their hand, if their hand is empty
+    fn refill(&mut self, player: usize) {
+        let (hand, capture) = if player == 0 {
+            (&mut self.player_1_hand, &mut self.player_1_capture)
+        } else {
+            (&mut self.player_2_hand, &mut self.player_2_capture)
+        };
+        if !hand.is_empty() || capture.is_empty() {
+            return;
+        }
+        let record = if self.audit {
+            let source = format!("player {} reshuffle", player + 1);
+            Some(RandomnessRecord::shuffle(&source, capture, &mut self.rng))
+        } else {
+            capture.shuffle(&mut self.rng);
+            None
+        };
+        for card in capture.deal_all_cards(1).unwrap().remove(0) {
+            hand.push_top(card);
+        }
+        let cards = hand.len();
+        if let Some(record) = record {
+            self.emit(WarEvent::Randomness(record));
+        }
+        self.emit(WarEvent::Reshuffle { player, cards });
+    }
+
+    /// Plays a card from a player's hand, shuffling in their captured cards if needed
+    fn play_card(&mut self, player: usize, face_up: bool) -> Option<Card> {
+        self.refill(player);
+        let card = self.hand(player).pop_top()?;
+        self.emit(WarEvent::CardPlayed {
+            player,
+            card,
+            face_up,
+        });
+        Some(card)
+    }
+
+    /// Plays the final card of a war, without shuffling in captured cards
+    fn play_final_card(&mut self, player: usize) -> Option<Card> {
+        let card = self.hand(player).pop_top()?;
+        self.emit(WarEvent::CardPlayed {
+            player,
+            card,
+            face_up: true,
+        });
+        Some(card)
+    }
+
+    fn capture(&mut self, player: usize, cards: Vec<Card>) {
+        if player == 0 {
+            self.player_1_capture.extend(cards.iter().copied());
+        } else {
+            self.player_2_capture.extend(cards.iter().copied());
+        }
+        self.emit(WarEvent::PileCaptured { player, cards });
+    }
+
+    /// Plays out tiebreaking rounds until one player wins the war,
+    /// adding all cards played to the reward pile
+    fn play_war(&mut self, reward_cards: &mut Vec<Card>) -> Ordering {
+        let mut ordering = Ordering::Equal;
+        while ordering == Ordering::Equal {
+            let mut player_1_check = None;
+            let mut player_2_check = None;
+            for _ in 0..3 {
+                let player_1_down_card = self.play_card(0, false);
+                let player_2_down_card = self.play_card(1, false);
+                if let Some(card) = player_1_down_card {
+                    reward_cards.push(card);
+                    player_1_check = player_1_down_card;
+                }
+                if let Some(card) = player_2_down_card {
+                    reward_cards.push(card);
+                    player_2_check = player_2_down_card;
+                }
+            }
+            let player_1_final = self.play_final_card(0);
+            let player_2_final = self.play_final_card(1);
+            if let Some(card) = player_1_final {
+                reward_cards.push(card);
+            }
+            if let Some(card) = player_2_final {
+                reward_cards.push(card);
+            }
+
+            player_1_check = player_1_final.or(player_1_check);
+            player_2_check = player_2_final.or(player_2_check);
+
+            ordering = match (player_1_check, player_2_check) {
+                (Some(card_1), Some(card_2)) => Suitless(card_1).cmp(&Suitless(card_2)),
+                (Some(_), None) => Ordering::Greater,
+                (None, Some(_)) => Ordering::Less,
+                (None, None) => {
+                    unreachable!("Unable to tiebreak");
+                }
+            }
+        }
+        ordering
+    }
 }
 
-impl Default for WarGame {
-    fn default() -> WarGame {
-        let mut players = vec![Hand::default(), Hand::default()];
+/// What a player can see of a game of War
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WarView {
+    /// The number of cards left in this player's hand
+    pub hand: usize,
+    /// The number of cards this player has captured, not yet shuffled back into their hand
+    pub captured: usize,
+    /// The total number of cards held by the opponent
+    pub opponent: usize,
+}
+
+impl<P: CardPile + Clone + Default> SeededGame for War<P> {
+    /// Creates a game of war, shuffling and reshuffling with a generator seeded from the given one
+    /// # Examples
+    /// ```
+    /// # use naipe::games::war::WarGame;
+    /// # use naipe::games::{Game, SeededGame};
+    /// let mut game_1 = WarGame::from_seed(42);
+    /// let mut game_2 = WarGame::from_seed(42);
+    /// for _ in 0..100 {
+    ///     assert_eq!(game_1.tick(), game_2.tick());
+    /// }
+    /// assert_eq!(game_1, game_2);
+    /// ```
+    fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> War<P> {
+        let mut rng = GameRng::from_seed(rng.gen());
+        let ([player_1_hand, player_2_hand], _) = deal(&mut rng, false, None);
+        War {
+            player_1_hand: pile_of(&player_1_hand),
+            player_2_hand: pile_of(&player_2_hand),
+            player_1_capture: Deck::new_empty(),
+            player_2_capture: Deck::new_empty(),
+            rng,
+            events: vec![],
+            audit: false,
+            lucky_deal: None,
+            ticked: false,
+        }
+    }
+}
+
+impl<P: CardPile + Clone + Default> AuditedGame for War<P> {
+    /// Deals a game of War as [`WarGameBuilder::audit`] does
+    fn audited_with_rng<R: Rng + ?Sized>(rng: &mut R) -> War<P> {
+        War::builder()
+            .audit()
+            .build_with_rng(rng)
+            .expect("A shuffled deal is always valid")
+    }
+}
+
+/// Builds a pile from a hand, keeping the last card of the hand on top
+fn pile_of<P: CardPile + Default>(hand: &Hand) -> P {
+    let mut pile = P::default();
+    for card in hand {
+        pile.push_top(*card);
+    }
+    pile
+}
+
+/// Empties a pile, giving its cards from the bottom up
+fn take_cards<P: CardPile>(pile: &mut P) -> Vec<Card> {
+    let mut cards: Vec<Card> = iter::from_fn(|| pile.pop_top()).collect();
+    cards.reverse();
+    cards
+}
+
+/// Shuffles a standard deck, and deals it out evenly between both players, recording each
+/// shuffle if audited
+///
+/// For a lucky deal, the deck is reshuffled until the favoured player is dealt the promised aces,
+/// or until [`LUCKY_SHUFFLE_LIMIT`] shuffles have been tried
+fn deal(
+    rng: &mut GameRng,
+    audit: bool,
+    lucky: Option<(usize, usize)>,
+) -> ([Hand; 2], Vec<WarEvent>) {
+    let mut events = vec![];
+    let mut shuffles = 0;
+    loop {
         let mut deck = Deck::default();
-        deck.shuffle_with_default_rng();
+        if audit {
+            let record = RandomnessRecord::shuffle("deal", &mut deck, rng);
+            events.push(WarEvent::Randomness(record));
+        } else {
+            deck.shuffle(rng);
+        }
+        shuffles += 1;
+        let mut players = vec![Hand::default(), Hand::default()];
         deck.deal_all_cards_to_hands(&mut players).unwrap();
-        WarGame {
-            player_1_hand: players[0].clone(),
-            player_2_hand: players[1].clone(),
+        let player_2_hand = players.pop().unwrap();
+        let player_1_hand = players.pop().unwrap();
+        let hands = [player_1_hand, player_2_hand];
+        let Some((player, aces)) = lucky else {
+            return (hands, events);
+        };
+        let dealt = hands[player]
+            .iter()
+            .filter(|card| card.get_rank() == Rank::Ace)
+            .count();
+        if dealt >= aces || shuffles >= LUCKY_SHUFFLE_LIMIT {
+            events.push(WarEvent::LuckyDeal(LuckyDeal {
+                player,
+                aces,
+                shuffles,
+                kept: dealt >= aces,
+            }));
+            return (hands, events);
+        }
+    }
+}
+
+/// Sets up a game of War from an uneven or pre-set position, for teaching or handicap play
+///
+/// By default, this deals a shuffled deck evenly, the same as [`SeededGame::new_with_rng`]
+/// # Examples
+/// ```
+/// # use naipe::games::war::WarGame;
+/// # use naipe::games::agent::AgentGame;
+/// use naipe::common::hand::Hand;
+/// // Player 2 is spotted ten of player 1's cards
+/// let game = WarGame::builder().spot(1, 10).build_from_seed(4).unwrap();
+/// assert_eq!(game.view(0).hand, 16);
+/// assert_eq!(game.view(1).hand, 36);
+///
+/// let game = WarGame::builder()
+///     .hands(
+///         Hand::from_notation("2S 3S AH").unwrap(),
+///         Hand::from_notation("KD").unwrap(),
+///     )
+///     .build_from_seed(4)
+///     .unwrap();
+/// assert_eq!(game.view(1).opponent, 3);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WarGameBuilder<P = Vec<Card>> {
+    hands: Option<[Hand; 2]>,
+    spot: Option<(usize, usize)>,
+    audit: bool,
+    lucky: Option<(usize, usize)>,
+    pile: PhantomData<P>,
+}
+
+impl<P> Default for WarGameBuilder<P> {
+    fn default() -> WarGameBuilder<P> {
+        WarGameBuilder {
+            hands: None,
+            spot: None,
+            audit: false,
+            lucky: None,
+            pile: PhantomData,
+        }
+    }
+}
+
+impl<P: CardPile + Clone + Default> WarGameBuilder<P> {
+    /// Starts each player with the given hand, instead of dealing a shuffled deck
+    ///
+    /// The last card of each hand is played first
+    #[must_use]
+    pub fn hands(mut self, player_1: Hand, player_2: Hand) -> WarGameBuilder<P> {
+        self.hands = Some([player_1, player_2]);
+        self
+    }
+
+    /// Moves cards from the top of the opponent's starting hand to the top of the player's,
+    /// with player 1 as player 0 and player 2 as player 1
+    #[must_use]
+    pub fn spot(mut self, player: usize, cards: usize) -> WarGameBuilder<P> {
+        self.spot = Some((player, cards));
+        self
+    }
+
+    /// Records the deal and every reshuffle as [`WarEvent::Randomness`] events, with the deal
+    /// given by [`EventSource::events`] before the first tick
+    /// # Examples
+    /// ```
+    /// # use naipe::games::event::EventSource;
+    /// # use naipe::games::war::{WarEvent, WarGame};
+    /// let game = WarGame::builder().audit().build_from_seed(2).unwrap();
+    /// let WarEvent::Randomness(record) = &game.events()[0] else {
+    ///     panic!("the deal is recorded");
+    /// };
+    /// assert_eq!(record.source, "deal");
+    /// assert!(record.verify());
+    ///
+    /// // The first tick delivers the deal's record to a sink
+    /// use naipe::games::event::tick_into;
+    /// let mut game = WarGame::builder().audit().build_from_seed(2).unwrap();
+    /// let mut received = vec![];
+    /// for _ in 0..5 {
+    ///     tick_into(&mut game, &mut received).unwrap();
+    /// }
+    /// let deals = received.iter().filter(|event| {
+    ///     matches!(event, WarEvent::Randomness(record) if record.source == "deal")
+    /// });
+    /// assert_eq!(deals.count(), 1);
+    /// ```
+    #[must_use]
+    pub fn audit(mut self) -> WarGameBuilder<P> {
+        self.audit = true;
+        self
+    }
+
+    /// Biases the deal in a player's favour, reshuffling until they are dealt at least the given
+    /// number of aces, for a friendlier game against a beginner
+    ///
+    /// The bias is reported by a [`WarEvent::LuckyDeal`] event before the first tick, and kept
+    /// as [`WarGame::lucky_deal`]. Hands given by [`WarGameBuilder::hands`] are never biased
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card::Rank;
+    /// # use naipe::games::event::EventSource;
+    /// # use naipe::games::war::{WarEvent, WarGame};
+    /// let game = WarGame::builder().lucky(0, 3).build_from_seed(9).unwrap();
+    /// let lucky = game.lucky_deal().unwrap();
+    /// assert!(lucky.kept);
+    /// assert_eq!(game.events(), &[WarEvent::LuckyDeal(*lucky)]);
+    /// let hand = game.cards(0).unwrap();
+    /// assert!(hand.iter().filter(|card| card.get_rank() == Rank::Ace).count() >= 3);
+    ///
+    /// // Watchers subscribed to the game's events learn of the bias on the first tick
+    /// use naipe::games::event::tick_into;
+    /// let mut game = WarGame::builder().lucky(0, 3).build_from_seed(9).unwrap();
+    /// let mut received = vec![];
+    /// tick_into(&mut game, &mut received).unwrap();
+    /// assert_eq!(received[0], WarEvent::LuckyDeal(*game.lucky_deal().unwrap()));
+    /// tick_into(&mut game, &mut received).unwrap();
+    /// let reported = received.iter().filter(|event| matches!(event, WarEvent::LuckyDeal(_)));
+    /// assert_eq!(reported.count(), 1);
+    /// ```
+    #[must_use]
+    pub fn lucky(mut self, player: usize, aces: usize) -> WarGameBuilder<P> {
+        self.lucky = Some((player, aces));
+        self
+    }
+
+    /// Builds the game, shuffling and reshuffling with a generator seeded from the given one
+    /// # Errors
+    /// [`WarSetupError::UnknownPlayer`] if a spotted or favoured player is not in the game,
+    /// [`WarSetupError::SpotTooLarge`] if the opponent would be left with no cards, or
+    /// [`WarSetupError::EmptyHand`] if a player would start with no cards
+    pub fn build_with_rng<R: Rng + ?Sized>(self, rng: &mut R) -> Result<War<P>, WarSetupError> {
+        let mut rng = GameRng::from_seed(rng.gen());
+        if let Some((player, _)) = self.lucky.filter(|(player, _)| *player > 1) {
+            return Err(WarSetupError::UnknownPlayer(player));
+        }
+        let (mut hands, events) = match self.hands {
+            Some(hands) => (hands, vec![]),
+            None => deal(&mut rng, self.audit, self.lucky),
+        };
+        let lucky_deal = events.iter().find_map(|event| match event {
+            WarEvent::LuckyDeal(lucky) => Some(*lucky),
+            _ => None,
+        });
+        if let Some(player) = hands.iter().position(Hand::is_empty) {
+            return Err(WarSetupError::EmptyHand(player));
+        }
+        if let Some((player, cards)) = self.spot {
+            if player > 1 {
+                return Err(WarSetupError::UnknownPlayer(player));
+            }
+            let opponent = 1 - player;
+            if cards >= hands[opponent].len() {
+                return Err(WarSetupError::SpotTooLarge(cards));
+            }
+            let spotted: Vec<Card> = (0..cards).filter_map(|_| hands[opponent].pop()).collect();
+            hands[player].extend(spotted);
+        }
+        let [player_1_hand, player_2_hand] = hands;
+        Ok(War {
+            player_1_hand: pile_of(&player_1_hand),
+            player_2_hand: pile_of(&player_2_hand),
             player_1_capture: Deck::new_empty(),
             player_2_capture: Deck::new_empty(),
+            rng,
+            events,
+            audit: self.audit,
+            lucky_deal,
+            ticked: false,
+        })
+    }
+
+    /// Builds the game from a fixed seed, which plays out identically every time
+    /// # Errors
+    /// As for [`WarGameBuilder::build_with_rng`]
+    pub fn build_from_seed(self, seed: u64) -> Result<War<P>, WarSetupError> {
+        self.build_with_rng(&mut GameRng::seed_from_u64(seed))
+    }
+}
+
+/// Errors related to setting up a game of War
+#[derive(Copy, Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WarSetupError {
+    #[error("No player {0} in a game of War")]
+    UnknownPlayer(usize),
+    #[error("Cannot spot {0} cards without leaving the opponent empty handed")]
+    SpotTooLarge(usize),
+    #[error("Player {0} would start with no cards")]
+    EmptyHand(usize),
+}
+
+impl<P> GameInfo for War<P> {
+    fn info() -> GameMetadata {
+        GameMetadata {
+            name: "War",
+            summary: "Two players flip cards, and the higher card captures both",
+            min_players: 2,
+            max_players: 2,
+            decks: 1,
+            minutes: 20,
+            tags: &[GameTag::Comparing, GameTag::Luck, GameTag::Children],
         }
     }
 }
 
-impl Game for WarGame {
+impl<P: CardPile + Clone + Default> Default for War<P> {
+    /// Creates a game of war seeded from [`rand::thread_rng`]
+    fn default() -> War<P> {
+        War::new_with_rng(&mut rand::thread_rng())
+    }
+}
+
+impl<P: CardPile + Clone + Default> Game for War<P> {
     type TickOk = bool;
     type TickError = ();
 
     /// Advances the game of war
     fn tick(&mut self) -> Result<bool, ()> {
+        // The deal's events stay pending until the first tick delivers them
+        if self.ticked {
+            self.events.clear();
+        }
+        self.ticked = true;
         if self.player_1_won() || self.player_2_won() {
-            debug!(
-                "Finished! (Player 1 Count: {}, Player 2 Count: {})",
-                self.player_1_card_count(),
-                self.player_2_card_count()
-            );
+            let winner = usize::from(self.player_2_won());
+            self.emit(WarEvent::GameOver { winner });
             return Ok(true);
         }
 
-        if self.player_1_hand.is_empty() {
-            self.player_1_capture.shuffle_with_default_rng();
-            self.player_1_hand
-                .extend(self.player_1_capture.deal_all_cards(1).unwrap()[0].clone());
-            debug!(
-                "Reshuffling player 1! Current card count: {} (Player 2 count: {})",
-                self.player_1_card_count(),
-                self.player_2_card_count()
-            );
-        }
-
-        if self.player_2_hand.is_empty() {
-            self.player_2_capture.shuffle_with_default_rng();
-            self.player_2_hand
-                .extend(self.player_2_capture.deal_all_cards(1).unwrap()[0].clone());
-            debug!(
-                "Reshuffling player 2!, Current card Count: {} (Player 1 count: {})",
-                self.player_2_card_count(),
-                self.player_1_card_count()
-            );
-        }
-
-        let player_1_play = Suitless(self.player_1_hand.pop().unwrap());
-        let player_2_play = Suitless(self.player_2_hand.pop().unwrap());
-        debug!(
-            "Player 1: {}, Player 2: {}",
-            player_1_play.0, player_2_play.0
-        );
-
-        match player_1_play.cmp(&player_2_play) {
+        let player_1_play = Suitless(self.play_card(0, true).unwrap());
+        let player_2_play = Suitless(self.play_card(1, true).unwrap());
+
+        let mut reward_cards = vec![player_1_play.unwrap(), player_2_play.unwrap()];
+        let ordering = match player_1_play.cmp(&player_2_play) {
             Ordering::Equal => {
-                debug!("{} == {}", player_1_play, player_2_play);
-                let mut reward_cards = vec![player_1_play.unwrap(), player_2_play.unwrap()];
-                let mut ordering = Ordering::Equal;
-                while ordering == Ordering::Equal {
-                    debug!("Tiebreaking round");
-                    let mut player_1_check = None;
-                    let mut player_2_check = None;
-                    for _ in 0..3 {
-                        let player_1_down_card = self.player_1_hand.pop().or_else(|| {
-                            self.player_1_capture.shuffle_with_default_rng();
-                            if self.player_1_capture.is_empty() {
-                                None
-                            } else {
-                                self.player_1_hand.extend(
-                                    self.player_1_capture.deal_all_cards(1).unwrap()[0].clone(),
-                                );
-                                self.player_1_hand.pop()
-                            }
-                        });
-                        let player_2_down_card = self.player_2_hand.pop().or_else(|| {
-                            self.player_2_capture.shuffle_with_default_rng();
-                            if self.player_2_capture.is_empty() {
-                                None
-                            } else {
-                                self.player_2_hand.extend(
-                                    self.player_2_capture.deal_all_cards(1).unwrap()[0].clone(),
-                                );
-                                self.player_2_hand.pop()
-                            }
-                        });
-                        if let Some(card) = player_1_down_card {
-                            reward_cards.push(card);
-                            player_1_check = player_1_down_card;
-                        }
-                        if let Some(card) = player_2_down_card {
-                            reward_cards.push(card);
-                            player_2_check = player_2_down_card;
-                        }
-                    }
-                    let player_1_final = self.player_1_hand.pop();
-                    let player_2_final = self.player_2_hand.pop();
-                    if let Some(card) = player_1_final {
-                        reward_cards.push(card);
-                    }
-                    if let Some(card) = player_2_final {
-                        reward_cards.push(card);
-                    }
-
-                    player_1_check = player_1_final.or(player_1_check);
-                    player_2_check = player_2_final.or(player_2_check);
-
-                    debug!("Tiebreakers: {:?} vs {:?}", player_1_check, player_2_check);
-
-                    ordering = match (player_1_check, player_2_check) {
-                        (Some(card_1), Some(card_2)) => Suitless(card_1).cmp(&Suitless(card_2)),
-                        (Some(_), None) => Ordering::Greater,
-                        (None, Some(_)) => Ordering::Less,
-                        (None, None) => {
-                            unreachable!("Unable to tiebreak");
-                        }
-                    }
-                }
+                self.emit(WarEvent::WarStarted {
+                    rank: player_1_play.get_rank(),
+                });
+                self.play_war(&mut reward_cards)
+            }
+            ordering => ordering,
+        };
+        if ordering == Ordering::Less {
+            self.capture(1, reward_cards);
+        } else {
+            self.capture(0, reward_cards);
+        }
 
-                if ordering == Ordering::Less {
-                    self.player_2_capture.extend(reward_cards);
-                } else {
-                    self.player_1_capture.extend(reward_cards);
-                }
+        Ok(false)
+    }
+}
+
+impl<P: CardPile + Clone + Default> AgentGame for War<P> {
+    type View = WarView;
+    /// War has no decisions, so agents only signal that they are ready to flip their card
+    type Action = ();
+    type TickOk = bool;
+    type TickError = ();
+
+    fn seat_count(&self) -> usize {
+        2
+    }
+
+    /// Gets the view for player 1 in seat 0, or player 2 in seat 1
+    fn view(&self, seat: usize) -> WarView {
+        if seat == 0 {
+            WarView {
+                hand: self.player_1_hand.len(),
+                captured: self.player_1_capture.len(),
+                opponent: self.player_2_card_count(),
             }
-            Ordering::Less => {
-                debug!("{} < {}", player_1_play, player_2_play);
-                self.player_2_capture.add(player_1_play.unwrap());
-                self.player_2_capture.add(player_2_play.unwrap());
+        } else {
+            WarView {
+                hand: self.player_2_hand.len(),
+                captured: self.player_2_capture.len(),
+                opponent: self.player_1_card_count(),
             }
-            Ordering::Greater => {
-                debug!("{} > {}", player_1_play, player_2_play);
-                self.player_1_capture.add(player_1_play.unwrap());
-                self.player_1_capture.add(player_2_play.unwrap());
+        }
+    }
+
+    /// Asks both agents to flip, then plays a round of War
+    /// # Examples
+    /// ```
+    /// # use naipe::games::agent::AgentGame;
+    /// # use naipe::games::war::{WarGame, WarView};
+    /// let mut game = WarGame::default();
+    /// let mut flips = 0;
+    /// let mut player_1 = |_: &WarView| flips += 1;
+    /// let mut player_2 = |_: &WarView| ();
+    /// game.tick_with_agents(&mut [&mut player_1, &mut player_2]).unwrap();
+    /// assert_eq!(flips, 1);
+    /// ```
+    fn tick_with_agents(&mut self, agents: &mut [&mut dyn Agent<WarView, ()>]) -> Result<bool, ()> {
+        if agents.len() != self.seat_count() {
+            return Err(());
+        }
+        if !(self.player_1_won() || self.player_2_won()) {
+            for (seat, agent) in agents.iter_mut().enumerate() {
+                agent.choose_action(&self.view(seat));
             }
         }
+        self.tick()
+    }
+}
 
-        debug!(
-            "Player 1 Count: {}, Player 2 Count: {}",
-            self.player_1_card_count(),
-            self.player_2_card_count()
-        );
+impl<P: CardPile + Clone + Default + 'static> ErasedGame for War<P> {
+    /// Writes the view as `hand=<cards> captured=<cards> opponent=<cards>`
+    fn encode_view(view: &WarView) -> String {
+        format!(
+            "hand={} captured={} opponent={}",
+            view.hand, view.captured, view.opponent
+        )
+    }
 
-        Ok(false)
+    /// Reads any text as the signal to flip
+    fn decode_action(_action: &str) -> Option<()> {
+        Some(())
+    }
+
+    fn fallback_action(_view: &WarView) {}
+
+    fn outcome(&self) -> Option<String> {
+        self.winner()
+            .map(|winner| format!("Player {} won", winner + 1))
+    }
+}
+
+impl<P: CardPile + Clone + Default> Concede for War<P> {
+    /// Concedes the game of war, handing all of the conceding player's cards to their opponent
+    ///
+    /// Player 1 sits in seat 0, and player 2 in seat 1
+    /// # Examples
+    /// ```
+    /// # use naipe::games::war::WarGame;
+    /// # use naipe::games::{Concede, Game};
+    /// let mut game = WarGame::default();
+    /// game.concede(1).unwrap();
+    /// assert!(game.player_1_won());
+    /// assert!(game.tick().unwrap());
+    /// assert!(game.concede(0).is_err());
+    /// ```
+    fn concede(&mut self, seat: usize) -> Result<(), ConcedeError> {
+        if self.player_1_won() || self.player_2_won() {
+            return Err(ConcedeError::GameOver);
+        }
+        match seat {
+            0 => {
+                self.player_2_capture
+                    .extend(take_cards(&mut self.player_1_hand));
+                self.player_2_capture
+                    .extend(take_cards(&mut self.player_1_capture));
+            }
+            1 => {
+                self.player_1_capture
+                    .extend(take_cards(&mut self.player_2_hand));
+                self.player_1_capture
+                    .extend(take_cards(&mut self.player_2_capture));
+            }
+            _ => return Err(ConcedeError::UnknownSeat(seat)),
+        }
+        Ok(())
+    }
+}
+
+impl<P: CardPile + Clone + Default> GameOutcome for War<P> {
+    /// Gets the winner, with player 1 in seat 0 and player 2 in seat 1
+    fn winner(&self) -> Option<usize> {
+        if self.player_1_won() {
+            Some(0)
+        } else if self.player_2_won() {
+            Some(1)
+        } else {
+            None
+        }
+    }
+}
+
+impl Revealing for WarEvent {
+    /// Cards played face up are gone from their player's hand, and captured cards are held by
+    /// the capturing player, whoever is watching
+    /// # Examples
+    /// ```
+    /// # use naipe::common::card_set::CardSet;
+    /// # use naipe::common::inference::SeenCards;
+    /// # use naipe::games::event::EventSource;
+    /// # use naipe::games::war::WarGame;
+    /// # use naipe::games::{Game, SeededGame};
+    /// let mut game = WarGame::from_seed(3);
+    /// let mut seen = SeenCards::new(0, 2, CardSet::full());
+    /// for _ in 0..20 {
+    ///     game.tick().unwrap();
+    ///     game.events().iter().for_each(|event| seen.observe(event));
+    /// }
+    /// assert!(seen.held(0).len() + seen.held(1).len() >= 20);
+    /// ```
+    fn reveals(&self, _observer: usize) -> Vec<Reveal> {
+        match self {
+            WarEvent::CardPlayed {
+                card,
+                face_up: true,
+                ..
+            } => vec![Reveal::Gone(*card)],
+            WarEvent::PileCaptured { player, cards } => cards
+                .iter()
+                .map(|card| Reveal::Held {
+                    seat: *player,
+                    card: *card,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+impl<P> EventSource for War<P> {
+    type Event = WarEvent;
+
+    /// Gets the events from the most recent tick
+    ///
+    /// Events from setting up the game, such as the audited deal or a lucky deal, are given before
+    /// the first tick, and again at the start of the first tick's events, so they reach
+    /// [`tick_into`](crate::games::event::tick_into) and recordings
+    /// # Examples
+    /// ```
+    /// # use naipe::games::event::EventSource;
+    /// # use naipe::games::war::{WarEvent, WarGame};
+    /// # use naipe::games::{Game, SeededGame};
+    /// let mut game = WarGame::from_seed(3);
+    /// game.tick().unwrap();
+    /// assert!(matches!(game.events()[0], WarEvent::CardPlayed { player: 0, .. }));
+    /// assert!(matches!(game.events().last(), Some(WarEvent::PileCaptured { .. })));
+    /// ```
+    fn events(&self) -> &[WarEvent] {
+        &self.events
     }
 }
@@ -6,8 +6,43 @@
 
 //! A crate implementing basic card game concepts, along with some games
 //!
-//! Common structs are in the [`crate::common`] module
+//! Common structs are in the [`crate::common`] module, and statistics for simulations
+//! are in the [`crate::stats`] module
+//!
+//! Games score with integers alone, so replays never depend on floating point. Statistics and
+//! rates are floating point, but the difficulty scores that choose deals are always computed as
+//! exact fractions, whichever features are enabled. The `exact-math` feature exposes those
+//! fractions, and exact win rates, through `common::ratio`
+//!
+//! Which optional parts were compiled in can be found at runtime with [`features`], and every
+//! module's errors convert into the crate's [`Error`]
+//!
+//! So games and rules can be added without breaking callers, every public enum but `Rank` and
+//! `Suit` is `#[non_exhaustive]`, and traits only the crate implements, such as
+//! [`common::pile::CardPile`], are sealed. The public API is checked against a snapshot in
+//! `tests/api`
+//!
+//! For small targets where even a random number generator is unwanted, the minimal profile of
+//! `default-features = false, features = ["minimal"]` leaves out the games, the shoe, and the
+//! evaluation cache. What remains is the card, deck, and hand model with the poker evaluator,
+//! orderings, inference, and statistics, with no `rand` dependency and no collections beyond
+//! those in `alloc`. Decks are then shuffled by permutations the caller supplies, with
+//! `Deck::permute`. The crate still links `std`, as its errors implement `std::error::Error`
 
+pub mod build;
+pub mod clock;
 pub mod common;
-#[cfg(any(feature = "war"))]
+pub mod error;
+#[cfg(any(
+    feature = "blackjack",
+    feature = "hearts",
+    feature = "memory",
+    feature = "solitaire",
+    feature = "war"
+))]
 pub mod games;
+mod sealed;
+pub mod stats;
+
+pub use build::features;
+pub use error::{Error, Result};
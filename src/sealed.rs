@@ -0,0 +1,7 @@
+//! Sealing for traits that only the crate implements
+//!
+//! A public trait with [`Sealed`] as a supertrait cannot be implemented outside the crate, so
+//! methods can be added to it without breaking anyone
+
+/// A supertrait that cannot be named outside the crate
+pub trait Sealed {}
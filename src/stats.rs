@@ -0,0 +1,345 @@
+//! Bounded-memory statistics for large simulations
+//!
+//! Each aggregator takes observations one at a time and uses a fixed amount of memory,
+//! no matter how many games are played
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+/// Count, mean, variance, and range of a stream of values
+///
+/// Uses Welford's algorithm, which stays accurate over very long streams
+/// # Examples
+/// ```
+/// # use naipe::stats::RunningStats;
+/// let mut stats = RunningStats::new();
+/// for value in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+///     stats.add(value);
+/// }
+/// assert_eq!(stats.count(), 8);
+/// assert_eq!(stats.mean(), Some(5.0));
+/// assert_eq!(stats.population_variance(), Some(4.0));
+/// assert_eq!(stats.min(), Some(2.0));
+/// assert_eq!(stats.max(), Some(9.0));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunningStats {
+    count: u64,
+    mean: f64,
+    squared_deviations: f64,
+    min: f64,
+    max: f64,
+}
+
+impl RunningStats {
+    /// Creates a new aggregator with no observations
+    pub fn new() -> RunningStats {
+        RunningStats::default()
+    }
+
+    /// Adds an observation
+    #[allow(clippy::cast_precision_loss)]
+    pub fn add(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squared_deviations += delta * (value - self.mean);
+    }
+
+    /// Adds the same observation a number of times
+    /// # Examples
+    /// ```
+    /// # use naipe::stats::RunningStats;
+    /// let mut stats = RunningStats::new();
+    /// stats.add_repeated(3.0, 4);
+    /// stats.add_repeated(7.0, 0);
+    /// assert_eq!(stats.count(), 4);
+    /// assert_eq!(stats.mean(), Some(3.0));
+    /// assert_eq!(stats.population_variance(), Some(0.0));
+    /// ```
+    pub fn add_repeated(&mut self, value: f64, count: u64) {
+        self.merge(&RunningStats {
+            count,
+            mean: value,
+            squared_deviations: 0.0,
+            min: value,
+            max: value,
+        });
+    }
+
+    /// Combines the observations of another aggregator into this one
+    ///
+    /// Useful for merging results gathered on separate threads
+    #[allow(clippy::cast_precision_loss)]
+    pub fn merge(&mut self, other: &RunningStats) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.squared_deviations += other.squared_deviations
+            + delta * delta * (self.count as f64 * other.count as f64) / count as f64;
+        self.mean += delta * other.count as f64 / count as f64;
+        self.count = count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// Gets the number of observations
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Gets the mean of the observations, if there are any
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Gets the variance of the observations as a whole population, if there are any
+    #[allow(clippy::cast_precision_loss)]
+    pub fn population_variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.squared_deviations / self.count as f64)
+    }
+
+    /// Gets the variance of the observations as a sample, if there are at least two
+    #[allow(clippy::cast_precision_loss)]
+    pub fn sample_variance(&self) -> Option<f64> {
+        (self.count > 1).then(|| self.squared_deviations / (self.count - 1) as f64)
+    }
+
+    /// Gets the smallest observation, if there are any
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Gets the largest observation, if there are any
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+}
+
+/// An estimate of a single quantile of a stream, using the P² algorithm
+///
+/// Keeps only five markers, adjusting them as observations arrive
+/// # Examples
+/// ```
+/// # use naipe::stats::P2Quantile;
+/// let mut median = P2Quantile::new(0.5);
+/// for value in 1..=10_000 {
+///     median.add(f64::from(value));
+/// }
+/// let estimate = median.estimate().unwrap();
+/// assert!((estimate - 5_000.0).abs() < 50.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct P2Quantile {
+    quantile: f64,
+    count: u64,
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+
+impl P2Quantile {
+    /// Creates an estimator for the given quantile, clamped between 0 and 1
+    pub fn new(quantile: f64) -> P2Quantile {
+        let quantile = quantile.clamp(0.0, 1.0);
+        P2Quantile {
+            quantile,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [
+                1.0,
+                1.0 + 2.0 * quantile,
+                1.0 + 4.0 * quantile,
+                3.0 + 2.0 * quantile,
+                5.0,
+            ],
+            increments: [
+                0.0,
+                quantile / 2.0,
+                quantile,
+                f64::midpoint(1.0, quantile),
+                1.0,
+            ],
+        }
+    }
+
+    /// Gets the quantile being estimated
+    pub fn quantile(&self) -> f64 {
+        self.quantile
+    }
+
+    /// Gets the number of observations
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Adds an observation
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn add(&mut self, value: f64) {
+        if self.count < 5 {
+            self.heights[self.count as usize] = value;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        let cell = if value < self.heights[0] {
+            self.heights[0] = value;
+            0
+        } else if value >= self.heights[4] {
+            self.heights[4] = value;
+            3
+        } else {
+            (0..4)
+                .find(|index| value < self.heights[index + 1])
+                .unwrap_or(3)
+        };
+        for position in &mut self.positions[cell + 1..] {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        for index in 1..4 {
+            let offset = self.desired[index] - self.positions[index];
+            let room_above = self.positions[index + 1] - self.positions[index];
+            let room_below = self.positions[index - 1] - self.positions[index];
+            if (offset >= 1.0 && room_above > 1.0) || (offset <= -1.0 && room_below < -1.0) {
+                let step = offset.signum();
+                let parabolic = self.parabolic(index, step);
+                self.heights[index] =
+                    if self.heights[index - 1] < parabolic && parabolic < self.heights[index + 1] {
+                        parabolic
+                    } else {
+                        self.linear(index, step)
+                    };
+                self.positions[index] += step;
+            }
+        }
+    }
+
+    fn parabolic(&self, index: usize, step: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[index]
+            + step / (n[index + 1] - n[index - 1])
+                * ((n[index] - n[index - 1] + step) * (q[index + 1] - q[index])
+                    / (n[index + 1] - n[index])
+                    + (n[index + 1] - n[index] - step) * (q[index] - q[index - 1])
+                        / (n[index] - n[index - 1]))
+    }
+
+    fn linear(&self, index: usize, step: f64) -> f64 {
+        let neighbour = if step > 0.0 { index + 1 } else { index - 1 };
+        self.heights[index]
+            + step * (self.heights[neighbour] - self.heights[index])
+                / (self.positions[neighbour] - self.positions[index])
+    }
+
+    /// Gets the current estimate, if there are any observations
+    ///
+    /// With fewer than five observations, the nearest observed value is used
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn estimate(&self) -> Option<f64> {
+        match self.count {
+            0 => None,
+            1..=4 => {
+                let mut observed = self.heights[..self.count as usize].to_vec();
+                observed.sort_by(f64::total_cmp);
+                let index = (self.quantile * (observed.len() - 1) as f64).round() as usize;
+                Some(observed[index])
+            }
+            _ => Some(self.heights[2]),
+        }
+    }
+}
+
+/// A uniform random sample of a fixed number of items from a stream
+///
+/// Useful for keeping a handful of interesting hands or games out of billions played
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reservoir<T> {
+    capacity: usize,
+    seen: u64,
+    samples: Vec<T>,
+}
+
+impl<T> Reservoir<T> {
+    /// Creates a reservoir keeping at most `capacity` samples
+    pub fn new(capacity: usize) -> Reservoir<T> {
+        Reservoir {
+            capacity,
+            seen: 0,
+            samples: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Offers an item to the sample, keeping it with the right probability
+    /// # Examples
+    /// ```
+    /// # use naipe::stats::Reservoir;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// let mut rng = StdRng::seed_from_u64(5);
+    /// let mut reservoir = Reservoir::new(10);
+    /// for game in 0..1_000 {
+    ///     reservoir.offer(game, &mut rng);
+    /// }
+    /// assert_eq!(reservoir.seen(), 1_000);
+    /// assert_eq!(reservoir.samples().len(), 10);
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn offer<R: Rng + ?Sized>(&mut self, item: T, rng: &mut R) {
+        self.seen += 1;
+        if self.samples.len() < self.capacity {
+            self.samples.push(item);
+            return;
+        }
+        let slot = rng.gen_range(0..self.seen);
+        if let Some(sample) = usize::try_from(slot)
+            .ok()
+            .and_then(|slot| self.samples.get_mut(slot))
+        {
+            *sample = item;
+        }
+    }
+
+    /// Gets the number of items offered
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    /// Gets the items currently sampled, in no particular order
+    pub fn samples(&self) -> &[T] {
+        &self.samples
+    }
+
+    /// Consumes the reservoir, returning the items sampled
+    pub fn into_samples(self) -> Vec<T> {
+        self.samples
+    }
+}
@@ -0,0 +1,278 @@
+//! Checks the crate's public API against the snapshot in `tests/api/public-api.txt`
+//!
+//! Every public item is listed with its path and signature, along with enum variants, trait
+//! members, and whether each enum is `#[non_exhaustive]`. Items are read from the source, so the
+//! snapshot is the same whichever features are enabled. A difference fails the test, listing the
+//! removed and changed items, which break semver, apart from the items merely added. Set
+//! `NAIPE_RECORD_API` to write the current API to the snapshot, then commit it with the change.
+
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RECORD_VAR: &str = "NAIPE_RECORD_API";
+
+fn snapshot_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/api/public-api.txt")
+}
+
+/// Gets every source file of the library, in path order
+fn source_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "bin") {
+                files.extend(source_files(&path));
+            }
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Gets the module path of a source file, such as `naipe::common::card`
+fn module_path(src: &Path, file: &Path) -> String {
+    let relative = file.strip_prefix(src).unwrap().with_extension("");
+    let mut path = vec!["naipe".to_string()];
+    path.extend(
+        relative
+            .iter()
+            .map(|part| part.to_string_lossy().to_string())
+            .filter(|part| part != "lib" && part != "mod"),
+    );
+    path.join("::")
+}
+
+/// Removes string and character literals and comments, which may hold unbalanced braces
+fn code_of(line: &str) -> String {
+    let mut code = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+                code.push_str("\"\"");
+            }
+            '\'' => {
+                // A character literal, rather than a lifetime, closes within three characters
+                let rest: String = chars.clone().take(3).collect();
+                if let Some(end) = rest.find('\'').filter(|end| *end > 0) {
+                    for _ in 0..=end {
+                        chars.next();
+                    }
+                    code.push_str("' '");
+                } else {
+                    code.push(c);
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => break,
+            _ => code.push(c),
+        }
+    }
+    code
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Cuts a signature before its body, or the end of its declaration
+fn signature(text: &str) -> String {
+    let text = collapse(text);
+    let end = text
+        .find(" {")
+        .or_else(|| text.find('{'))
+        .unwrap_or(text.len());
+    text[..end].trim_end_matches([';', ',']).trim().to_string()
+}
+
+/// A block the parser is inside, with the depth of braces at which it opened
+struct Scope {
+    name: String,
+    kind: &'static str,
+    depth: usize,
+}
+
+/// Lists the public API declared in a source file
+fn public_items(module: &str, source: &str) -> Vec<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut items = vec![];
+    let mut scopes: Vec<Scope> = vec![];
+    let mut depth = 0;
+    let mut attributes = String::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index].trim();
+        index += 1;
+        if line.starts_with("//") || line.is_empty() {
+            continue;
+        }
+        if line.starts_with("#[") || line.starts_with("#![") {
+            // Attributes may span lines, until their brackets balance
+            let mut attribute = code_of(line);
+            while attribute.matches('[').count() > attribute.matches(']').count() {
+                attribute.push_str(&code_of(lines[index]));
+                index += 1;
+            }
+            attributes.push_str(&attribute);
+            continue;
+        }
+
+        // Gather a declaration spanning lines, until its body opens or it ends
+        let mut text = code_of(line);
+        let declaration = text.starts_with("pub ")
+            || text.starts_with("impl")
+            || text.starts_with("mod ")
+            || text.starts_with("fn ")
+            || text.starts_with("type ")
+            || text.starts_with("const ");
+        if declaration {
+            // Parameters listed one per line end in commas, but the signature goes on
+            let open = |text: &str| text.matches('(').count() > text.matches(')').count();
+            while !text.contains('{') && (open(&text) || !text.trim_end().ends_with([';', ','])) {
+                let Some(next) = lines.get(index) else {
+                    break;
+                };
+                text.push(' ');
+                text.push_str(&code_of(next));
+                index += 1;
+            }
+        }
+        let non_exhaustive = attributes.contains("non_exhaustive");
+        attributes.clear();
+
+        let hidden = scopes.iter().any(|scope| scope.kind == "private");
+        let scope = scopes.last().filter(|scope| scope.depth + 1 == depth);
+        let path = std::iter::once(module.to_string())
+            .chain(scopes.iter().map(|scope| scope.name.clone()))
+            .collect::<Vec<String>>()
+            .join("::");
+        let public = text.starts_with("pub ");
+        match scope.map(|scope| scope.kind) {
+            _ if hidden => {}
+            Some("enum") => {
+                if let Some(variant) = text
+                    .split(|c: char| !c.is_alphanumeric() && c != '_')
+                    .next()
+                    .filter(|word| word.starts_with(char::is_uppercase))
+                {
+                    items.push(format!("{path}::{variant}"));
+                }
+            }
+            Some("trait") if declaration => items.push(format!("{path}: {}", signature(&text))),
+            _ if public => {
+                let marker = if non_exhaustive && text.starts_with("pub enum") {
+                    "#[non_exhaustive] "
+                } else {
+                    ""
+                };
+                items.push(format!("{path}: {marker}{}", signature(&text)));
+            }
+            _ => {}
+        }
+
+        let opened = text.matches('{').count();
+        let closed = text.matches('}').count();
+        if opened > closed {
+            let words: Vec<&str> = text.split_whitespace().collect();
+            let named = |keyword: &str| {
+                words
+                    .iter()
+                    .position(|word| *word == keyword)
+                    .and_then(|position| words.get(position + 1))
+                    .map(|name| {
+                        name.split(|c: char| !c.is_alphanumeric() && c != '_')
+                            .next()
+                            .unwrap_or_default()
+                            .to_string()
+                    })
+            };
+            let (kind, name) = if text.starts_with("impl") {
+                ("impl", format!("<{}>", signature(&text)))
+            } else if public && words.contains(&"enum") {
+                ("enum", named("enum").unwrap_or_default())
+            } else if public && words.contains(&"trait") {
+                ("trait", named("trait").unwrap_or_default())
+            } else if public && words.contains(&"struct") {
+                ("struct", named("struct").unwrap_or_default())
+            } else if public && words.contains(&"mod") {
+                ("mod", named("mod").unwrap_or_default())
+            } else {
+                // Bodies of functions, and anything private, declare no public API
+                ("private", String::new())
+            };
+            scopes.push(Scope { name, kind, depth });
+            depth += opened - closed;
+        } else {
+            depth -= closed - opened;
+            while scopes.last().is_some_and(|scope| scope.depth >= depth) {
+                scopes.pop();
+            }
+        }
+    }
+    assert_eq!(depth, 0, "Unbalanced braces reading {module}");
+    items
+}
+
+fn current_api() -> Vec<String> {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    source_files(&src)
+        .iter()
+        .flat_map(|file| public_items(&module_path(&src, file), &fs::read_to_string(file).unwrap()))
+        .collect()
+}
+
+#[test]
+fn public_api_matches_snapshot() {
+    let current = current_api();
+    if env::var_os(RECORD_VAR).is_some() {
+        fs::create_dir_all(snapshot_path().parent().unwrap()).unwrap();
+        fs::write(snapshot_path(), current.join("\n") + "\n").unwrap();
+    }
+    let snapshot = fs::read_to_string(snapshot_path()).unwrap();
+    let recorded: HashSet<&str> = snapshot.lines().collect();
+    let present: HashSet<&str> = current.iter().map(String::as_str).collect();
+    let removed: Vec<&str> = snapshot
+        .lines()
+        .filter(|item| !present.contains(item))
+        .collect();
+    let added: Vec<&str> = current
+        .iter()
+        .map(String::as_str)
+        .filter(|item| !recorded.contains(item))
+        .collect();
+    assert!(
+        removed.is_empty() && added.is_empty(),
+        "The public API differs from the snapshot. Set {RECORD_VAR} to record it.\n\
+         Removed or changed, which breaks semver:\n  {}\nAdded:\n  {}",
+        removed.join("\n  "),
+        added.join("\n  "),
+    );
+}
+
+#[test]
+fn public_enums_are_non_exhaustive() {
+    // Ranks and suits are fixed by the cards themselves, so only they may be matched exhaustively
+    let exhaustive: Vec<String> = current_api()
+        .into_iter()
+        .filter(|item| item.contains(": pub enum "))
+        .filter(|item| !item.ends_with("pub enum Rank") && !item.ends_with("pub enum Suit"))
+        .collect();
+    assert!(
+        exhaustive.is_empty(),
+        "Public enums must be #[non_exhaustive]:\n  {}",
+        exhaustive.join("\n  ")
+    );
+}
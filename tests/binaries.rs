@@ -0,0 +1,97 @@
+#![cfg(all(feature = "cli", any(feature = "blackjack", feature = "war")))]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{self, Command, Output, Stdio};
+
+fn save_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("naipe-{name}-{}.save", process::id()))
+}
+
+fn run(binary: &str, args: &[&str]) -> String {
+    let Output { status, stdout, .. } = Command::new(binary)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(status.success());
+    String::from_utf8(stdout).unwrap()
+}
+
+#[cfg(feature = "blackjack")]
+#[test]
+fn blackjack_script() {
+    let output = run(
+        env!("CARGO_BIN_EXE_blackjack"),
+        &["--script", "tests/scripts/blackjack.txt"],
+    );
+    assert!(output.contains("> s"));
+    assert!(output.contains("running total"));
+}
+
+#[cfg(feature = "blackjack")]
+#[test]
+fn blackjack_hot_seat() {
+    let output = run(
+        env!("CARGO_BIN_EXE_blackjack"),
+        &[
+            "--players",
+            "2",
+            "--script",
+            "tests/scripts/blackjack_hot_seat.txt",
+        ],
+    );
+    assert!(output.contains("Player 1: "));
+    assert!(output.contains("Player 2: "));
+}
+
+#[cfg(feature = "blackjack")]
+#[test]
+fn blackjack_resume() {
+    let path = save_path("blackjack");
+    fs::write(&path, "10\n-20\n0").unwrap();
+    let output = run(
+        env!("CARGO_BIN_EXE_blackjack"),
+        &[
+            "--script",
+            "tests/scripts/empty.txt",
+            "--resume",
+            path.to_str().unwrap(),
+        ],
+    );
+    assert!(output.contains("Player 3 finished with"));
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+    fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "war")]
+#[test]
+fn war_script() {
+    let output = run(
+        env!("CARGO_BIN_EXE_war"),
+        &["--script", "tests/scripts/war.txt"],
+    );
+    assert!(output.contains("Press enter to flip"));
+    assert!(output.contains("Player 1 holds"));
+}
+
+#[cfg(feature = "war")]
+#[test]
+fn war_save_and_resume() {
+    let path = save_path("war");
+    let path_arg = path.to_str().unwrap();
+    run(
+        env!("CARGO_BIN_EXE_war"),
+        &["--script", "tests/scripts/war.txt", "--save", path_arg],
+    );
+    let saved = fs::read_to_string(&path).unwrap();
+    assert_eq!(saved.lines().count(), 2);
+
+    let output = run(
+        env!("CARGO_BIN_EXE_war"),
+        &["--script", "tests/scripts/war.txt", "--resume", path_arg],
+    );
+    assert!(output.contains("Saved to"));
+    fs::remove_file(&path).unwrap();
+}
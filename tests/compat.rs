@@ -0,0 +1,82 @@
+//! Replays games recorded by earlier versions of the crate, failing on any divergence
+//!
+//! Recordings live in `tests/compat/<version>/`, one JSON list of replays per game. Set
+//! `NAIPE_RECORD_COMPAT` to record the current version's games before checking, then commit the
+//! new recordings alongside the release. Recordings are never overwritten, so a version's games
+//! are only recorded once, by the release that introduced it.
+//!
+//! Games could not be seeded before replays were added, so the 0.1.0 recordings were made by the
+//! first commit able to record them (`0b73bbc`, "Add typed game events and seeded replays"), and
+//! pin War's play from then on.
+#![cfg(all(feature = "serde", feature = "war"))]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use naipe::games::event::{EventSource, Replay};
+use naipe::games::war::WarGame;
+use naipe::games::{Game, SeededGame};
+
+const RECORD_VAR: &str = "NAIPE_RECORD_COMPAT";
+const GAMES_PER_VERSION: usize = 4;
+const MAX_TICKS: usize = 100_000;
+
+fn compat_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/compat")
+}
+
+fn record<G>(file: &str)
+where
+    G: SeededGame + Game<TickOk = bool> + EventSource,
+    G::Event: Clone + PartialEq + serde::Serialize,
+{
+    let replays: Vec<Replay<G::Event>> = (0..)
+        .filter_map(|seed| Replay::record::<G>(seed, MAX_TICKS).ok())
+        .take(GAMES_PER_VERSION)
+        .collect();
+    let dir = compat_dir().join(env!("CARGO_PKG_VERSION"));
+    let path = dir.join(file);
+    assert!(
+        !path.exists(),
+        "{} is already recorded, and earlier recordings are never replaced",
+        path.display()
+    );
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(path, serde_json::to_string(&replays).unwrap()).unwrap();
+}
+
+/// Verifies every recording of the game from every version, describing each divergence
+fn divergences<G>(file: &str) -> Vec<String>
+where
+    G: SeededGame + Game<TickOk = bool> + EventSource,
+    G::Event: Clone + PartialEq + serde::de::DeserializeOwned,
+{
+    let mut recordings: Vec<PathBuf> = fs::read_dir(compat_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path().join(file))
+        .filter(|path| path.exists())
+        .collect();
+    recordings.sort();
+    assert!(!recordings.is_empty(), "No recordings of {file}");
+    recordings
+        .iter()
+        .flat_map(|path| {
+            let replays: Vec<Replay<G::Event>> =
+                serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+            replays.into_iter().filter_map(move |replay| {
+                let err = replay.verify::<G>().err()?;
+                Some(format!("{} seed {}: {err}", path.display(), replay.seed))
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn war_replays_match_earlier_versions() {
+    if env::var_os(RECORD_VAR).is_some() {
+        record::<WarGame>("war.json");
+    }
+    let divergences = divergences::<WarGame>("war.json");
+    assert!(divergences.is_empty(), "{}", divergences.join("\n"));
+}
@@ -0,0 +1,65 @@
+//! Exercises the card, deck, and hand model as built without random number generators
+//!
+//! Every feature is optional here, so these run in the minimal profile too. Check it with
+//! `cargo test --no-default-features --features minimal --test minimal`
+
+use naipe::common::card::{Card, Rank, Suit};
+use naipe::common::deck::{Deck, DeckPermutationError, ShuffleLog};
+use naipe::common::hand::Hand;
+
+/// A fixed shuffle, standing in for one from a hardware random source
+fn reversal(len: usize) -> Vec<usize> {
+    (0..len).rev().collect()
+}
+
+#[test]
+fn caller_supplied_shuffle_deals_hands() {
+    let mut deck = Deck::default();
+    deck.permute(&reversal(deck.len())).unwrap();
+    let mut hands = vec![Hand::new(); 4];
+    deck.deal_all_cards_to_hands(&mut hands).unwrap();
+    assert!(deck.is_empty());
+    assert!(hands.iter().all(|hand| hand.len() == 13));
+    assert_eq!(
+        hands.iter().map(Hand::len).sum::<usize>(),
+        Card::all_cards().len()
+    );
+}
+
+#[test]
+fn shuffle_log_needs_no_hashing() {
+    let mut log = ShuffleLog::new();
+    let mut deck = Deck::default();
+    assert!(log.record(&deck));
+    deck.permute(&reversal(deck.len())).unwrap();
+    assert!(log.record(&deck));
+    assert!(!log.record(&deck.clone()));
+    assert_eq!(log.duplicates(), 1);
+}
+
+#[test]
+fn caller_supplied_shuffle_is_checked() {
+    let mut deck = Deck::from_notation("AS KH").unwrap();
+    assert_eq!(
+        deck.permute(&[1, 1]),
+        Err(DeckPermutationError::InvalidPosition(1))
+    );
+    assert_eq!(deck, Deck::from_notation("AS KH").unwrap());
+    deck.permute(&[1, 0]).unwrap();
+    assert_eq!(deck.draw(), Some(Card::new(Suit::Heart, Rank::King)));
+}
+
+#[test]
+fn features_report_the_build() {
+    let features = naipe::features();
+    assert_eq!(
+        features.contains(naipe::build::Feature::Rand),
+        cfg!(feature = "rand")
+    );
+    assert_eq!(
+        features.contains(naipe::build::Feature::Std),
+        cfg!(feature = "std")
+    );
+    // Every game shuffles and keeps hashed collections, so no game is built without both
+    assert!(cfg!(all(feature = "rand", feature = "std")) || features.games().is_empty());
+}
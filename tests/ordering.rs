@@ -0,0 +1,96 @@
+//! Pins the derived orderings on cards, which games and frontends rely on when sorting hands
+//!
+//! Changing any of these orders is a breaking change, so a failure here should come with a major
+//! version bump rather than an updated expectation.
+
+use naipe::common::card::{Card, Rank, Suit};
+use naipe::common::order::CardOrder;
+
+fn cards(notation: &str) -> Vec<Card> {
+    notation
+        .split_whitespace()
+        .map(|card| card.parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn ranks_run_from_two_to_joker() {
+    let mut ranks = Rank::all_ranks();
+    ranks.push(Rank::Joker);
+    ranks.reverse();
+    ranks.sort();
+    assert_eq!(
+        ranks,
+        vec![
+            Rank::Two,
+            Rank::Three,
+            Rank::Four,
+            Rank::Five,
+            Rank::Six,
+            Rank::Seven,
+            Rank::Eight,
+            Rank::Nine,
+            Rank::Ten,
+            Rank::Jack,
+            Rank::Queen,
+            Rank::King,
+            Rank::Ace,
+            Rank::Joker,
+        ]
+    );
+}
+
+#[test]
+fn suits_run_from_spades_to_diamonds() {
+    let mut suits = Suit::all_suits();
+    suits.reverse();
+    suits.sort();
+    assert_eq!(
+        suits,
+        vec![Suit::Spade, Suit::Club, Suit::Heart, Suit::Diamond]
+    );
+}
+
+#[test]
+fn cards_sort_by_suit_then_rank() {
+    let mut deck = Card::all_cards();
+    deck.extend(
+        Suit::all_suits()
+            .into_iter()
+            .map(|suit| Card::new(suit, Rank::Joker)),
+    );
+    deck.reverse();
+    deck.sort();
+    let mut expected = Vec::new();
+    for suit in ["S", "C", "H", "D"] {
+        let notation: Vec<String> = [
+            "2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K", "A",
+        ]
+        .iter()
+        .map(|rank| format!("{rank}{suit}"))
+        .collect();
+        expected.extend(cards(&notation.join(" ")));
+        expected.push(Card::new(suit.parse().unwrap(), Rank::Joker));
+    }
+    assert_eq!(deck, expected);
+}
+
+#[test]
+fn standard_order_matches_derived_order() {
+    let standard = CardOrder::standard();
+    let mut deck = Card::all_cards();
+    deck.extend(
+        Suit::all_suits()
+            .into_iter()
+            .map(|suit| Card::new(suit, Rank::Joker)),
+    );
+    for card in &deck {
+        for other in &deck {
+            assert_eq!(
+                standard.compare(*card, *other),
+                card.cmp(other),
+                "{card} {other}"
+            );
+        }
+    }
+}
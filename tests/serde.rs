@@ -0,0 +1,382 @@
+#![cfg(feature = "serde")]
+
+use std::num::NonZeroU16;
+
+use naipe::common::card::{Card, Rank, Suit};
+use naipe::common::card_set::CardSet;
+use naipe::common::deck::Deck;
+use naipe::common::hand::Hand;
+
+fn round_trip<T>(value: &T) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let json = serde_json::to_string(value).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn rank_round_trip() {
+    for rank in Rank::all_ranks() {
+        assert_eq!(round_trip(&rank), rank);
+    }
+}
+
+#[test]
+fn suit_round_trip() {
+    for suit in Suit::all_suits() {
+        assert_eq!(round_trip(&suit), suit);
+    }
+}
+
+#[test]
+fn card_round_trip() {
+    for card in Card::all_cards() {
+        assert_eq!(round_trip(&card), card);
+    }
+    assert_eq!(round_trip(&Card::red_joker()), Card::red_joker());
+}
+
+#[test]
+fn card_set_round_trip() {
+    let mut set: CardSet = Card::all_cards().into_iter().step_by(3).collect();
+    set.insert(Card::black_joker());
+    assert_eq!(round_trip(&set), set);
+}
+
+#[test]
+fn deck_round_trip_keeps_order() {
+    let mut deck = Deck::new(NonZeroU16::new(2).unwrap());
+    // Deal every 37th card, which visits all 104 in a scrambled order
+    let order: Vec<usize> = (0..deck.len()).map(|at| at * 37 % deck.len()).collect();
+    deck.permute(&order).unwrap();
+    assert_eq!(round_trip(&deck), deck);
+    assert_eq!(round_trip(&Deck::new_empty()), Deck::new_empty());
+}
+
+#[test]
+fn hand_round_trip_keeps_order() {
+    let hand: Hand = [
+        Card::new(Suit::Heart, Rank::Queen),
+        Card::new(Suit::Club, Rank::Two),
+        Card::new(Suit::Heart, Rank::Queen),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(round_trip(&hand), hand);
+}
+
+#[test]
+fn sorted_hand_loads_sorted() {
+    use naipe::common::hand::SortedHand;
+    use naipe::common::order::CardOrder;
+
+    let mut hand = SortedHand::new(CardOrder::default());
+    hand.extend(Hand::from_notation("KH 2S AS 9H").unwrap());
+    assert_eq!(round_trip(&hand), hand);
+
+    // Cards saved out of order, such as by hand, are sorted as the hand loads
+    let mut json = serde_json::to_value(&hand).unwrap();
+    json["cards"].as_array_mut().unwrap().reverse();
+    let restored: SortedHand = serde_json::from_value(json).unwrap();
+    assert_eq!(restored, hand);
+}
+
+#[test]
+fn render_metadata_round_trip() {
+    use naipe::common::theme::{CardBack, DeckTheme, RenderMetadata, SuitColor};
+
+    let metadata = RenderMetadata {
+        theme: DeckTheme::default()
+            .four_color(true)
+            .suit_color_override(Suit::Spade, SuitColor::Blue)
+            .back(CardBack::new("casino-green")),
+        ..RenderMetadata::default()
+    };
+    let json = serde_json::to_value(&metadata).unwrap();
+    // Backs are stored as their plain identifiers
+    assert_eq!(json["theme"]["back"], "casino-green");
+    assert_eq!(round_trip(&metadata), metadata);
+}
+
+#[cfg(feature = "war")]
+#[test]
+fn war_game_round_trip_mid_game() {
+    use naipe::games::event::EventSource;
+    use naipe::games::war::WarGame;
+    use naipe::games::{Game, SeededGame};
+
+    let mut game = WarGame::from_seed(1251);
+    for _ in 0..30 {
+        if game.tick().unwrap() {
+            break;
+        }
+    }
+    let mut restored = round_trip(&game);
+    assert_eq!(restored.cards(0), game.cards(0));
+    assert_eq!(restored.cards(1), game.cards(1));
+    // Events from the last tick are not saved
+    assert!(restored.events().is_empty());
+
+    // The generator is part of the state, so the restored game plays out identically
+    restored.tick().unwrap();
+    game.tick().unwrap();
+    assert_eq!(restored, game);
+    for _ in 0..500 {
+        assert_eq!(restored.tick(), game.tick());
+    }
+    assert_eq!(restored, game);
+}
+
+#[cfg(feature = "war")]
+#[test]
+fn war_game_saved_without_generator_loads() {
+    use naipe::games::war::WarGame;
+    use naipe::games::Game;
+
+    // The form of a save from before the generator was part of the state
+    let json = r#"{
+        "player_1_hand": [{"suit": "Spade", "rank": "Two"}, {"suit": "Spade", "rank": "Ace"}],
+        "player_2_hand": [{"suit": "Heart", "rank": "King"}],
+        "player_1_capture": [],
+        "player_2_capture": [{"suit": "Club", "rank": "Three"}]
+    }"#;
+    let mut game: WarGame = serde_json::from_str(json).unwrap();
+    let mut again: WarGame = serde_json::from_str(json).unwrap();
+    assert_eq!(game.cards(0).unwrap().len(), 2);
+    // Old saves reshuffle from a fixed seed, so they still play out the same every time
+    loop {
+        let finished = game.tick().unwrap();
+        assert_eq!(again.tick(), Ok(finished));
+        if finished {
+            break;
+        }
+    }
+    assert_eq!(game, again);
+}
+
+#[cfg(any(
+    feature = "blackjack",
+    feature = "hearts",
+    feature = "memory",
+    feature = "solitaire",
+    feature = "war"
+))]
+#[test]
+fn tournament_round_trip_mid_run() {
+    use naipe::games::tournament::Tournament;
+
+    let entrants = (1..=5).map(|entrant| format!("bot {entrant}"));
+    let mut tournament = Tournament::round_robin(entrants).unwrap();
+    for _ in 0..4 {
+        let pairing = tournament.next_match().unwrap();
+        tournament.record(Some(pairing.entrants[0])).unwrap();
+    }
+    let mut restored = round_trip(&tournament);
+    assert_eq!(restored, tournament);
+    assert_eq!(restored.standings(), tournament.standings());
+
+    // The restored tournament carries on with the same schedule
+    while let Some(pairing) = tournament.next_match() {
+        assert_eq!(restored.next_match(), Some(pairing));
+        tournament.record(None).unwrap();
+        restored.record(None).unwrap();
+    }
+    assert!(restored.is_over());
+    assert_eq!(restored.results().len(), 10);
+}
+
+#[cfg(any(
+    feature = "blackjack",
+    feature = "hearts",
+    feature = "memory",
+    feature = "solitaire",
+    feature = "war"
+))]
+#[test]
+fn swiss_tournament_round_trip_between_rounds() {
+    use std::num::NonZeroUsize;
+
+    use naipe::games::tournament::Tournament;
+
+    let entrants = (1..=7).map(|entrant| format!("bot {entrant}"));
+    let rounds = NonZeroUsize::new(3).unwrap();
+    let mut tournament = Tournament::swiss(entrants, rounds).unwrap();
+    for _ in 0..3 {
+        let pairing = tournament.next_match().unwrap();
+        tournament.record(Some(pairing.entrants[1])).unwrap();
+    }
+    assert_eq!(tournament.current_round(), Some(1));
+    let mut restored = round_trip(&tournament);
+    assert_eq!(restored, tournament);
+
+    // Later rounds are paired the same way from the restored standings
+    while let Some(pairing) = tournament.next_match() {
+        assert_eq!(restored.next_match(), Some(pairing));
+        tournament.record(Some(pairing.entrants[0])).unwrap();
+        restored.record(Some(pairing.entrants[0])).unwrap();
+    }
+    assert!(restored.is_over());
+    assert_eq!(restored.byes(), tournament.byes());
+    assert_eq!(restored.byes().len(), 3);
+    assert_eq!(restored.standings(), tournament.standings());
+}
+
+#[cfg(any(
+    feature = "blackjack",
+    feature = "hearts",
+    feature = "memory",
+    feature = "solitaire",
+    feature = "war"
+))]
+#[test]
+fn tournament_with_unknown_entrant_is_refused() {
+    use naipe::games::tournament::Tournament;
+
+    let mut tournament = Tournament::round_robin(["a", "b", "c"].map(String::from)).unwrap();
+    let pairing = tournament.next_match().unwrap();
+    tournament.record(Some(pairing.entrants[0])).unwrap();
+    let mut saved = serde_json::to_value(&tournament).unwrap();
+    assert_eq!(
+        serde_json::from_value::<Tournament>(saved.clone()).unwrap(),
+        tournament
+    );
+
+    // A bye for an entrant who never entered
+    let mut bad = saved.clone();
+    bad["byes"] = serde_json::json!([{"round": 0, "entrant": 7}]);
+    assert!(serde_json::from_value::<Tournament>(bad).is_err());
+
+    // A match scheduled against an entrant who never entered
+    let mut bad = saved.clone();
+    bad["schedule"][1]["entrants"][1] = serde_json::json!(9);
+    assert!(serde_json::from_value::<Tournament>(bad).is_err());
+
+    // A result for a match other than the one scheduled
+    saved["results"][0]["pairing"]["round"] = serde_json::json!(2);
+    assert!(serde_json::from_value::<Tournament>(saved).is_err());
+}
+
+#[cfg(any(
+    feature = "blackjack",
+    feature = "hearts",
+    feature = "memory",
+    feature = "solitaire",
+    feature = "war"
+))]
+#[test]
+fn preflop_chart_round_trip() {
+    use naipe::games::preflop::{Position, PreflopChart};
+
+    let chart = PreflopChart::parse("[SB]\n55+, A2s+, KJo+: raise 70 call 30").unwrap();
+    let restored = round_trip(&chart);
+    assert_eq!(restored, chart);
+    assert_eq!(
+        restored
+            .get(Position::SmallBlind, "A5s".parse().unwrap())
+            .call,
+        30
+    );
+}
+
+#[cfg(feature = "war")]
+#[test]
+fn replay_id_round_trip() {
+    use naipe::games::event::Replay;
+    use naipe::games::id::ReplayId;
+    use naipe::games::war::{WarEvent, WarGame};
+
+    let replay: Replay<WarEvent> = Replay::record::<WarGame>(6, 100_000)
+        .unwrap()
+        .with_id(ReplayId::new("finals"));
+    let json = serde_json::to_value(&replay).unwrap();
+    // IDs are stored as plain strings
+    assert_eq!(json["id"], "finals");
+    assert_eq!(round_trip(&replay), replay);
+}
+
+#[cfg(feature = "memory")]
+#[test]
+fn memory_game_round_trip_mid_turn() {
+    use naipe::games::memory::{MemoryCell, MemoryGame};
+
+    let mut game = MemoryGame::builder().grid(4, 4).build_from_seed(8).unwrap();
+    game.flip(5).unwrap();
+    let mut restored = round_trip(&game);
+    assert_eq!(restored, game);
+    assert!(matches!(restored.cell(1, 1), Some(MemoryCell::FaceUp(_))));
+    assert_eq!(restored.flip(6), game.flip(6));
+}
+
+#[cfg(feature = "hearts")]
+#[test]
+fn hearts_game_round_trip_keeps_voids() {
+    use naipe::games::agent::AgentGame;
+    use naipe::games::event::EventSource;
+    use naipe::games::hearts::{play_lowest, HeartsGame};
+    use naipe::games::SeededGame;
+
+    let mut game = HeartsGame::from_seed(1262);
+    let (mut north, mut east, mut south, mut west) =
+        (play_lowest, play_lowest, play_lowest, play_lowest);
+    while (0..4).all(|seat| game.voids().voids(seat).is_empty()) {
+        game.tick_with_agents(&mut [&mut north, &mut east, &mut south, &mut west])
+            .unwrap();
+    }
+    let mut restored = round_trip(&game);
+    assert_eq!(restored.voids(), game.voids());
+    // Events from the last tick are not saved
+    assert!(restored.events().is_empty());
+
+    for _ in 0..100 {
+        assert_eq!(
+            restored.tick_with_agents(&mut [&mut north, &mut east, &mut south, &mut west]),
+            game.tick_with_agents(&mut [&mut north, &mut east, &mut south, &mut west])
+        );
+    }
+    assert_eq!(restored, game);
+}
+
+#[cfg(feature = "solitaire")]
+#[test]
+fn scenario_round_trip_plays_the_same() {
+    use naipe::games::scenario::{Scenario, Step};
+    use naipe::games::solitaire::accordion::{AccordionGame, AccordionMove};
+
+    let scenario = Scenario {
+        title: "Two squeezes".to_string(),
+        start: AccordionGame::deal(&Deck::from_notation("2S 3S 4S").unwrap()),
+        steps: vec![
+            Step::captioned(AccordionMove { from: 1, onto: 0 }, "Suits match"),
+            Step::new(AccordionMove { from: 1, onto: 0 }),
+        ],
+    };
+    let restored = round_trip(&scenario);
+    assert_eq!(restored, scenario);
+    assert_eq!(restored.play().unwrap(), scenario.play().unwrap());
+}
+
+#[cfg(any(
+    feature = "blackjack",
+    feature = "hearts",
+    feature = "memory",
+    feature = "solitaire",
+    feature = "war"
+))]
+#[test]
+fn series_without_seats_is_refused() {
+    use std::num::NonZeroUsize;
+
+    use naipe::games::series::Series;
+
+    let mut series = Series::new(NonZeroUsize::new(3).unwrap(), NonZeroUsize::new(5).unwrap());
+    series.record(Some(2)).unwrap();
+    let restored = round_trip(&series);
+    assert_eq!(restored, series);
+    assert_eq!(restored.dealer(), 1);
+
+    let json = r#"{"best_of": 3, "first_dealer": 0, "wins": [], "results": []}"#;
+    assert!(serde_json::from_str::<Series>(json).is_err());
+    let json = r#"{"best_of": 3, "first_dealer": 0, "wins": [0, 1], "results": [5]}"#;
+    assert!(serde_json::from_str::<Series>(json).is_err());
+}